@@ -25,7 +25,7 @@ use async_trait::async_trait;
 use thiserror::Error;
 use tracing::error;
 
-use crate::{ActorContext, QueueCapacity, SendError};
+use crate::{ActorContext, Envelope, QueueCapacity, SendError};
 
 /// The actor exit status represents the outcome of the execution of an actor,
 /// after the end of the execution.
@@ -136,6 +136,32 @@ pub trait Actor: Send + Sized + 'static {
         true
     }
 
+    /// Maximum number of messages processed in a row by an actor that does not yield after each
+    /// message, see [`Actor::yield_after_each_message`]. Past that number, the actor cooperatively
+    /// yields so that it does not starve the other tasks of its runtime while it works through a
+    /// large backlog. Each message of a batch counts.
+    fn max_messages_between_yields(&self) -> usize {
+        100
+    }
+
+    /// Maximum number of pending messages handed over to [`Actor::process_batch`] at once.
+    ///
+    /// If set to a value greater than 1, the actor loop drains up to that many messages from
+    /// the mailbox and processes them with [`Actor::process_batch`] instead of one at a time.
+    fn max_batch_size(&self) -> usize {
+        1
+    }
+
+    /// If set to true, the messages still pending in the mailbox when the actor quits
+    /// (`Command::Quit`) are processed before the actor finalizes. Killing the actor never
+    /// processes pending messages.
+    ///
+    /// Messages keep being processed for as long as they are available, so this should only be
+    /// enabled for actors whose upstream actors stop sending messages upon shutdown.
+    fn drain_mailbox_on_quit(&self) -> bool {
+        false
+    }
+
     /// The Actor's incoming mailbox queue capacity. It is set when the actor is spawned.
     fn queue_capacity(&self) -> QueueCapacity {
         QueueCapacity::Unbounded
@@ -175,6 +201,24 @@ pub trait Actor: Send + Sized + 'static {
         Ok(())
     }
 
+    /// Processes a batch of messages drained from the mailbox.
+    ///
+    /// This hook is only called when [`Actor::max_batch_size`] is greater than 1. The batch
+    /// contains at least one envelope, in the order they were received. A command, if any, is
+    /// always the last envelope of the batch.
+    ///
+    /// The default implementation simply handles the messages one after the other.
+    async fn process_batch(
+        &mut self,
+        batch: Vec<Envelope<Self>>,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        for mut envelope in batch {
+            envelope.handle_message(self, ctx).await?;
+        }
+        Ok(())
+    }
+
     /// Hook  that can be set up to define what should happen upon actor exit.
     /// This hook is called only once.
     ///
@@ -308,6 +308,11 @@ impl<T> Receiver<T> {
         }
     }
 
+    /// Returns the number of pending low priority messages.
+    pub fn num_pending_low_priority_messages(&self) -> usize {
+        self.low_priority_rx.len() + usize::from(self.pending_low_priority_message.is_some())
+    }
+
     /// Drain all of the pending low priority messages and return them.
     pub fn drain_low_priority(&self) -> Vec<T> {
         let mut messages = Vec::new();
@@ -392,6 +397,20 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_num_pending_low_priority_messages() -> anyhow::Result<()> {
+        let (sender, receiver) = super::channel::<usize>(QueueCapacity::Unbounded);
+        assert_eq!(receiver.num_pending_low_priority_messages(), 0);
+        sender.send_low_priority(1).await?;
+        sender.send_low_priority(2).await?;
+        sender.send_high_priority(3)?;
+        assert_eq!(receiver.num_pending_low_priority_messages(), 2);
+        assert_eq!(receiver.try_recv(), Ok(3));
+        assert_eq!(receiver.try_recv(), Ok(1));
+        assert_eq!(receiver.num_pending_low_priority_messages(), 1);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_try_recv_high_priority() -> anyhow::Result<()> {
         let (sender, receiver) = super::channel::<usize>(QueueCapacity::Unbounded);
@@ -17,13 +17,14 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use std::any::Any;
+use std::any::{Any, TypeId};
 use std::fmt;
 
 use async_trait::async_trait;
 use tokio::sync::oneshot;
 
 use crate::actor::DeferableReplyHandler;
+use crate::metrics::QueuedMessageGuard;
 use crate::scheduler::NoAdvanceTimeGuard;
 use crate::{Actor, ActorContext, ActorExitStatus};
 
@@ -38,6 +39,7 @@ use crate::{Actor, ActorContext, ActorExitStatus};
 pub struct Envelope<A> {
     handler_envelope: Box<dyn EnvelopeT<A>>,
     _no_advance_time_guard: Option<NoAdvanceTimeGuard>,
+    queued_message_guard_opt: Option<QueuedMessageGuard>,
 }
 
 impl<A: Actor> Envelope<A> {
@@ -48,6 +50,13 @@ impl<A: Actor> Envelope<A> {
         self.handler_envelope.message()
     }
 
+    /// Returns true if the envelope holds a message of type `M`.
+    ///
+    /// Contrary to [`Envelope::message_typed`], this method does not consume the message.
+    pub fn is_message_of_type<M: 'static>(&self) -> bool {
+        self.handler_envelope.message_type_id() == TypeId::of::<M>()
+    }
+
     pub fn message_typed<M: 'static>(&mut self) -> Option<M> {
         if let Ok(boxed_msg) = self.handler_envelope.message().downcast::<M>() {
             Some(*boxed_msg)
@@ -56,6 +65,33 @@ impl<A: Actor> Envelope<A> {
         }
     }
 
+    /// Takes the message out of the envelope, together with the channel on which its reply is
+    /// expected, if the envelope holds a message of type `M`.
+    ///
+    /// The caller is then in charge of sending the reply. If the channel is dropped instead, the
+    /// sender of the message gets an error when awaiting the reply.
+    pub fn message_and_reply_tx_typed<M: 'static>(
+        &mut self,
+    ) -> Option<(M, oneshot::Sender<<A as DeferableReplyHandler<M>>::Reply>)>
+    where A: DeferableReplyHandler<M> {
+        if !self.is_message_of_type::<M>() {
+            return None;
+        }
+        let boxed_msg_and_reply_tx = self
+            .handler_envelope
+            .message_and_reply_tx()
+            .downcast::<(M, oneshot::Sender<<A as DeferableReplyHandler<M>>::Reply>)>()
+            .ok()?;
+        Some(*boxed_msg_and_reply_tx)
+    }
+
+    /// Updates the mailbox metrics of the actor once the envelope is out of its queue.
+    pub(crate) fn record_dequeue(&mut self) {
+        if let Some(queued_message_guard) = self.queued_message_guard_opt.take() {
+            queued_message_guard.dequeue();
+        }
+    }
+
     /// Execute the captured handle function.
     pub async fn handle_message(
         &mut self,
@@ -78,11 +114,16 @@ impl<A: Actor> fmt::Debug for Envelope<A> {
 trait EnvelopeT<A: Actor>: Send {
     fn debug_msg(&self) -> String;
 
+    fn message_type_id(&self) -> TypeId;
+
     /// Returns the message as a boxed any.
     ///
     /// This method is only useful in unit tests.
     fn message(&mut self) -> Box<dyn Any>;
 
+    /// Returns the message and its reply channel as a boxed any.
+    fn message_and_reply_tx(&mut self) -> Box<dyn Any>;
+
     /// Execute the captured handle function.
     async fn handle_message(
         &mut self,
@@ -106,6 +147,10 @@ where
         }
     }
 
+    fn message_type_id(&self) -> TypeId {
+        TypeId::of::<M>()
+    }
+
     fn message(&mut self) -> Box<dyn Any> {
         if let Some((_, message)) = self.take() {
             Box::new(message)
@@ -114,6 +159,14 @@ where
         }
     }
 
+    fn message_and_reply_tx(&mut self) -> Box<dyn Any> {
+        if let Some((response_tx, message)) = self.take() {
+            Box::new((message, response_tx))
+        } else {
+            Box::new(())
+        }
+    }
+
     async fn handle_message(
         &mut self,
         actor: &mut A,
@@ -140,6 +193,7 @@ where
 pub(crate) fn wrap_in_envelope<A, M>(
     msg: M,
     no_advance_time_guard: Option<NoAdvanceTimeGuard>,
+    queued_message_guard_opt: Option<QueuedMessageGuard>,
 ) -> (Envelope<A>, oneshot::Receiver<A::Reply>)
 where
     A: DeferableReplyHandler<M>,
@@ -150,6 +204,7 @@ where
     let envelope = Envelope {
         handler_envelope: Box::new(handler_envelope),
         _no_advance_time_guard: no_advance_time_guard,
+        queued_message_guard_opt,
     };
     (envelope, response_rx)
 }
@@ -41,6 +41,7 @@ pub mod channel_with_priority;
 mod command;
 mod envelope;
 mod mailbox;
+mod metrics;
 mod observation;
 mod registry;
 pub(crate) mod scheduler;
@@ -56,6 +57,7 @@ mod universe;
 pub use actor::{Actor, ActorExitStatus, DeferableReplyHandler, Handler};
 pub use actor_handle::{ActorHandle, Health, Healthz, Supervisable};
 pub use command::{Command, Observe};
+pub use envelope::Envelope;
 pub use observation::{Observation, ObservationType};
 use quickwit_common::KillSwitch;
 pub use spawn_builder::SpawnContext;
@@ -122,6 +124,8 @@ const OBSERVE_TIMEOUT: Duration = Duration::from_secs(3);
 pub enum AskError<E: fmt::Debug> {
     #[error("message could not be delivered")]
     MessageNotDelivered,
+    #[error("message could not be delivered because the actor queue is full")]
+    QueueFull,
     #[error("error while the message was being processed")]
     ProcessMessageError,
     #[error("the handler returned an error: `{0:?}`")]
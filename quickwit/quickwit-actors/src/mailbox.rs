@@ -29,6 +29,7 @@ use tokio::sync::oneshot;
 
 use crate::channel_with_priority::{Receiver, Sender, TrySendError};
 use crate::envelope::{wrap_in_envelope, Envelope};
+use crate::metrics::{EnqueuedMessageRecorder, MailboxMetrics};
 use crate::scheduler::SchedulerClient;
 use crate::{Actor, AskError, Command, DeferableReplyHandler, QueueCapacity, RecvError, SendError};
 
@@ -111,6 +112,7 @@ struct Inner<A: Actor> {
     pub(crate) tx: Sender<Envelope<A>>,
     scheduler_client_opt: Option<SchedulerClient>,
     instance_id: String,
+    metrics_opt: Option<MailboxMetrics>,
 }
 
 impl<A: Actor> fmt::Debug for Mailbox<A> {
@@ -158,7 +160,7 @@ impl<A: Actor> Mailbox<A> {
         A: DeferableReplyHandler<M>,
         M: fmt::Debug + Send + 'static,
     {
-        let (envelope, response_rx) = self.wrap_in_envelope(message);
+        let (envelope, response_rx, enqueued_message_recorder_opt) = self.wrap_in_envelope(message);
         self.inner
             .tx
             .try_send_low_priority(envelope)
@@ -172,10 +174,18 @@ impl<A: Actor> Mailbox<A> {
                     }
                 }
             })?;
+        record_enqueue(enqueued_message_recorder_opt);
         Ok(response_rx)
     }
 
-    fn wrap_in_envelope<M>(&self, message: M) -> (Envelope<A>, oneshot::Receiver<A::Reply>)
+    fn wrap_in_envelope<M>(
+        &self,
+        message: M,
+    ) -> (
+        Envelope<A>,
+        oneshot::Receiver<A::Reply>,
+        Option<EnqueuedMessageRecorder>,
+    )
     where
         A: DeferableReplyHandler<M>,
         M: fmt::Debug + Send + 'static,
@@ -185,7 +195,15 @@ impl<A: Actor> Mailbox<A> {
             .scheduler_client_opt
             .as_ref()
             .map(|scheduler_client| scheduler_client.no_advance_time_guard());
-        wrap_in_envelope(message, guard)
+        let (queued_message_guard_opt, enqueued_message_recorder_opt) =
+            match self.inner.metrics_opt.as_ref().map(MailboxMetrics::queue) {
+                Some((queued_message_guard, enqueued_message_recorder)) => {
+                    (Some(queued_message_guard), Some(enqueued_message_recorder))
+                }
+                None => (None, None),
+            };
+        let (envelope, response_rx) = wrap_in_envelope(message, guard, queued_message_guard_opt);
+        (envelope, response_rx, enqueued_message_recorder_opt)
     }
 
     /// Sends a message to the actor owning the associated inbox.
@@ -202,9 +220,12 @@ impl<A: Actor> Mailbox<A> {
         A: DeferableReplyHandler<M>,
         M: fmt::Debug + Send + 'static,
     {
-        let (envelope, response_rx) = self.wrap_in_envelope(message);
+        let (envelope, response_rx, enqueued_message_recorder_opt) = self.wrap_in_envelope(message);
         match self.inner.tx.try_send_low_priority(envelope) {
-            Ok(()) => Ok(response_rx),
+            Ok(()) => {
+                record_enqueue(enqueued_message_recorder_opt);
+                Ok(response_rx)
+            }
             Err(TrySendError::Full(envelope)) => {
                 if let Some(backpressure_micros_counter) = backpressure_micros_counter_opt {
                     let now = Instant::now();
@@ -214,6 +235,7 @@ impl<A: Actor> Mailbox<A> {
                 } else {
                     self.inner.tx.send_low_priority(envelope).await?;
                 }
+                record_enqueue(enqueued_message_recorder_opt);
                 Ok(response_rx)
             }
             Err(TrySendError::Disconnected) => Err(SendError::Disconnected),
@@ -228,8 +250,9 @@ impl<A: Actor> Mailbox<A> {
         A: DeferableReplyHandler<M>,
         M: fmt::Debug + Send + 'static,
     {
-        let (envelope, response_rx) = self.wrap_in_envelope(message);
+        let (envelope, response_rx, enqueued_message_recorder_opt) = self.wrap_in_envelope(message);
         self.inner.tx.send_high_priority(envelope)?;
+        record_enqueue(enqueued_message_recorder_opt);
         Ok(response_rx)
     }
 
@@ -242,13 +265,14 @@ impl<A: Actor> Mailbox<A> {
         A: DeferableReplyHandler<M>,
         M: fmt::Debug + Send + 'static,
     {
-        let (envelope, response_rx) = self.wrap_in_envelope(message);
+        let (envelope, response_rx, enqueued_message_recorder_opt) = self.wrap_in_envelope(message);
         match priority {
             Priority::High => self.inner.tx.send_high_priority(envelope)?,
             Priority::Low => {
                 self.inner.tx.send_low_priority(envelope).await?;
             }
         }
+        record_enqueue(enqueued_message_recorder_opt);
         Ok(response_rx)
     }
 
@@ -309,6 +333,28 @@ impl<A: Actor> Mailbox<A> {
             .map_err(|_| AskError::ProcessMessageError)?
             .map_err(AskError::from)
     }
+
+    /// Similar to `ask_for_res`, except this method does not wait for the actor
+    /// queue to have some room for the message.
+    ///
+    /// If the queue is full, the message is dropped and `AskError::QueueFull` is
+    /// returned right away. This is useful to shed load rather than experience
+    /// backpressure.
+    pub async fn try_ask_for_res<M, T, E>(&self, message: M) -> Result<T, AskError<E>>
+    where
+        A: DeferableReplyHandler<M, Reply = Result<T, E>>,
+        M: fmt::Debug + Send + 'static,
+        E: fmt::Debug,
+    {
+        self.try_send_message(message)
+            .map_err(|try_send_error| match try_send_error {
+                TrySendError::Full(_) => AskError::QueueFull,
+                TrySendError::Disconnected => AskError::MessageNotDelivered,
+            })?
+            .await
+            .map_err(|_| AskError::ProcessMessageError)?
+            .map_err(AskError::from)
+    }
 }
 
 pub struct Inbox<A: Actor> {
@@ -328,22 +374,70 @@ impl<A: Actor> Inbox<A> {
         self.rx.is_empty()
     }
 
+    /// Returns the number of messages waiting in the mailbox, commands and scheduled messages
+    /// excluded.
+    pub(crate) fn num_pending_messages(&self) -> usize {
+        self.rx.num_pending_low_priority_messages()
+    }
+
     pub(crate) async fn recv(&self) -> Result<Envelope<A>, RecvError> {
-        self.rx.recv().await
+        let mut envelope = self.rx.recv().await?;
+        envelope.record_dequeue();
+        Ok(envelope)
     }
 
     pub(crate) async fn recv_cmd_and_scheduled_msg_only(&self) -> Envelope<A> {
-        self.rx.recv_high_priority().await
+        let mut envelope = self.rx.recv_high_priority().await;
+        envelope.record_dequeue();
+        envelope
     }
 
     pub(crate) fn try_recv(&self) -> Result<Envelope<A>, RecvError> {
-        self.rx.try_recv()
+        let mut envelope = self.rx.try_recv()?;
+        envelope.record_dequeue();
+        Ok(envelope)
+    }
+
+    /// Waits for the next message and returns it along with up to `max - 1` messages that are
+    /// already pending, without waiting for more.
+    pub(crate) async fn recv_many(&self, max: usize) -> Result<Vec<Envelope<A>>, RecvError> {
+        let first_envelope = self.recv().await?;
+        let mut envelopes = Vec::with_capacity(max.max(1));
+        envelopes.push(first_envelope);
+        self.drain_into(&mut envelopes, max);
+        Ok(envelopes)
+    }
+
+    /// Returns up to `max` pending messages, without waiting.
+    ///
+    /// The returned list is empty if no message is available.
+    pub(crate) fn drain(&self, max: usize) -> Vec<Envelope<A>> {
+        let mut envelopes = Vec::new();
+        self.drain_into(&mut envelopes, max);
+        envelopes
+    }
+
+    fn drain_into(&self, envelopes: &mut Vec<Envelope<A>>, max: usize) {
+        while envelopes.len() < max {
+            // A command may change the state of the actor, so it always ends the batch.
+            if envelopes
+                .last()
+                .map(|envelope| envelope.is_message_of_type::<Command>())
+                .unwrap_or(false)
+            {
+                break;
+            }
+            let Ok(envelope) = self.try_recv() else {
+                break;
+            };
+            envelopes.push(envelope);
+        }
     }
 
     #[cfg(any(test, feature = "testsuite"))]
     pub async fn recv_typed_message<M: 'static>(&self) -> Result<M, RecvError> {
         loop {
-            match self.rx.recv().await {
+            match self.recv().await {
                 Ok(mut envelope) => {
                     if let Some(msg) = envelope.message_typed() {
                         return Ok(msg);
@@ -383,6 +477,12 @@ impl<A: Actor> Inbox<A> {
     }
 }
 
+fn record_enqueue(enqueued_message_recorder_opt: Option<EnqueuedMessageRecorder>) {
+    if let Some(enqueued_message_recorder) = enqueued_message_recorder_opt {
+        enqueued_message_recorder.record_enqueue();
+    }
+}
+
 pub(crate) fn create_mailbox<A: Actor>(
     actor_name: String,
     queue_capacity: QueueCapacity,
@@ -390,11 +490,13 @@ pub(crate) fn create_mailbox<A: Actor>(
 ) -> (Mailbox<A>, Inbox<A>) {
     let (tx, rx) = crate::channel_with_priority::channel(queue_capacity);
     let ref_count = Arc::new(AtomicUsize::new(1));
+    let metrics_opt = MailboxMetrics::for_actor(&actor_name);
     let mailbox = Mailbox {
         inner: Arc::new(Inner {
             tx,
             instance_id: quickwit_common::new_coolid(&actor_name),
             scheduler_client_opt,
+            metrics_opt,
         }),
         ref_count,
     };
@@ -431,6 +533,7 @@ mod tests {
     use std::time::Duration;
 
     use super::*;
+    use crate::metrics::ACTOR_METRICS;
     use crate::tests::{Ping, PingReceiverActor};
     use crate::{ActorContext, ActorExitStatus, Handler, Universe};
 
@@ -485,6 +588,22 @@ mod tests {
         }
     }
 
+    #[derive(Debug)]
+    struct FallibleMessage;
+
+    #[async_trait]
+    impl Handler<FallibleMessage> for BackPressureActor {
+        type Reply = Result<(), String>;
+
+        async fn handle(
+            &mut self,
+            _message: FallibleMessage,
+            _ctx: &ActorContext<Self>,
+        ) -> Result<Result<(), String>, ActorExitStatus> {
+            Ok(Ok(()))
+        }
+    }
+
     #[tokio::test]
     async fn test_mailbox_send_with_backpressure_counter_low_backpressure() {
         let universe = Universe::with_accelerated_time();
@@ -594,6 +713,92 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_try_ask_for_res_queue_full() {
+        let universe = Universe::with_accelerated_time();
+        let (mailbox, _inbox) = universe
+            .create_mailbox::<BackPressureActor>("hello".to_string(), QueueCapacity::Bounded(1));
+        mailbox.try_send_message(FallibleMessage).unwrap();
+        let ask_error = mailbox.try_ask_for_res(FallibleMessage).await.unwrap_err();
+        assert!(matches!(ask_error, AskError::QueueFull));
+    }
+
+    #[tokio::test]
+    async fn test_try_ask_for_res_disconnect() {
+        let universe = Universe::with_accelerated_time();
+        let (mailbox, inbox) = universe
+            .create_mailbox::<BackPressureActor>("hello".to_string(), QueueCapacity::Bounded(1));
+        mem::drop(inbox);
+        let ask_error = mailbox.try_ask_for_res(FallibleMessage).await.unwrap_err();
+        assert!(matches!(ask_error, AskError::MessageNotDelivered));
+    }
+
+    #[tokio::test]
+    async fn test_mailbox_metrics_reflect_backlog() {
+        let universe = Universe::with_accelerated_time();
+        let actor_name = "test-mailbox-metrics-backlog";
+        let (mailbox, inbox) = universe
+            .create_mailbox::<PingReceiverActor>(actor_name.to_string(), QueueCapacity::Bounded(3));
+        let queue_depth = ACTOR_METRICS
+            .mailbox_queue_depth
+            .with_label_values([actor_name]);
+        let enqueued_total = ACTOR_METRICS
+            .mailbox_enqueued_total
+            .with_label_values([actor_name]);
+        let dequeued_total = ACTOR_METRICS
+            .mailbox_dequeued_total
+            .with_label_values([actor_name]);
+
+        for _ in 0..3 {
+            mailbox.try_send_message(Ping).unwrap();
+        }
+        // Refused messages are not part of the backlog.
+        mailbox.try_send_message(Ping).unwrap_err();
+        assert_eq!(queue_depth.get(), 3);
+        assert_eq!(enqueued_total.get(), 3);
+
+        inbox.try_recv().unwrap();
+        assert_eq!(queue_depth.get(), 2);
+        assert_eq!(dequeued_total.get(), 1);
+
+        // Pending messages are dropped along with the inbox.
+        mem::drop(inbox);
+        assert_eq!(queue_depth.get(), 0);
+        assert_eq!(dequeued_total.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mailbox_metrics_ignore_messages_waiting_for_room() {
+        let universe = Universe::with_accelerated_time();
+        let actor_name = "test-mailbox-metrics-waiting-for-room";
+        let (mailbox, inbox) = universe
+            .create_mailbox::<PingReceiverActor>(actor_name.to_string(), QueueCapacity::Bounded(1));
+        let queue_depth = ACTOR_METRICS
+            .mailbox_queue_depth
+            .with_label_values([actor_name]);
+        let enqueued_total = ACTOR_METRICS
+            .mailbox_enqueued_total
+            .with_label_values([actor_name]);
+
+        mailbox.send_message(Ping).await.unwrap();
+        let mailbox_clone = mailbox.clone();
+        let send_handle =
+            tokio::spawn(async move { mailbox_clone.send_message(Ping).await.map(|_| ()) });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        // The second message is waiting for room in the mailbox, it is not queued yet.
+        assert!(!send_handle.is_finished());
+        assert_eq!(queue_depth.get(), 1);
+        assert_eq!(enqueued_total.get(), 1);
+
+        inbox.try_recv().unwrap();
+        send_handle.await.unwrap().unwrap();
+        assert_eq!(queue_depth.get(), 1);
+        assert_eq!(enqueued_total.get(), 2);
+
+        inbox.try_recv().unwrap();
+        assert_eq!(queue_depth.get(), 0);
+    }
+
     #[tokio::test]
     async fn test_weak_mailbox_ref_count() {
         let universe = Universe::with_accelerated_time();
@@ -0,0 +1,196 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use once_cell::sync::Lazy;
+use quickwit_common::metrics::{
+    new_counter_vec, new_gauge_vec, new_histogram_vec, Histogram, HistogramVec, IntCounter,
+    IntCounterVec, IntGauge, IntGaugeVec,
+};
+
+pub(crate) struct ActorMetrics {
+    pub mailbox_queue_depth: IntGaugeVec<1>,
+    pub mailbox_enqueued_total: IntCounterVec<1>,
+    pub mailbox_dequeued_total: IntCounterVec<1>,
+    pub mailbox_time_in_queue_seconds: HistogramVec<1>,
+}
+
+impl Default for ActorMetrics {
+    fn default() -> Self {
+        ActorMetrics {
+            mailbox_queue_depth: new_gauge_vec(
+                "mailbox_queue_depth",
+                "Number of messages waiting in the mailboxes of an actor.",
+                "quickwit_actors",
+                ["actor"],
+            ),
+            mailbox_enqueued_total: new_counter_vec(
+                "mailbox_enqueued_total",
+                "Number of messages sent to the mailboxes of an actor.",
+                "quickwit_actors",
+                ["actor"],
+            ),
+            mailbox_dequeued_total: new_counter_vec(
+                "mailbox_dequeued_total",
+                "Number of messages received by an actor from its mailboxes.",
+                "quickwit_actors",
+                ["actor"],
+            ),
+            mailbox_time_in_queue_seconds: new_histogram_vec(
+                "mailbox_time_in_queue_seconds",
+                "Time spent by messages in the mailboxes of an actor before being received.",
+                "quickwit_actors",
+                ["actor"],
+            ),
+        }
+    }
+}
+
+/// `ACTOR_METRICS` exposes the mailbox metrics of all the actors through a prometheus endpoint.
+pub(crate) static ACTOR_METRICS: Lazy<ActorMetrics> = Lazy::new(ActorMetrics::default);
+
+/// Mailbox metrics are only exported if `QW_ENABLE_ACTOR_MAILBOX_METRICS` is set to `true`: they
+/// cost an allocation and a histogram sample per message, on the hot path of every actor. They are
+/// always enabled in the unit tests of this crate.
+static MAILBOX_METRICS_ENABLED: Lazy<bool> = Lazy::new(|| {
+    cfg!(test) || quickwit_common::get_from_env("QW_ENABLE_ACTOR_MAILBOX_METRICS", false)
+});
+
+/// Handles on the mailbox metrics of a given actor.
+///
+/// All of the mailboxes of the actors sharing the same name report to the same series.
+#[derive(Clone)]
+pub(crate) struct MailboxMetrics {
+    queue_depth: IntGauge,
+    enqueued_total: IntCounter,
+    dequeued_total: IntCounter,
+    time_in_queue_seconds: Histogram,
+}
+
+impl MailboxMetrics {
+    /// Returns the mailbox metrics of the actor named `actor_name`, or `None` if mailbox metrics
+    /// are disabled.
+    pub fn for_actor(actor_name: &str) -> Option<Self> {
+        if !*MAILBOX_METRICS_ENABLED {
+            return None;
+        }
+        let mailbox_metrics = MailboxMetrics {
+            queue_depth: ACTOR_METRICS
+                .mailbox_queue_depth
+                .with_label_values([actor_name]),
+            enqueued_total: ACTOR_METRICS
+                .mailbox_enqueued_total
+                .with_label_values([actor_name]),
+            dequeued_total: ACTOR_METRICS
+                .mailbox_dequeued_total
+                .with_label_values([actor_name]),
+            time_in_queue_seconds: ACTOR_METRICS
+                .mailbox_time_in_queue_seconds
+                .with_label_values([actor_name]),
+        };
+        Some(mailbox_metrics)
+    }
+
+    /// Accounts for a message that is about to be sent to the mailbox.
+    ///
+    /// The message only becomes part of the queue depth once the sender reports that it was
+    /// enqueued with [`EnqueuedMessageRecorder::record_enqueue`], which may happen long after the
+    /// send started if the mailbox is full. It stays part of the queue depth until the returned
+    /// guard is either passed to [`QueuedMessageGuard::dequeue`] or dropped.
+    pub fn queue(&self) -> (QueuedMessageGuard, EnqueuedMessageRecorder) {
+        let queued_message = Arc::new(QueuedMessage {
+            mailbox_metrics: self.clone(),
+            state: Mutex::new(QueuedMessageState::Sending),
+        });
+        (
+            QueuedMessageGuard(queued_message.clone()),
+            EnqueuedMessageRecorder(queued_message),
+        )
+    }
+}
+
+struct QueuedMessage {
+    mailbox_metrics: MailboxMetrics,
+    state: Mutex<QueuedMessageState>,
+}
+
+enum QueuedMessageState {
+    /// The sender is waiting for room in the mailbox.
+    Sending,
+    Queued {
+        queued_at: Instant,
+    },
+    /// The message was received by the actor or dropped.
+    Done,
+}
+
+/// Kept by the sender of a message to report when the message is enqueued. See
+/// [`MailboxMetrics::queue`].
+pub(crate) struct EnqueuedMessageRecorder(Arc<QueuedMessage>);
+
+impl EnqueuedMessageRecorder {
+    /// Accounts for a message successfully sent to the mailbox.
+    pub fn record_enqueue(self) {
+        let mailbox_metrics = &self.0.mailbox_metrics;
+        mailbox_metrics.enqueued_total.inc();
+        let mut state = self.0.state.lock().unwrap();
+        // The actor may have received the message before the sender got to report it.
+        if let QueuedMessageState::Sending = *state {
+            mailbox_metrics.queue_depth.inc();
+            *state = QueuedMessageState::Queued {
+                queued_at: Instant::now(),
+            };
+        }
+    }
+}
+
+/// Travels along with a queued message. See [`MailboxMetrics::queue`].
+pub(crate) struct QueuedMessageGuard(Arc<QueuedMessage>);
+
+impl QueuedMessageGuard {
+    /// Accounts for a message received by the actor.
+    pub fn dequeue(self) {
+        let mailbox_metrics = &self.0.mailbox_metrics;
+        mailbox_metrics.dequeued_total.inc();
+        let mut state = self.0.state.lock().unwrap();
+        let time_in_queue_secs = match *state {
+            QueuedMessageState::Queued { queued_at } => {
+                mailbox_metrics.queue_depth.dec();
+                queued_at.elapsed().as_secs_f64()
+            }
+            QueuedMessageState::Sending | QueuedMessageState::Done => 0.0,
+        };
+        mailbox_metrics
+            .time_in_queue_seconds
+            .observe(time_in_queue_secs);
+        *state = QueuedMessageState::Done;
+    }
+}
+
+impl Drop for QueuedMessageGuard {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock().unwrap();
+        if let QueuedMessageState::Queued { .. } = *state {
+            self.0.mailbox_metrics.queue_depth.dec();
+        }
+        *state = QueuedMessageState::Done;
+    }
+}
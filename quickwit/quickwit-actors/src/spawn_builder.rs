@@ -20,20 +20,28 @@
 use std::time::Duration;
 
 use anyhow::Context;
+use once_cell::sync::Lazy;
 use quickwit_common::metrics::IntCounter;
 use sync_wrapper::SyncWrapper;
 use tokio::sync::watch;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::envelope::Envelope;
 use crate::mailbox::{create_mailbox, Inbox};
-use crate::registry::{ActorJoinHandle, ActorRegistry};
+use crate::registry::{ActorJoinHandle, ActorObservation, ActorRegistry};
 use crate::scheduler::{NoAdvanceTimeGuard, SchedulerClient};
 use crate::supervisor::Supervisor;
 use crate::{
     Actor, ActorContext, ActorExitStatus, ActorHandle, KillSwitch, Mailbox, QueueCapacity,
 };
 
+/// Warning about the messages dropped by exiting actors is enabled in debug builds, or when
+/// `QW_WARN_ON_DROPPED_ACTOR_MESSAGES` is set to `true`.
+static DROPPED_MESSAGES_WARNING_ENABLED: Lazy<bool> = Lazy::new(|| {
+    cfg!(debug_assertions)
+        || quickwit_common::get_from_env("QW_WARN_ON_DROPPED_ACTOR_MESSAGES", false)
+});
+
 #[derive(Clone)]
 pub struct SpawnContext {
     pub(crate) scheduler_client: SchedulerClient,
@@ -74,6 +82,12 @@ impl SpawnContext {
         }
     }
 
+    /// Observes all of the actors registered in this context, waiting at most `timeout` for each
+    /// of them.
+    pub async fn observe(&self, timeout: Duration) -> Vec<ActorObservation> {
+        self.registry.observe(timeout).await
+    }
+
     /// Schedules a new event.
     /// Once `timeout` is elapsed, the future `fut` is
     /// executed.
@@ -240,6 +254,19 @@ async fn recv_envelope<A: Actor>(inbox: &mut Inbox<A>, ctx: &ActorContext<A>) ->
     }
 }
 
+/// Receives a batch of up to `max_batch_size` envelopes.
+///
+/// This function waits for the first envelope only. It must not be called on a paused actor.
+async fn recv_batch<A: Actor>(
+    inbox: &mut Inbox<A>,
+    ctx: &ActorContext<A>,
+    max_batch_size: usize,
+) -> Vec<Envelope<A>> {
+    ctx.protect_future(inbox.recv_many(max_batch_size))
+        .await
+        .expect("Disconnection should be impossible because the ActorContext holds a Mailbox too")
+}
+
 fn try_recv_envelope<A: Actor>(inbox: &mut Inbox<A>) -> Option<Envelope<A>> {
     inbox.try_recv().ok()
 }
@@ -248,6 +275,8 @@ struct ActorExecutionEnv<A: Actor> {
     actor: SyncWrapper<A>,
     inbox: Inbox<A>,
     ctx: ActorContext<A>,
+    /// Number of messages processed since the actor last yielded.
+    num_messages_since_yield: usize,
 }
 
 impl<A: Actor> ActorExecutionEnv<A> {
@@ -258,27 +287,64 @@ impl<A: Actor> ActorExecutionEnv<A> {
     async fn process_messages(&mut self) -> ActorExitStatus {
         loop {
             if let Err(exit_status) = self.process_all_available_messages().await {
+                if matches!(exit_status, ActorExitStatus::Quit)
+                    && self.actor.get_mut().drain_mailbox_on_quit()
+                {
+                    return self.drain_mailbox().await;
+                }
                 return exit_status;
             }
         }
     }
 
+    /// Processes the messages pending in the mailbox of an actor that quit, without waiting for
+    /// new messages.
+    async fn drain_mailbox(&mut self) -> ActorExitStatus {
+        while let Some(mut envelope) = try_recv_envelope(&mut self.inbox) {
+            if self.ctx.kill_switch().is_dead() {
+                return ActorExitStatus::Killed;
+            }
+            self.ctx.record_progress();
+
+            match envelope
+                .handle_message(self.actor.get_mut(), &self.ctx)
+                .await
+            {
+                Ok(()) | Err(ActorExitStatus::Quit) | Err(ActorExitStatus::Success) => {}
+                Err(exit_status) => return exit_status,
+            }
+        }
+        ActorExitStatus::Quit
+    }
+
     async fn process_one_message(
         &mut self,
         mut envelope: Envelope<A>,
     ) -> Result<(), ActorExitStatus> {
         self.yield_and_check_if_killed().await?;
+        self.num_messages_since_yield += 1;
         envelope
             .handle_message(self.actor.get_mut(), &self.ctx)
             .await?;
         Ok(())
     }
 
+    async fn process_batch(&mut self, batch: Vec<Envelope<A>>) -> Result<(), ActorExitStatus> {
+        self.yield_and_check_if_killed().await?;
+        self.num_messages_since_yield += batch.len();
+        self.actor.get_mut().process_batch(batch, &self.ctx).await?;
+        Ok(())
+    }
+
     async fn yield_and_check_if_killed(&mut self) -> Result<(), ActorExitStatus> {
         if self.ctx.kill_switch().is_dead() {
             return Err(ActorExitStatus::Killed);
         }
-        if self.actor.get_mut().yield_after_each_message() {
+        let actor = self.actor.get_mut();
+        if actor.yield_after_each_message()
+            || self.num_messages_since_yield >= actor.max_messages_between_yields()
+        {
+            self.num_messages_since_yield = 0;
             self.ctx.yield_now().await;
             if self.ctx.kill_switch().is_dead() {
                 return Err(ActorExitStatus::Killed);
@@ -291,20 +357,39 @@ impl<A: Actor> ActorExecutionEnv<A> {
 
     async fn process_all_available_messages(&mut self) -> Result<(), ActorExitStatus> {
         self.yield_and_check_if_killed().await?;
-        let envelope = recv_envelope(&mut self.inbox, &self.ctx).await;
-        self.process_one_message(envelope).await?;
+        let max_batch_size = self.actor.get_mut().max_batch_size();
+        // Batches are only assembled for a running actor: a paused actor must only
+        // process commands and scheduled messages.
+        if max_batch_size > 1 && self.ctx.state().is_running() {
+            let batch = recv_batch(&mut self.inbox, &self.ctx, max_batch_size).await;
+            self.process_batch(batch).await?;
+        } else {
+            let envelope = recv_envelope(&mut self.inbox, &self.ctx).await;
+            self.process_one_message(envelope).await?;
+        }
         // If the actor is Running (not Paused), we consume all the messages in the mailbox
         // and call `on_drained_message`.
         if self.ctx.state().is_running() {
             loop {
-                while let Some(envelope) = try_recv_envelope(&mut self.inbox) {
-                    self.process_one_message(envelope).await?;
+                if max_batch_size > 1 {
+                    loop {
+                        let batch = self.inbox.drain(max_batch_size);
+                        if batch.is_empty() {
+                            break;
+                        }
+                        self.process_batch(batch).await?;
+                    }
+                } else {
+                    while let Some(envelope) = try_recv_envelope(&mut self.inbox) {
+                        self.process_one_message(envelope).await?;
+                    }
                 }
                 // We have reached the last message.
                 // Let's still yield and see if we have more messages:
                 // an upstream actor might have experienced backpressure, and is now waiting for our
                 // mailbox to have some room.
                 self.ctx.yield_now().await;
+                self.num_messages_since_yield = 0;
                 if self.inbox.is_empty() {
                     break;
                 }
@@ -346,6 +431,24 @@ impl<A: Actor> ActorExecutionEnv<A> {
         exit_status
     }
 
+    /// Warns about the messages left in the mailbox of an actor that exited without being killed,
+    /// as these messages will never be processed.
+    fn warn_on_dropped_messages(&self, exit_status: &ActorExitStatus) {
+        if !*DROPPED_MESSAGES_WARNING_ENABLED || matches!(exit_status, ActorExitStatus::Killed) {
+            return;
+        }
+        let num_dropped_messages = self.inbox.num_pending_messages();
+
+        if num_dropped_messages > 0 {
+            warn!(
+                actor_id = %self.ctx.actor_instance_id(),
+                exit_status = %exit_status,
+                num_dropped_messages,
+                "actor exited with pending messages"
+            );
+        }
+    }
+
     fn process_exit_status(&self, exit_status: &ActorExitStatus) {
         match &exit_status {
             ActorExitStatus::Success
@@ -359,6 +462,7 @@ impl<A: Actor> ActorExecutionEnv<A> {
                 error!(exit_status=?exit_status, "actor-failure");
             }
         }
+        self.warn_on_dropped_messages(exit_status);
         info!(actor_id = %self.ctx.actor_instance_id(), exit_status = %exit_status, "actor-exit");
         self.ctx.exit(exit_status);
     }
@@ -382,6 +486,7 @@ async fn actor_loop<A: Actor>(
         actor: SyncWrapper::new(actor),
         inbox,
         ctx,
+        num_messages_since_yield: 0,
     };
 
     let initialize_exit_status_res: Result<(), ActorExitStatus> = actor_env.initialize().await;
@@ -220,6 +220,11 @@ mod tests {
         ReturnError,
         Increment,
         Freeze(Duration),
+        // Works for `work_duration`, recording progress every `progress_period`.
+        SlowWork {
+            work_duration: Duration,
+            progress_period: Duration,
+        },
     }
 
     #[derive(Default, Clone)]
@@ -273,6 +278,18 @@ mod tests {
                 FailingActorMessage::Freeze(wait_duration) => {
                     ctx.sleep(wait_duration).await;
                 }
+                FailingActorMessage::SlowWork {
+                    work_duration,
+                    progress_period,
+                } => {
+                    let mut elapsed = Duration::ZERO;
+
+                    while elapsed < work_duration {
+                        ctx.sleep(progress_period).await;
+                        ctx.record_progress();
+                        elapsed += progress_period;
+                    }
+                }
             }
             Ok(self.counter)
         }
@@ -388,6 +405,41 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_supervisor_does_not_kill_slow_actor_recording_progress() {
+        let universe = Universe::with_accelerated_time();
+        let actor = FailingActor::default();
+        let (mailbox, supervisor_handle) = universe.spawn_builder().supervise(actor);
+        assert_eq!(
+            mailbox.ask(FailingActorMessage::Increment).await.unwrap(),
+            1
+        );
+        mailbox
+            .send_message(FailingActorMessage::SlowWork {
+                work_duration: crate::HEARTBEAT.mul_f32(3.0f32),
+                progress_period: crate::HEARTBEAT.mul_f32(0.5f32),
+            })
+            .await
+            .unwrap();
+        // The actor was not restarted: its counter was preserved.
+        assert_eq!(
+            mailbox.ask(FailingActorMessage::Increment).await.unwrap(),
+            2
+        );
+        assert_eq!(
+            supervisor_handle.observe().await.metrics,
+            SupervisorMetrics {
+                num_panics: 0,
+                num_errors: 0,
+                num_kills: 0
+            }
+        );
+        assert!(!matches!(
+            supervisor_handle.quit().await.0,
+            ActorExitStatus::Panicked
+        ));
+    }
+
     #[tokio::test]
     async fn test_supervisor_forwards_quit_commands() {
         let universe = Universe::with_accelerated_time();
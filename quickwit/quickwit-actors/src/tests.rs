@@ -18,8 +18,10 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use std::cell::Cell;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::ops::Mul;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
@@ -28,8 +30,8 @@ use serde::Serialize;
 
 use crate::observation::ObservationType;
 use crate::{
-    Actor, ActorContext, ActorExitStatus, ActorHandle, ActorState, Command, Handler, Health,
-    Mailbox, Observation, Supervisable, Universe,
+    Actor, ActorContext, ActorExitStatus, ActorHandle, ActorState, Command, Envelope, Handler,
+    Health, Mailbox, Observation, Supervisable, Universe,
 };
 
 // An actor that receives ping messages.
@@ -641,6 +643,218 @@ async fn test_drain_is_called() {
     universe.assert_quit().await;
 }
 
+#[derive(Default)]
+struct BatchingActor {
+    counts: BatchCounts,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+struct BatchCounts {
+    num_messages: usize,
+    num_batches: usize,
+    max_batch_len: usize,
+}
+
+#[async_trait]
+impl Actor for BatchingActor {
+    type ObservableState = BatchCounts;
+
+    fn observable_state(&self) -> BatchCounts {
+        self.counts
+    }
+
+    fn max_batch_size(&self) -> usize {
+        4
+    }
+
+    async fn process_batch(
+        &mut self,
+        batch: Vec<Envelope<Self>>,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        self.counts.num_batches += 1;
+        self.counts.max_batch_len = self.counts.max_batch_len.max(batch.len());
+        for mut envelope in batch {
+            envelope.handle_message(self, ctx).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler<Ping> for BatchingActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        _message: Ping,
+        _ctx: &ActorContext<Self>,
+    ) -> Result<Self::Reply, ActorExitStatus> {
+        self.counts.num_messages += 1;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_process_batch() {
+    let universe = Universe::with_accelerated_time();
+    let (mailbox, handle) = universe.spawn_builder().spawn(BatchingActor::default());
+    handle.pause();
+    for _ in 0..10 {
+        mailbox.send_message(Ping).await.unwrap();
+    }
+    handle.resume();
+    let counts = *handle.process_pending_and_observe().await;
+    assert_eq!(counts.num_messages, 10);
+    assert!(counts.num_batches >= 3);
+    assert!(counts.num_batches < 10);
+    assert_eq!(counts.max_batch_len, 4);
+    universe.assert_quit().await;
+}
+
+/// Records the ticks of a concurrent task seen while handling messages: the ticks only advance
+/// when the actor yields.
+struct NonYieldingActor {
+    ticks: Arc<AtomicUsize>,
+    observed_ticks: BTreeSet<usize>,
+}
+
+#[async_trait]
+impl Actor for NonYieldingActor {
+    type ObservableState = usize;
+
+    fn observable_state(&self) -> usize {
+        self.observed_ticks.len()
+    }
+
+    fn yield_after_each_message(&self) -> bool {
+        false
+    }
+
+    fn max_messages_between_yields(&self) -> usize {
+        10
+    }
+}
+
+#[async_trait]
+impl Handler<Ping> for NonYieldingActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        _message: Ping,
+        _ctx: &ActorContext<Self>,
+    ) -> Result<Self::Reply, ActorExitStatus> {
+        self.observed_ticks
+            .insert(self.ticks.load(Ordering::Relaxed));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_actor_yields_periodically_on_large_backlog() {
+    let universe = Universe::with_accelerated_time();
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let ticker_handle = tokio::spawn({
+        let ticks = ticks.clone();
+        async move {
+            loop {
+                ticks.fetch_add(1, Ordering::Relaxed);
+                tokio::task::yield_now().await;
+            }
+        }
+    });
+    let actor = NonYieldingActor {
+        ticks,
+        observed_ticks: BTreeSet::new(),
+    };
+    let (mailbox, handle) = universe.spawn_builder().spawn(actor);
+    handle.pause();
+    for _ in 0..100 {
+        mailbox.send_message(Ping).await.unwrap();
+    }
+    handle.resume();
+    let num_observed_ticks = *handle.process_pending_and_observe().await;
+    // The actor yields every 10 messages, so the ticker advances at least 10 times while the
+    // backlog is processed, but not after each message.
+    assert!(num_observed_ticks >= 10);
+    assert!(num_observed_ticks < 100);
+    ticker_handle.abort();
+    universe.assert_quit().await;
+}
+
+#[tokio::test]
+async fn test_inbox_drain() {
+    let universe = Universe::with_accelerated_time();
+    let (mailbox, inbox) = universe.create_test_mailbox::<PingReceiverActor>();
+    for _ in 0..5 {
+        mailbox.send_message(Ping).await.unwrap();
+    }
+    assert_eq!(inbox.recv_many(3).await.unwrap().len(), 3);
+    assert_eq!(inbox.drain(3).len(), 2);
+    assert!(inbox.drain(3).is_empty());
+    universe.assert_quit().await;
+}
+
+#[derive(Default)]
+struct DrainOnQuitActor {
+    ping_count: usize,
+}
+
+impl Actor for DrainOnQuitActor {
+    type ObservableState = usize;
+
+    fn observable_state(&self) -> usize {
+        self.ping_count
+    }
+
+    fn drain_mailbox_on_quit(&self) -> bool {
+        true
+    }
+}
+
+#[async_trait]
+impl Handler<Ping> for DrainOnQuitActor {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        _message: Ping,
+        _ctx: &ActorContext<Self>,
+    ) -> Result<Self::Reply, ActorExitStatus> {
+        self.ping_count += 1;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_quit_drains_mailbox() {
+    let universe = Universe::with_accelerated_time();
+    let (mailbox, handle) = universe.spawn_builder().spawn(DrainOnQuitActor::default());
+    handle.pause();
+    for _ in 0..3 {
+        mailbox.send_message(Ping).await.unwrap();
+    }
+    let (exit_status, ping_count) = handle.quit().await;
+    assert!(matches!(exit_status, ActorExitStatus::Quit));
+    assert_eq!(ping_count, 3);
+    universe.assert_quit().await;
+}
+
+#[tokio::test]
+async fn test_kill_does_not_drain_mailbox() {
+    let universe = Universe::with_accelerated_time();
+    let (mailbox, handle) = universe.spawn_builder().spawn(DrainOnQuitActor::default());
+    handle.pause();
+    for _ in 0..3 {
+        mailbox.send_message(Ping).await.unwrap();
+    }
+    let (exit_status, ping_count) = handle.kill().await;
+    assert!(matches!(exit_status, ActorExitStatus::Killed));
+    assert_eq!(ping_count, 0);
+    universe.assert_quit().await;
+}
+
 #[tokio::test]
 async fn test_unsync_actor() {
     #[derive(Default)]
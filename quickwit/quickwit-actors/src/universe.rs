@@ -100,7 +100,7 @@ impl Universe {
     }
 
     pub async fn observe(&self, timeout: Duration) -> Vec<ActorObservation> {
-        self.spawn_ctx.registry.observe(timeout).await
+        self.spawn_ctx.observe(timeout).await
     }
 
     pub fn kill(&self) {
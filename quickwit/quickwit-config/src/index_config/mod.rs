@@ -32,8 +32,8 @@ use cron::Schedule;
 use humantime::parse_duration;
 use quickwit_common::uri::Uri;
 use quickwit_doc_mapper::{
-    DefaultDocMapper, DefaultDocMapperBuilder, DocMapper, FieldMappingEntry, Mode, ModeType,
-    QuickwitJsonOptions, TokenizerEntry,
+    CoercionMode, DefaultDocMapper, DefaultDocMapperBuilder, DocMapper, FieldMappingEntry, Mode,
+    ModeType, QuickwitJsonOptions, TokenizerEntry,
 };
 use quickwit_proto::types::IndexId;
 use serde::{Deserialize, Serialize};
@@ -90,6 +90,9 @@ pub struct DocMapping {
     pub max_num_partitions: NonZeroU32,
     #[serde(default)]
     pub tokenizers: Vec<TokenizerEntry>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "CoercionMode::is_lenient")]
+    pub coercion_mode: CoercionMode,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, utoipa::ToSchema)]
@@ -454,6 +457,7 @@ impl TestableForRegression for IndexConfig {
             max_num_partitions: NonZeroU32::new(100).unwrap(),
             timestamp_field: Some("timestamp".to_string()),
             tokenizers: vec![tokenizer],
+            coercion_mode: CoercionMode::default(),
         };
         let retention_policy = Some(RetentionPolicy {
             retention_period: "90 days".to_string(),
@@ -531,6 +535,7 @@ pub fn build_doc_mapper(
         partition_key: doc_mapping.partition_key.clone(),
         max_num_partitions: doc_mapping.max_num_partitions,
         tokenizers: doc_mapping.tokenizers.clone(),
+        coercion_mode: doc_mapping.coercion_mode,
     };
     Ok(Arc::new(builder.try_build()?))
 }
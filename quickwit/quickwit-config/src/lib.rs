@@ -68,8 +68,8 @@ pub use crate::metastore_config::{
     MetastoreBackend, MetastoreConfig, MetastoreConfigs, PostgresMetastoreConfig,
 };
 pub use crate::node_config::{
-    enable_ingest_v2, IndexerConfig, IngestApiConfig, JaegerConfig, NodeConfig, SearcherConfig,
-    SplitCacheLimits, DEFAULT_QW_CONFIG_PATH,
+    enable_ingest_v2, IndexerConfig, IngestApiConfig, JaegerConfig, JanitorConfig, NodeConfig,
+    SearcherConfig, SplitCacheLimits, DEFAULT_QW_CONFIG_PATH,
 };
 use crate::source_config::serialize::{SourceConfigV0_7, VersionedSourceConfig};
 pub use crate::storage_config::{
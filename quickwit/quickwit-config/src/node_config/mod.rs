@@ -91,6 +91,11 @@ pub struct IndexerConfig {
     pub split_store_max_num_splits: usize,
     #[serde(default = "IndexerConfig::default_max_concurrent_split_uploads")]
     pub max_concurrent_split_uploads: usize,
+    /// Maximum number of times per minute that a publisher retries publishes that failed on
+    /// transient metastore errors. Retries back off exponentially, and beyond that budget,
+    /// publishes wait for the metastore to recover.
+    #[serde(default = "IndexerConfig::default_max_publish_retries_per_minute")]
+    pub max_publish_retries_per_minute: NonZeroU32,
     /// Limits the IO throughput of the `SplitDownloader` and the `MergeExecutor`.
     /// On hardware where IO is constrained, it makes sure that Merges (a batch operation)
     /// does not starve indexing itself (as it is a latency sensitive operation).
@@ -130,6 +135,10 @@ impl IndexerConfig {
         12
     }
 
+    fn default_max_publish_retries_per_minute() -> NonZeroU32 {
+        NonZeroU32::new(30).unwrap()
+    }
+
     pub fn default_split_store_max_num_bytes() -> ByteSize {
         ByteSize::gib(100)
     }
@@ -155,6 +164,7 @@ impl IndexerConfig {
             split_store_max_num_bytes: ByteSize::mb(1),
             split_store_max_num_splits: 3,
             max_concurrent_split_uploads: 4,
+            max_publish_retries_per_minute: NonZeroU32::new(30).unwrap(),
             cpu_capacity: PIPELINE_FULL_CAPACITY * 4u32,
             max_merge_write_throughput: None,
             merge_concurrency: NonZeroUsize::new(3).unwrap(),
@@ -171,6 +181,7 @@ impl Default for IndexerConfig {
             split_store_max_num_bytes: Self::default_split_store_max_num_bytes(),
             split_store_max_num_splits: Self::default_split_store_max_num_splits(),
             max_concurrent_split_uploads: Self::default_max_concurrent_split_uploads(),
+            max_publish_retries_per_minute: Self::default_max_publish_retries_per_minute(),
             cpu_capacity: Self::default_cpu_capacity(),
             merge_concurrency: Self::default_merge_concurrency(),
             max_merge_write_throughput: None,
@@ -247,6 +258,39 @@ pub struct IngestApiConfig {
     pub max_queue_disk_usage: ByteSize,
     pub replication_factor: usize,
     pub content_length_limit: ByteSize,
+    /// Maximum number of ingest requests processed concurrently by the REST API. Beyond that,
+    /// requests are rejected with a `503`. Unlimited if not set.
+    pub max_concurrent_requests: Option<NonZeroUsize>,
+    /// Treat unknown content-encodings of request bodies as `identity` and log them instead of
+    /// rejecting the request. This is meant for deployments behind trusted proxies that may tag
+    /// bodies with their own encodings. Disabled algorithms are still rejected.
+    pub lenient_unknown_content_encoding: bool,
+    /// Maximum size of decompressed request bodies. With stacked content-encodings, the limit
+    /// applies to the output of each decoder. Unlimited if not set.
+    pub decompressed_content_length_limit: Option<ByteSize>,
+    /// Reject request bodies without a `content-encoding` header that start with the magic bytes
+    /// of a compression format, with a hint to set the header, instead of letting them reach the
+    /// parser as binary.
+    pub detect_undeclared_compression: bool,
+    /// Validate decompressed request bodies as UTF-8, transcoding them first according to the
+    /// charset of their `content-type` header. Invalid bodies are rejected with a `400`.
+    pub validate_utf8_request_bodies: bool,
+    /// Directory of the custom brotli dictionaries that request bodies can be compressed with,
+    /// one per file. The ID of a dictionary is the name of its file.
+    pub brotli_dictionaries_dir: Option<PathBuf>,
+    /// Maximum time in seconds an ingest request with the `wait_for` or `force` commit type
+    /// waits for its documents to be committed before failing with a `408`.
+    pub wait_for_commit_timeout_secs: u64,
+    /// Preference order of the encodings used to compress ingest responses, for instance
+    /// `[zstd, gzip, br]`. It breaks ties between the encodings a client accepts with the same
+    /// q-value. By default, ties are broken by the order of the `accept-encoding` header.
+    pub response_compression_preference: Vec<String>,
+}
+
+impl IngestApiConfig {
+    pub fn wait_for_commit_timeout(&self) -> Duration {
+        Duration::from_secs(self.wait_for_commit_timeout_secs)
+    }
 }
 
 impl Default for IngestApiConfig {
@@ -256,6 +300,14 @@ impl Default for IngestApiConfig {
             max_queue_disk_usage: ByteSize::gib(4),   // TODO maybe we want more?
             replication_factor: 1,
             content_length_limit: ByteSize::mib(10),
+            max_concurrent_requests: None,
+            lenient_unknown_content_encoding: false,
+            decompressed_content_length_limit: None,
+            detect_undeclared_compression: false,
+            validate_utf8_request_bodies: false,
+            brotli_dictionaries_dir: None,
+            wait_for_commit_timeout_secs: 300,
+            response_compression_preference: Vec::new(),
         }
     }
 }
@@ -374,6 +426,34 @@ impl Default for JaegerConfig {
     }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JanitorConfig {
+    /// Splits are only deleted by the retention policy once this grace period has elapsed on top
+    /// of their retention period, so that a clock running ahead on the janitor node does not get
+    /// splits deleted early.
+    #[serde(default = "JanitorConfig::default_retention_policy_grace_period_secs")]
+    retention_policy_grace_period_secs: u64,
+}
+
+impl JanitorConfig {
+    pub fn retention_policy_grace_period(&self) -> Duration {
+        Duration::from_secs(self.retention_policy_grace_period_secs)
+    }
+
+    fn default_retention_policy_grace_period_secs() -> u64 {
+        300 // 5 minutes
+    }
+}
+
+impl Default for JanitorConfig {
+    fn default() -> Self {
+        Self {
+            retention_policy_grace_period_secs: Self::default_retention_policy_grace_period_secs(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct NodeConfig {
     pub cluster_id: String,
@@ -395,6 +475,7 @@ pub struct NodeConfig {
     pub searcher_config: SearcherConfig,
     pub ingest_api_config: IngestApiConfig,
     pub jaeger_config: JaegerConfig,
+    pub janitor_config: JanitorConfig,
 }
 
 impl NodeConfig {
@@ -37,7 +37,7 @@ use crate::storage_config::StorageConfigs;
 use crate::templating::render_config;
 use crate::{
     validate_identifier, validate_node_id, ConfigFormat, IndexerConfig, IngestApiConfig,
-    JaegerConfig, MetastoreConfigs, NodeConfig, SearcherConfig,
+    JaegerConfig, JanitorConfig, MetastoreConfigs, NodeConfig, SearcherConfig,
 };
 
 pub const DEFAULT_CLUSTER_ID: &str = "quickwit-default-cluster";
@@ -206,6 +206,9 @@ struct NodeConfigBuilder {
     #[serde(rename = "jaeger")]
     #[serde(default)]
     jaeger_config: JaegerConfig,
+    #[serde(rename = "janitor")]
+    #[serde(default)]
+    janitor_config: JanitorConfig,
 }
 
 impl NodeConfigBuilder {
@@ -309,6 +312,7 @@ impl NodeConfigBuilder {
             searcher_config: self.searcher_config,
             ingest_api_config: self.ingest_api_config,
             jaeger_config: self.jaeger_config,
+            janitor_config: self.janitor_config,
         };
 
         validate(&node_config)?;
@@ -353,6 +357,7 @@ impl Default for NodeConfigBuilder {
             searcher_config: SearcherConfig::default(),
             ingest_api_config: IngestApiConfig::default(),
             jaeger_config: JaegerConfig::default(),
+            janitor_config: JanitorConfig::default(),
         }
     }
 }
@@ -445,6 +450,7 @@ pub fn node_config_for_test() -> NodeConfig {
         searcher_config: SearcherConfig::default(),
         ingest_api_config: IngestApiConfig::default(),
         jaeger_config: JaegerConfig::default(),
+        janitor_config: JanitorConfig::default(),
     }
 }
 
@@ -452,8 +458,8 @@ pub fn node_config_for_test() -> NodeConfig {
 mod tests {
     use std::env;
     use std::net::Ipv4Addr;
-    use std::num::{NonZeroU64, NonZeroUsize};
-    use std::path::Path;
+    use std::num::{NonZeroU32, NonZeroU64, NonZeroUsize};
+    use std::path::{Path, PathBuf};
 
     use bytesize::ByteSize;
     use itertools::Itertools;
@@ -551,6 +557,7 @@ mod tests {
                 split_store_max_num_bytes: ByteSize::tb(1),
                 split_store_max_num_splits: 10_000,
                 max_concurrent_split_uploads: 8,
+                max_publish_retries_per_minute: NonZeroU32::new(30).unwrap(),
                 merge_concurrency: NonZeroUsize::new(2).unwrap(),
                 cpu_capacity: IndexerConfig::default_cpu_capacity(),
                 enable_cooperative_indexing: false,
@@ -561,6 +568,16 @@ mod tests {
             config.ingest_api_config,
             IngestApiConfig {
                 replication_factor: 2,
+                max_concurrent_requests: NonZeroUsize::new(64),
+                lenient_unknown_content_encoding: true,
+                decompressed_content_length_limit: Some(ByteSize::mb(100)),
+                detect_undeclared_compression: true,
+                validate_utf8_request_bodies: true,
+                brotli_dictionaries_dir: Some(PathBuf::from(
+                    "/var/lib/quickwit/brotli-dictionaries"
+                )),
+                wait_for_commit_timeout_secs: 60,
+                response_compression_preference: vec!["zstd".to_string(), "gzip".to_string()],
                 ..Default::default()
             }
         );
@@ -586,6 +603,12 @@ mod tests {
                 max_fetch_spans: NonZeroU64::new(1_000).unwrap(),
             }
         );
+        assert_eq!(
+            config.janitor_config,
+            JanitorConfig {
+                retention_policy_grace_period_secs: 600,
+            }
+        );
         Ok(())
     }
 
@@ -799,6 +822,7 @@ mod tests {
         assert_eq!(config.searcher_config, SearcherConfig::default());
         assert_eq!(config.ingest_api_config, IngestApiConfig::default());
         assert_eq!(config.jaeger_config, JaegerConfig::default());
+        assert_eq!(config.janitor_config, JanitorConfig::default());
     }
 
     #[tokio::test]
@@ -44,10 +44,10 @@ use quickwit_proto::control_plane::{
 };
 use quickwit_proto::indexing::ShardPositionsUpdate;
 use quickwit_proto::metastore::{
-    serde_utils, AddSourceRequest, CreateIndexRequest, CreateIndexResponse, DeleteIndexRequest,
-    DeleteShardsRequest, DeleteShardsSubrequest, DeleteSourceRequest, EmptyResponse,
-    FindIndexTemplateMatchesRequest, IndexTemplateMatch, MetastoreError, MetastoreResult,
-    MetastoreService, MetastoreServiceClient, ToggleSourceRequest,
+    serde_utils, AddSourceRequest, CreateIndexMode, CreateIndexRequest, CreateIndexResponse,
+    DeleteIndexRequest, DeleteShardsRequest, DeleteShardsSubrequest, DeleteSourceRequest,
+    EmptyResponse, FindIndexTemplateMatchesRequest, IndexTemplateMatch, MetastoreError,
+    MetastoreResult, MetastoreService, MetastoreServiceClient, ToggleSourceRequest,
 };
 use quickwit_proto::types::{IndexUid, NodeId, ShardId, SourceUid};
 use serde::Serialize;
@@ -215,6 +215,7 @@ impl ControlPlane {
             let create_index_request = CreateIndexRequest {
                 index_config_json,
                 source_configs_json,
+                mode: CreateIndexMode::ErrorIfExists as i32,
             };
             let create_index_future = {
                 let mut metastore = self.metastore.clone();
@@ -441,6 +442,8 @@ impl Handler<CreateIndexRequest> for ControlPlane {
         request: CreateIndexRequest,
         ctx: &ActorContext<Self>,
     ) -> Result<Self::Reply, ActorExitStatus> {
+        let create_index_mode = request.mode();
+
         let response = match self.metastore.create_index(request).await {
             Ok(response) => response,
             Err(metastore_error) => return convert_metastore_error(metastore_error),
@@ -453,6 +456,30 @@ impl Handler<CreateIndexRequest> for ControlPlane {
                     return Err(ActorExitStatus::from(anyhow::anyhow!(serde_error)));
                 }
             };
+        let index_uid = index_metadata.index_uid.clone();
+
+        if self.model.index_uid(index_uid.index_id()).as_ref() == Some(&index_uid) {
+            // The index already exists: it was either left untouched or overwritten.
+            if create_index_mode == CreateIndexMode::IfNotExists {
+                return Ok(Ok(response));
+            }
+            // The metastore deleted the shards of the overwritten index, so we must replace the
+            // index in the model and let the ingesters know.
+            let ingester_needing_resync: BTreeSet<NodeId> = self
+                .model
+                .list_shards_for_index(&index_uid)
+                .flat_map(|shard_entry| shard_entry.ingesters())
+                .collect();
+
+            self.model.delete_index(&index_uid);
+            self.model.add_index(index_metadata);
+
+            self.ingest_controller
+                .sync_with_ingesters(&ingester_needing_resync, &self.model);
+
+            self.rebuild_plan_debounced(ctx);
+            return Ok(Ok(response));
+        }
         // Now, create index can also add sources to support creating indexes automatically from
         // index and source config templates.
         let should_rebuild_plan = !index_metadata.sources.is_empty();
@@ -808,6 +835,86 @@ mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_control_plane_create_index_overwrite() {
+        let universe = Universe::with_accelerated_time();
+        let self_node_id: NodeId = "test-node".into();
+        let indexer_pool = IndexerPool::default();
+        let ingester_pool = IngesterPool::default();
+
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_list_indexes_metadata()
+            .returning(|_| {
+                let mut index_metadata = IndexMetadata::for_test("test-index", "ram:///test-index");
+                index_metadata
+                    .add_source(SourceConfig::ingest_v2())
+                    .unwrap();
+                Ok(
+                    ListIndexesMetadataResponse::try_from_indexes_metadata(vec![index_metadata])
+                        .unwrap(),
+                )
+            });
+        mock_metastore.expect_list_shards().returning(|_| {
+            let subresponses = vec![ListShardsSubresponse {
+                index_uid: "test-index:0".to_string(),
+                source_id: INGEST_V2_SOURCE_ID.to_string(),
+                shards: vec![Shard {
+                    index_uid: "test-index:0".to_string(),
+                    source_id: INGEST_V2_SOURCE_ID.to_string(),
+                    shard_id: Some(ShardId::from(1)),
+                    shard_state: ShardState::Open as i32,
+                    ..Default::default()
+                }],
+            }];
+            let response = ListShardsResponse { subresponses };
+            Ok(response)
+        });
+        mock_metastore
+            .expect_create_index()
+            .withf(|create_index_request| create_index_request.mode() == CreateIndexMode::Overwrite)
+            .returning(|_| {
+                // The overwritten index keeps its UID.
+                let index_metadata = IndexMetadata::for_test("test-index", "ram:///test-index");
+                let index_metadata_json = serde_json::to_string(&index_metadata).unwrap();
+                let response = CreateIndexResponse {
+                    index_uid: index_metadata.index_uid.into(),
+                    index_metadata_json,
+                };
+                Ok(response)
+            });
+        let cluster_config = ClusterConfig::for_test();
+        let (control_plane_mailbox, _control_plane_handle) = ControlPlane::spawn(
+            &universe,
+            cluster_config,
+            self_node_id,
+            indexer_pool,
+            ingester_pool,
+            MetastoreServiceClient::from(mock_metastore),
+        );
+        let control_plane_state = control_plane_mailbox.ask(Observe).await.unwrap();
+        assert_eq!(control_plane_state.model_metrics.num_indexes, 1);
+        assert_eq!(control_plane_state.model_metrics.num_shards, 1);
+
+        let index_config = IndexConfig::for_test("test-index", "ram:///test-index");
+        let mut create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        create_index_request.set_mode(CreateIndexMode::Overwrite);
+
+        let create_index_response = control_plane_mailbox
+            .ask_for_res(create_index_request)
+            .await
+            .unwrap();
+        assert_eq!(create_index_response.index_uid, "test-index:0");
+
+        let control_plane_state = control_plane_mailbox.ask(Observe).await.unwrap();
+        assert_eq!(control_plane_state.model_metrics.num_indexes, 1);
+        assert_eq!(control_plane_state.model_metrics.num_sources, 0);
+        assert_eq!(control_plane_state.model_metrics.num_shards, 0);
+
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_control_plane_delete_index() {
         let universe = Universe::with_accelerated_time();
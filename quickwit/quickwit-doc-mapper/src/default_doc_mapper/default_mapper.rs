@@ -37,8 +37,8 @@ use tantivy::TantivyDocument as Document;
 use super::field_mapping_entry::RAW_TOKENIZER_NAME;
 use super::DefaultDocMapperBuilder;
 use crate::default_doc_mapper::mapping_tree::{build_mapping_tree, MappingNode};
-use crate::default_doc_mapper::FieldMappingType;
 pub use crate::default_doc_mapper::QuickwitJsonOptions;
+use crate::default_doc_mapper::{CoercionMode, FieldMappingEntry, FieldMappingType};
 use crate::doc_mapper::{JsonObject, Partition};
 use crate::query_builder::build_query;
 use crate::routing_expression::RoutingExpr;
@@ -87,6 +87,8 @@ pub struct DefaultDocMapper {
     required_fields: Vec<Field>,
     /// Defines how unmapped fields should be handle.
     mode: Mode,
+    /// Defines how values that do not match the type of their field should be handled.
+    coercion_mode: CoercionMode,
     /// User-defined tokenizers.
     tokenizer_entries: Vec<TokenizerEntry>,
     /// Tokenizer manager.
@@ -140,6 +142,24 @@ fn validate_timestamp_field(
     Ok(())
 }
 
+/// Disables the coercion of the values of the numeric fields, including the fields nested in
+/// objects.
+fn disable_coercion(field_mappings: &mut [FieldMappingEntry]) {
+    for field_mapping in field_mappings {
+        match &mut field_mapping.mapping_type {
+            FieldMappingType::I64(numeric_options, _)
+            | FieldMappingType::U64(numeric_options, _)
+            | FieldMappingType::F64(numeric_options, _) => {
+                numeric_options.coerce = false;
+            }
+            FieldMappingType::Object(object_options) => {
+                disable_coercion(&mut object_options.field_mappings);
+            }
+            _ => {}
+        }
+    }
+}
+
 impl TryFrom<DefaultDocMapperBuilder> for DefaultDocMapper {
     type Error = anyhow::Error;
 
@@ -155,7 +175,13 @@ impl TryFrom<DefaultDocMapperBuilder> for DefaultDocMapper {
         };
 
         // Adding regular fields.
-        let field_mappings = build_mapping_tree(&builder.field_mappings, &mut schema_builder)?;
+        let field_mappings = if builder.coercion_mode.is_lenient() {
+            build_mapping_tree(&builder.field_mappings, &mut schema_builder)?
+        } else {
+            let mut strict_field_mappings = builder.field_mappings.clone();
+            disable_coercion(&mut strict_field_mappings);
+            build_mapping_tree(&strict_field_mappings, &mut schema_builder)?
+        };
         let source_field = if builder.store_source {
             Some(schema_builder.add_json_field(SOURCE_FIELD_NAME, STORED))
         } else {
@@ -260,6 +286,7 @@ impl TryFrom<DefaultDocMapperBuilder> for DefaultDocMapper {
             partition_key,
             max_num_partitions: builder.max_num_partitions,
             mode: builder.mode,
+            coercion_mode: builder.coercion_mode,
             tokenizer_entries: builder.tokenizers,
             tokenizer_manager,
         })
@@ -364,6 +391,7 @@ impl From<DefaultDocMapper> for DefaultDocMapperBuilder {
             tag_fields: default_doc_mapper.tag_field_names.into_iter().collect(),
             default_search_fields: default_doc_mapper.default_search_field_names,
             mode: default_doc_mapper.mode,
+            coercion_mode: default_doc_mapper.coercion_mode,
             partition_key: partition_key_opt,
             max_num_partitions: default_doc_mapper.max_num_partitions,
             tokenizers: default_doc_mapper.tokenizer_entries,
@@ -1515,6 +1543,69 @@ mod tests {
         assert_eq!(doc.len(), 0);
     }
 
+    #[test]
+    fn test_lenient_coercion_mode_coerces_type_mismatch() {
+        let default_doc_mapper: DefaultDocMapper = serde_json::from_str(
+            r#"{
+            "field_mappings": [
+                {
+                    "name": "some_obj",
+                    "type": "object",
+                    "field_mappings": [
+                        {
+                            "name": "count",
+                            "type": "u64"
+                        }
+                    ]
+                }
+            ],
+            "coercion_mode": "lenient"
+        }"#,
+        )
+        .unwrap();
+        let (_, doc) = default_doc_mapper
+            .doc_from_json_str(r#"{ "some_obj": { "count": "42" } }"#)
+            .unwrap();
+        let count_field = default_doc_mapper
+            .schema
+            .get_field("some_obj.count")
+            .unwrap();
+        assert_eq!(doc.get_first(count_field), Some(&TantivyValue::U64(42)));
+    }
+
+    #[test]
+    fn test_strict_coercion_mode_rejects_type_mismatch() {
+        let default_doc_mapper: DefaultDocMapper = serde_json::from_str(
+            r#"{
+            "field_mappings": [
+                {
+                    "name": "some_obj",
+                    "type": "object",
+                    "field_mappings": [
+                        {
+                            "name": "count",
+                            "type": "u64",
+                            "coerce": true
+                        }
+                    ]
+                }
+            ],
+            "coercion_mode": "strict"
+        }"#,
+        )
+        .unwrap();
+        assert!(default_doc_mapper
+            .doc_from_json_str(r#"{ "some_obj": { "count": 42 } }"#)
+            .is_ok());
+        let parsing_err = default_doc_mapper
+            .doc_from_json_str(r#"{ "some_obj": { "count": "42" } }"#)
+            .err()
+            .unwrap();
+        assert!(
+            matches!(parsing_err, DocParsingError::ValueError(field_name, _) if field_name == "some_obj.count")
+        );
+    }
+
     #[test]
     fn test_dymamic_mode_simple() {
         let default_doc_mapper: DefaultDocMapper =
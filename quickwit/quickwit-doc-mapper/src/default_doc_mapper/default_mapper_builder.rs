@@ -80,6 +80,10 @@ pub struct DefaultDocMapperBuilder {
     /// User-defined tokenizers.
     #[serde(default)]
     pub tokenizers: Vec<TokenizerEntry>,
+    /// Defines how values that do not match the type of their field should be handled.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "CoercionMode::is_lenient")]
+    pub coercion_mode: CoercionMode,
 }
 
 /// Defines how an unmapped field should be handled.
@@ -151,6 +155,28 @@ pub enum ModeType {
     Dynamic,
 }
 
+/// `CoercionMode` describing how values that do not match the type of their field should be
+/// handled.
+#[derive(Clone, Copy, Default, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CoercionMode {
+    /// Lenient mode: values are coerced to the type of their field whenever possible, unless
+    /// coercion is disabled in the field mapping, e.g. the JSON string `"42"` is indexed as the
+    /// number `42` in a `u64` field.
+    #[default]
+    Lenient,
+    /// Strict mode: when parsing a document with a value that does not match the type of its
+    /// field, an error is yielded, regardless of the `coerce` parameter of the field mapping.
+    Strict,
+}
+
+impl CoercionMode {
+    /// Returns whether values should be coerced to the type of their field.
+    pub fn is_lenient(&self) -> bool {
+        *self == CoercionMode::Lenient
+    }
+}
+
 #[cfg(test)]
 impl Default for DefaultDocMapperBuilder {
     fn default() -> Self {
@@ -178,6 +204,7 @@ mod tests {
         assert!(default_mapper_builder.field_mappings.is_empty());
         assert!(default_mapper_builder.tag_fields.is_empty());
         assert_eq!(default_mapper_builder.mode.mode_type(), ModeType::Dynamic);
+        assert_eq!(default_mapper_builder.coercion_mode, CoercionMode::Lenient);
         assert_eq!(default_mapper_builder.store_source, false);
         assert!(default_mapper_builder.timestamp_field.is_none());
     }
@@ -35,9 +35,9 @@ mod routing_expression;
 pub mod tag_pruning;
 
 pub use default_doc_mapper::{
-    analyze_text, BinaryFormat, DefaultDocMapper, DefaultDocMapperBuilder, FieldMappingEntry,
-    FieldMappingType, Mode, ModeType, QuickwitBytesOptions, QuickwitJsonOptions, TokenizerConfig,
-    TokenizerEntry,
+    analyze_text, BinaryFormat, CoercionMode, DefaultDocMapper, DefaultDocMapperBuilder,
+    FieldMappingEntry, FieldMappingType, Mode, ModeType, QuickwitBytesOptions, QuickwitJsonOptions,
+    TokenizerConfig, TokenizerEntry,
 };
 use default_doc_mapper::{
     FastFieldOptions, FieldMappingEntryForSerialization, IndexRecordOptionSchema,
@@ -73,6 +73,7 @@ pub enum Cardinality {
 
 #[derive(utoipa::OpenApi)]
 #[openapi(components(schemas(
+    CoercionMode,
     FastFieldOptions,
     FieldMappingEntryForSerialization,
     IndexRecordOptionSchema,
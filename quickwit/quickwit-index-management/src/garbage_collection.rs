@@ -348,9 +348,11 @@ pub async fn delete_splits_from_storage_and_metastore(
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
     use std::time::Duration;
 
     use itertools::Itertools;
+    use quickwit_common::uri::Uri;
     use quickwit_common::ServiceStream;
     use quickwit_config::IndexConfig;
     use quickwit_metastore::{
@@ -360,7 +362,7 @@ mod tests {
     use quickwit_proto::metastore::{CreateIndexRequest, EntityKind, StageSplitsRequest};
     use quickwit_proto::types::IndexUid;
     use quickwit_storage::{
-        storage_for_test, BulkDeleteError, DeleteFailure, MockStorage, PutPayload,
+        storage_for_test, BulkDeleteError, DeleteFailure, MockStorage, PutPayload, StorageResolver,
     };
 
     use super::*;
@@ -669,6 +671,69 @@ mod tests {
             .is_empty());
     }
 
+    #[tokio::test]
+    async fn test_delete_splits_from_storage_and_metastore_local_file_storage() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage_uri = Uri::from_str(&format!("file://{}", temp_dir.path().display())).unwrap();
+        let storage = StorageResolver::unconfigured()
+            .resolve(&storage_uri)
+            .await
+            .unwrap();
+        let mut metastore = metastore_for_test();
+
+        let index_id = "test-delete-splits-local-file--index";
+        let index_config = IndexConfig::for_test(index_id, storage_uri.as_str());
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        let index_uid: IndexUid = metastore
+            .create_index(create_index_request)
+            .await
+            .unwrap()
+            .index_uid
+            .into();
+
+        let split_id = "test-delete-splits-local-file--split";
+        let split_metadata = SplitMetadata {
+            split_id: split_id.to_string(),
+            index_uid: index_uid.clone(),
+            ..Default::default()
+        };
+        let stage_splits_request =
+            StageSplitsRequest::try_from_split_metadata(index_uid.clone(), &split_metadata)
+                .unwrap();
+        metastore.stage_splits(stage_splits_request).await.unwrap();
+        let mark_splits_for_deletion =
+            MarkSplitsForDeletionRequest::new(index_uid.clone(), vec![split_id.to_string()]);
+        metastore
+            .mark_splits_for_deletion(mark_splits_for_deletion)
+            .await
+            .unwrap();
+
+        let split_path_str = format!("{split_id}.split");
+        let split_path = Path::new(&split_path_str);
+        let payload: Box<dyn PutPayload> = Box::new(b"split-payload".to_vec());
+        storage.put(split_path, payload).await.unwrap();
+        assert!(temp_dir.path().join(split_path).try_exists().unwrap());
+
+        let split_bytes = storage.get_all(split_path).await.unwrap();
+        assert_eq!(split_bytes.as_slice(), b"split-payload");
+
+        let deleted_split_infos = delete_splits_from_storage_and_metastore(
+            index_uid,
+            storage.clone(),
+            metastore,
+            vec![split_metadata],
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(deleted_split_infos.len(), 1);
+        assert_eq!(deleted_split_infos[0].split_id, split_id);
+        assert!(!storage.exists(split_path).await.unwrap());
+        assert!(!temp_dir.path().join(split_path).try_exists().unwrap());
+    }
+
     #[tokio::test]
     async fn test_delete_splits_from_storage_and_metastore_storage_error() {
         let mut mock_storage = MockStorage::new();
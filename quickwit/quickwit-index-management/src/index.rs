@@ -21,6 +21,7 @@ use std::path::Path;
 use std::time::Duration;
 
 use quickwit_common::fs::{empty_dir, get_cache_directory_path};
+use quickwit_common::pubsub::EventBroker;
 use quickwit_config::{validate_identifier, IndexConfig, SourceConfig};
 use quickwit_indexing::check_source_connectivity;
 use quickwit_metastore::{
@@ -29,10 +30,11 @@ use quickwit_metastore::{
     SplitMetadata, SplitState,
 };
 use quickwit_proto::metastore::{
-    serde_utils, AddSourceRequest, CreateIndexRequest, DeleteIndexRequest, EntityKind,
-    IndexMetadataRequest, ListSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError,
-    MetastoreService, MetastoreServiceClient, ResetSourceCheckpointRequest,
+    serde_utils, AddSourceRequest, CreateIndexMode, CreateIndexRequest, DeleteIndexRequest,
+    EntityKind, IndexMetadataRequest, ListSplitsRequest, MarkSplitsForDeletionRequest,
+    MetastoreError, MetastoreService, MetastoreServiceClient, ResetSourceCheckpointRequest,
 };
+use quickwit_proto::search::ReportSplitsRequest;
 use quickwit_proto::types::{IndexUid, SplitId};
 use quickwit_proto::{ServiceError, ServiceErrorCode};
 use quickwit_storage::{StorageResolver, StorageResolverError};
@@ -81,6 +83,7 @@ impl ServiceError for IndexServiceError {
 pub struct IndexService {
     metastore: MetastoreServiceClient,
     storage_resolver: StorageResolver,
+    event_broker: EventBroker,
 }
 
 impl IndexService {
@@ -89,37 +92,33 @@ impl IndexService {
         Self {
             metastore,
             storage_resolver,
+            event_broker: EventBroker::default(),
         }
     }
 
+    /// Sets the event broker on which the IDs of the deleted splits are published, so that
+    /// searchers evict them from their split cache.
+    pub fn with_event_broker(mut self, event_broker: EventBroker) -> Self {
+        self.event_broker = event_broker;
+        self
+    }
+
     pub fn metastore(&self) -> MetastoreServiceClient {
         self.metastore.clone()
     }
 
-    /// Creates an index from `IndexConfig`.
+    /// Creates an index from `IndexConfig`. `create_index_mode` tells what to do when the index
+    /// already exists: the metastore either fails, returns the existing index, or overwrites it
+    /// in a single operation, marking its splits for deletion.
     pub async fn create_index(
         &mut self,
         index_config: IndexConfig,
-        overwrite: bool,
+        create_index_mode: CreateIndexMode,
     ) -> Result<IndexMetadata, IndexServiceError> {
         validate_storage_uri(&self.storage_resolver, &index_config)
             .await
             .map_err(IndexServiceError::InvalidConfig)?;
 
-        // Delete existing index if it exists.
-        if overwrite {
-            match self.delete_index(&index_config.index_id, false).await {
-                Ok(_)
-                | Err(IndexServiceError::Metastore(MetastoreError::NotFound(
-                    EntityKind::Index { .. },
-                ))) => {
-                    // Ignore index not found error.
-                }
-                Err(error) => {
-                    return Err(error);
-                }
-            }
-        }
         let mut metastore = self.metastore.clone();
 
         let index_config_json = serde_utils::to_json_str(&index_config)?;
@@ -133,6 +132,7 @@ impl IndexService {
         let create_index_request = CreateIndexRequest {
             index_config_json,
             source_configs_json,
+            mode: create_index_mode as i32,
         };
         let create_index_response = metastore.create_index(create_index_request).await?;
         let index_metadata = create_index_response.deserialize_index_metadata()?;
@@ -208,6 +208,8 @@ impl IndexService {
             None,
         )
         .await?;
+        self.report_deleted_splits(&deleted_splits);
+
         let delete_index_request = DeleteIndexRequest {
             index_uid: index_uid.to_string(),
         };
@@ -253,6 +255,9 @@ impl IndexService {
         )
         .await?;
 
+        if !dry_run {
+            self.report_deleted_splits(&deleted_entries.removed_split_entries);
+        }
         Ok(deleted_entries)
     }
 
@@ -294,7 +299,7 @@ impl IndexService {
             .mark_splits_for_deletion(mark_splits_for_deletion_request)
             .await?;
         // FIXME: return an error.
-        if let Err(err) = delete_splits_from_storage_and_metastore(
+        match delete_splits_from_storage_and_metastore(
             index_uid.clone(),
             storage,
             self.metastore.clone(),
@@ -303,7 +308,10 @@ impl IndexService {
         )
         .await
         {
-            error!(metastore_endpoints=?self.metastore.endpoints(), index_id=%index_id, error=?err, "failed to delete all the split files during garbage collection");
+            Ok(deleted_splits) => self.report_deleted_splits(&deleted_splits),
+            Err(err) => {
+                error!(metastore_endpoints=?self.metastore.endpoints(), index_id=%index_id, error=?err, "failed to delete all the split files during garbage collection");
+            }
         }
         for source_id in index_metadata.sources.keys() {
             let reset_source_checkpoint_request = ResetSourceCheckpointRequest {
@@ -317,6 +325,21 @@ impl IndexService {
         Ok(())
     }
 
+    /// Publishes the IDs of deleted splits, so that searchers evict them from their split cache.
+    fn report_deleted_splits(&self, deleted_splits: &[SplitInfo]) {
+        if deleted_splits.is_empty() {
+            return;
+        }
+        let deleted_split_ids = deleted_splits
+            .iter()
+            .map(|split_info| split_info.split_id.clone())
+            .collect();
+        self.event_broker.publish(ReportSplitsRequest {
+            report_splits: Vec::new(),
+            deleted_split_ids,
+        });
+    }
+
     /// Adds a source to an index identified by its UID.
     pub async fn add_source(
         &mut self,
@@ -426,7 +449,7 @@ mod tests {
         let index_uri = "ram://indexes/test-index";
         let index_config = IndexConfig::for_test(index_id, index_uri);
         let index_metadata_0 = index_service
-            .create_index(index_config.clone(), false)
+            .create_index(index_config.clone(), CreateIndexMode::ErrorIfExists)
             .await
             .unwrap();
         assert_eq!(index_metadata_0.index_id(), index_id);
@@ -443,7 +466,7 @@ mod tests {
             .is_ok());
 
         let error = index_service
-            .create_index(index_config.clone(), false)
+            .create_index(index_config.clone(), CreateIndexMode::ErrorIfExists)
             .await
             .unwrap_err();
         let IndexServiceError::Metastore(inner_error) = error else {
@@ -454,12 +477,35 @@ mod tests {
         );
 
         let index_metadata_1 = index_service
-            .create_index(index_config, true)
+            .create_index(index_config, CreateIndexMode::Overwrite)
             .await
             .unwrap();
         assert_eq!(index_metadata_1.index_id(), index_id);
         assert_eq!(index_metadata_1.index_uri(), &index_uri);
-        assert!(index_metadata_0.index_uid != index_metadata_1.index_uid);
+        // The overwritten index keeps its UID.
+        assert_eq!(index_metadata_0.index_uid, index_metadata_1.index_uid);
+    }
+
+    #[tokio::test]
+    async fn test_create_index_if_not_exists() {
+        let metastore = metastore_for_test();
+        let storage_resolver = StorageResolver::for_test();
+        let mut index_service = IndexService::new(metastore, storage_resolver);
+        let index_id = "test-index";
+        let index_uri = "ram://indexes/test-index";
+        let index_config = IndexConfig::for_test(index_id, index_uri);
+        let index_metadata_0 = index_service
+            .create_index(index_config.clone(), CreateIndexMode::IfNotExists)
+            .await
+            .unwrap();
+        assert_eq!(index_metadata_0.index_id(), index_id);
+
+        // The existing index is left untouched.
+        let index_metadata_1 = index_service
+            .create_index(index_config, CreateIndexMode::IfNotExists)
+            .await
+            .unwrap();
+        assert_eq!(index_metadata_0.index_uid, index_metadata_1.index_uid);
     }
 
     #[tokio::test]
@@ -470,12 +516,19 @@ mod tests {
             .resolve(&Uri::for_test("ram://indexes/test-index"))
             .await
             .unwrap();
-        let mut index_service = IndexService::new(metastore.clone(), storage_resolver);
+        let event_broker = EventBroker::default();
+        let (report_splits_tx, mut report_splits_rx) = tokio::sync::mpsc::unbounded_channel();
+        let _subscription_handle =
+            event_broker.subscribe(move |report_splits_request: ReportSplitsRequest| {
+                report_splits_tx.send(report_splits_request).unwrap();
+            });
+        let mut index_service =
+            IndexService::new(metastore.clone(), storage_resolver).with_event_broker(event_broker);
         let index_id = "test-index";
         let index_uri = "ram://indexes/test-index";
         let index_config = IndexConfig::for_test(index_id, index_uri);
         let index_uid = index_service
-            .create_index(index_config.clone(), false)
+            .create_index(index_config.clone(), CreateIndexMode::ErrorIfExists)
             .await
             .unwrap()
             .index_uid;
@@ -521,5 +574,9 @@ mod tests {
             .unwrap();
         assert!(splits.is_empty());
         assert!(!storage.exists(split_path).await.unwrap());
+
+        // The deleted split is reported so that searchers evict it from their split cache.
+        let report_splits_request = report_splits_rx.recv().await.unwrap();
+        assert_eq!(report_splits_request.deleted_split_ids, [split_id]);
     }
 }
@@ -20,6 +20,7 @@
 use std::string::FromUtf8Error;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::{bail, Context};
 use async_trait::async_trait;
@@ -515,6 +516,8 @@ impl Handler<RawDocBatch> for DocProcessor {
             docs: processed_docs,
             checkpoint_delta: raw_doc_batch.checkpoint_delta,
             force_commit: raw_doc_batch.force_commit,
+            backfill: raw_doc_batch.backfill,
+            processed_at: Instant::now(),
         };
         ctx.send_message(&self.indexer_mailbox, processed_doc_batch)
             .await?;
@@ -650,6 +653,58 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_doc_processor_extracts_timestamps() -> anyhow::Result<()> {
+        let universe = Universe::with_accelerated_time();
+        let doc_mapper = Arc::new(default_doc_mapper_for_test());
+        let (indexer_mailbox, indexer_inbox) = universe.create_test_mailbox();
+        let doc_processor = DocProcessor::try_new(
+            "my-index".to_string(),
+            "my-source".to_string(),
+            doc_mapper,
+            indexer_mailbox,
+            None,
+            SourceInputFormat::Json,
+        )
+        .unwrap();
+        let (doc_processor_mailbox, doc_processor_handle) =
+            universe.spawn_builder().spawn(doc_processor);
+        doc_processor_mailbox
+            .send_message(RawDocBatch::for_test(
+                &[
+                    r#"{"body": "rfc3339", "timestamp": "2021-08-13T06:44:22Z"}"#,
+                    r#"{"body": "unix epoch", "timestamp": 1628837063}"#,
+                    r#"{"body": "unparseable", "timestamp": "yesterday"}"#,
+                    r#"{"body": "missing"}"#,
+                ],
+                0..4,
+            ))
+            .await?;
+        let counters = doc_processor_handle
+            .process_pending_and_observe()
+            .await
+            .state;
+        assert_eq!(counters.num_valid_docs.load(Ordering::Relaxed), 2);
+        assert_eq!(counters.num_doc_parsing_errors.load(Ordering::Relaxed), 2);
+
+        let batches: Vec<ProcessedDocBatch> = indexer_inbox.drain_for_test_typed();
+        assert_eq!(batches.len(), 1);
+        let timestamps: Vec<Option<DateTime>> = batches[0]
+            .docs
+            .iter()
+            .map(|doc| doc.timestamp_opt)
+            .collect();
+        assert_eq!(
+            timestamps,
+            [
+                Some(DateTime::from_timestamp_secs(1628837062)),
+                Some(DateTime::from_timestamp_secs(1628837063)),
+            ]
+        );
+        universe.assert_quit().await;
+        Ok(())
+    }
+
     const DOCMAPPER_WITH_PARTITION_JSON: &str = r#"
         {
             "tag_fields": ["tenant"],
@@ -696,6 +751,7 @@ mod tests {
                 ],
                 checkpoint_delta: SourceCheckpointDelta::from_range(0..2),
                 force_commit: false,
+                backfill: false,
             })
             .await?;
         universe
@@ -845,6 +901,7 @@ mod tests {
             docs: vec![raw_doc],
             checkpoint_delta: SourceCheckpointDelta::from_range(0..2),
             force_commit: false,
+            backfill: false,
         };
         doc_processor_mailbox
             .send_message(raw_doc_batch)
@@ -927,6 +984,7 @@ mod tests {
             docs: vec![raw_doc],
             checkpoint_delta: SourceCheckpointDelta::from_range(0..2),
             force_commit: false,
+            backfill: false,
         };
         doc_processor_mailbox
             .send_message(raw_doc_batch)
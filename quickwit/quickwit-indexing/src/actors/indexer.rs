@@ -184,6 +184,7 @@ impl IndexerState {
 
     async fn create_workbench(
         &self,
+        backfill: bool,
         ctx: &ActorContext<Indexer>,
     ) -> anyhow::Result<IndexingWorkbench> {
         let workbench_id = Ulid::new();
@@ -237,6 +238,7 @@ impl IndexerState {
             publish_token_opt,
             last_delete_opstamp,
             memory_usage: ByteSize(0),
+            backfill,
         };
         Ok(workbench)
     }
@@ -248,10 +250,11 @@ impl IndexerState {
     async fn get_or_create_workbench<'a>(
         &'a self,
         indexing_workbench_opt: &'a mut Option<IndexingWorkbench>,
+        backfill: bool,
         ctx: &'a ActorContext<Indexer>,
     ) -> anyhow::Result<&'a mut IndexingWorkbench> {
         if indexing_workbench_opt.is_none() {
-            let indexing_workbench = self.create_workbench(ctx).await?;
+            let indexing_workbench = self.create_workbench(backfill, ctx).await?;
             let commit_timeout_message = CommitTimeout {
                 workbench_id: indexing_workbench.workbench_id,
             };
@@ -283,7 +286,7 @@ impl IndexerState {
             memory_usage,
             ..
         } = self
-            .get_or_create_workbench(indexing_workbench_opt, ctx)
+            .get_or_create_workbench(indexing_workbench_opt, batch.backfill, ctx)
             .await?;
         if publish_lock.is_dead() {
             // Release indexing permit early.
@@ -296,6 +299,7 @@ impl IndexerState {
             .context("batch delta does not follow indexer checkpoint")?;
         let mut memory_usage_delta: u64 = 0;
         counters.num_doc_batches_in_workbench += 1;
+        let processed_at = batch.processed_at;
         for doc in batch.docs {
             let ProcessedDoc {
                 doc,
@@ -315,6 +319,11 @@ impl IndexerState {
             let mem_usage_before = indexed_split.index_writer.mem_usage() as u64;
             indexed_split.split_attrs.uncompressed_docs_size_in_bytes += num_bytes as u64;
             indexed_split.split_attrs.num_docs += 1;
+            // Batches are indexed in order, so the first batch of a split holds its oldest docs.
+            indexed_split
+                .split_attrs
+                .oldest_doc_instant_opt
+                .get_or_insert(processed_at);
             if let Some(timestamp) = timestamp_opt {
                 record_timestamp(timestamp, &mut indexed_split.split_attrs.time_range);
             }
@@ -354,6 +363,9 @@ struct IndexingWorkbench {
     last_delete_opstamp: u64,
     // Number of bytes declared as used by tantivy.
     memory_usage: ByteSize,
+    // Backfill workbenches only index backfill batches. Their checkpoint delta only covers the
+    // backfill partitions of the source, so the live partitions are left untouched.
+    backfill: bool,
 }
 
 pub struct Indexer {
@@ -583,6 +595,14 @@ impl Indexer {
     ) -> Result<(), ActorExitStatus> {
         fail_point!("indexer:batch:before");
         let force_commit = batch.force_commit;
+
+        // Backfill documents and live documents never end up in the same split.
+        if let Some(indexing_workbench) = &self.indexing_workbench_opt {
+            if indexing_workbench.backfill != batch.backfill {
+                self.send_to_serializer(CommitTrigger::BackfillBoundary, ctx)
+                    .await?;
+            }
+        }
         self.indexer_state
             .index_batch(
                 batch,
@@ -623,6 +643,7 @@ impl Indexer {
             publish_token_opt,
             batch_parent_span,
             indexing_permit,
+            backfill,
             ..
         }) = self.indexing_workbench_opt.take()
         else {
@@ -636,6 +657,11 @@ impl Indexer {
         if let Some(other_split) = other_indexed_split_opt {
             splits.push(other_split)
         }
+        if backfill {
+            for split in &mut splits {
+                split.split_attrs.backfill = true;
+            }
+        }
 
         // Avoid producing empty split, but still update the checkpoint if it is not empty to avoid
         // reprocessing the same faulty documents.
@@ -693,9 +719,9 @@ mod tests {
 
     use quickwit_actors::Universe;
     use quickwit_doc_mapper::{default_doc_mapper_for_test, DefaultDocMapper};
-    use quickwit_metastore::checkpoint::SourceCheckpointDelta;
+    use quickwit_metastore::checkpoint::{PartitionId, SourceCheckpointDelta};
     use quickwit_proto::metastore::{EmptyResponse, LastDeleteOpstampResponse};
-    use quickwit_proto::types::{IndexUid, PipelineUid};
+    use quickwit_proto::types::{IndexUid, PipelineUid, Position};
     use tantivy::{doc, DateTime};
 
     use super::*;
@@ -769,6 +795,7 @@ mod tests {
             index_serializer_mailbox,
         );
         let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
+        let first_batch_processed_at = Instant::now();
         indexer_mailbox
             .send_message(ProcessedDocBatch {
                 docs: vec![
@@ -793,6 +820,8 @@ mod tests {
                 ],
                 checkpoint_delta: SourceCheckpointDelta::from_range(4..6),
                 force_commit: false,
+                backfill: false,
+                processed_at: first_batch_processed_at,
             })
             .await?;
         indexer_mailbox
@@ -819,6 +848,8 @@ mod tests {
                 ],
                 checkpoint_delta: SourceCheckpointDelta::from_range(6..8),
                 force_commit: false,
+                backfill: false,
+                processed_at: Instant::now(),
             })
             .await?;
         indexer_mailbox
@@ -834,6 +865,8 @@ mod tests {
                 }],
                 checkpoint_delta: SourceCheckpointDelta::from_range(8..9),
                 force_commit: false,
+                backfill: false,
+                processed_at: Instant::now(),
             })
             .await?;
         let indexer_counters = indexer_handle.process_pending_and_observe().await.state;
@@ -852,6 +885,10 @@ mod tests {
         let batch = messages.into_iter().next().unwrap();
         assert_eq!(batch.commit_trigger, CommitTrigger::NumDocsLimit);
         assert_eq!(batch.splits[0].split_attrs.num_docs, 4);
+        assert_eq!(
+            batch.splits[0].split_attrs.oldest_doc_instant_opt,
+            Some(first_batch_processed_at)
+        );
         for split in batch.splits.iter() {
             assert_eq!(split.split_attrs.delete_opstamp, last_delete_opstamp);
         }
@@ -925,6 +962,8 @@ mod tests {
                     docs: vec![make_doc(i)],
                     checkpoint_delta: SourceCheckpointDelta::from_range(i..i + 1),
                     force_commit: false,
+                    backfill: false,
+                    processed_at: Instant::now(),
                 })
                 .await?;
             let output_messages: Vec<IndexedSplitBatchBuilder> =
@@ -998,6 +1037,8 @@ mod tests {
                         }],
                         force_commit: false,
                         checkpoint_delta: SourceCheckpointDelta::from_range(position..position + 1),
+                        backfill: false,
+                        processed_at: Instant::now(),
                     })
                     .await
                     .is_ok()
@@ -1076,6 +1117,8 @@ mod tests {
                 }],
                 checkpoint_delta: SourceCheckpointDelta::from_range(8..9),
                 force_commit: false,
+                backfill: false,
+                processed_at: Instant::now(),
             })
             .await
             .unwrap();
@@ -1163,6 +1206,8 @@ mod tests {
                 }],
                 checkpoint_delta: SourceCheckpointDelta::from_range(8..9),
                 force_commit: false,
+                backfill: false,
+                processed_at: Instant::now(),
             })
             .await
             .unwrap();
@@ -1257,6 +1302,8 @@ mod tests {
                 ],
                 checkpoint_delta: SourceCheckpointDelta::from_range(8..9),
                 force_commit: false,
+                backfill: false,
+                processed_at: Instant::now(),
             })
             .await?;
 
@@ -1340,6 +1387,8 @@ mod tests {
                     }],
                     checkpoint_delta: SourceCheckpointDelta::from_range(partition..partition + 1),
                     force_commit: false,
+                    backfill: false,
+                    processed_at: Instant::now(),
                 })
                 .await
                 .unwrap();
@@ -1418,6 +1467,8 @@ mod tests {
                     }],
                     checkpoint_delta: SourceCheckpointDelta::from_range(0..1),
                     force_commit: false,
+                    backfill: false,
+                    processed_at: Instant::now(),
                 })
                 .await
                 .unwrap();
@@ -1489,6 +1540,8 @@ mod tests {
                 }],
                 checkpoint_delta: SourceCheckpointDelta::from_range(0..1),
                 force_commit: false,
+                backfill: false,
+                processed_at: Instant::now(),
             })
             .await
             .unwrap();
@@ -1545,6 +1598,8 @@ mod tests {
                 }],
                 checkpoint_delta: SourceCheckpointDelta::from_range(0..1),
                 force_commit: true,
+                backfill: false,
+                processed_at: Instant::now(),
             })
             .await
             .unwrap();
@@ -1566,6 +1621,117 @@ mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_indexer_isolates_backfill_batches() {
+        let universe = Universe::with_accelerated_time();
+        let pipeline_id = IndexingPipelineId {
+            index_uid: IndexUid::new_with_random_ulid("test-index"),
+            source_id: "test-source".to_string(),
+            node_id: "test-node".to_string(),
+            pipeline_uid: PipelineUid::default(),
+        };
+        let doc_mapper: Arc<dyn DocMapper> =
+            Arc::new(serde_json::from_str::<DefaultDocMapper>(DOCMAPPER_SIMPLE_JSON).unwrap());
+        let body_field = doc_mapper.schema().get_field("body").unwrap();
+        let indexing_directory = TempDirectory::for_test();
+        let indexing_settings = IndexingSettings::for_test();
+        let mut metastore = MetastoreServiceClient::mock();
+        metastore
+            .expect_last_delete_opstamp()
+            .times(2)
+            .returning(move |_last_delete_opstamp_request| Ok(LastDeleteOpstampResponse::new(10)));
+        metastore.expect_publish_splits().never();
+        let (index_serializer_mailbox, index_serializer_inbox) = universe.create_test_mailbox();
+        let indexer = Indexer::new(
+            pipeline_id,
+            doc_mapper,
+            MetastoreServiceClient::from(metastore),
+            indexing_directory,
+            indexing_settings,
+            None,
+            index_serializer_mailbox,
+        );
+        let (indexer_mailbox, indexer_handle) = universe.spawn_builder().spawn(indexer);
+        indexer_mailbox
+            .send_message(ProcessedDocBatch {
+                docs: vec![ProcessedDoc {
+                    doc: doc!(body_field=>"live doc"),
+                    timestamp_opt: None,
+                    partition: 0,
+                    num_bytes: 30,
+                }],
+                checkpoint_delta: SourceCheckpointDelta::from_range(0..1),
+                force_commit: false,
+                backfill: false,
+                processed_at: Instant::now(),
+            })
+            .await
+            .unwrap();
+        indexer_mailbox
+            .send_message(ProcessedDocBatch {
+                docs: vec![
+                    ProcessedDoc {
+                        doc: doc!(body_field=>"backfill doc 1"),
+                        timestamp_opt: None,
+                        partition: 0,
+                        num_bytes: 30,
+                    },
+                    ProcessedDoc {
+                        doc: doc!(body_field=>"backfill doc 2"),
+                        timestamp_opt: None,
+                        partition: 0,
+                        num_bytes: 30,
+                    },
+                ],
+                checkpoint_delta: SourceCheckpointDelta::from_partition_delta(
+                    "0-backfill".into(),
+                    Position::Beginning,
+                    Position::offset(2u64),
+                )
+                .unwrap(),
+                force_commit: false,
+                backfill: true,
+                processed_at: Instant::now(),
+            })
+            .await
+            .unwrap();
+        universe
+            .send_exit_with_success(&indexer_mailbox)
+            .await
+            .unwrap();
+        let (exit_status, _indexer_counters) = indexer_handle.join().await;
+        assert!(matches!(exit_status, ActorExitStatus::Success));
+        let output_messages: Vec<IndexedSplitBatchBuilder> =
+            index_serializer_inbox.drain_for_test_typed();
+        assert_eq!(output_messages.len(), 2);
+
+        let live_batch = &output_messages[0];
+        assert_eq!(live_batch.commit_trigger, CommitTrigger::BackfillBoundary);
+        assert_eq!(live_batch.splits.len(), 1);
+        assert_eq!(live_batch.splits[0].split_attrs.num_docs, 1);
+        assert!(!live_batch.splits[0].split_attrs.backfill);
+        let live_checkpoint_delta = live_batch.checkpoint_delta_opt.as_ref().unwrap();
+        assert_eq!(
+            live_checkpoint_delta.source_delta,
+            SourceCheckpointDelta::from_range(0..1)
+        );
+
+        let backfill_batch = &output_messages[1];
+        assert_eq!(backfill_batch.commit_trigger, CommitTrigger::NoMoreDocs);
+        assert_eq!(backfill_batch.splits.len(), 1);
+        assert_eq!(backfill_batch.splits[0].split_attrs.num_docs, 2);
+        assert!(backfill_batch.splits[0].split_attrs.backfill);
+        let backfill_checkpoint_delta = backfill_batch.checkpoint_delta_opt.as_ref().unwrap();
+        assert_eq!(
+            backfill_checkpoint_delta
+                .source_delta
+                .partitions()
+                .collect::<Vec<_>>(),
+            [&PartitionId::from("0-backfill")]
+        );
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_indexer_checkpoint_on_all_failed_docs() -> anyhow::Result<()> {
         let pipeline_id = IndexingPipelineId {
@@ -1608,6 +1774,8 @@ mod tests {
                 docs: Vec::new(),
                 checkpoint_delta: SourceCheckpointDelta::from_range(4..6),
                 force_commit: false,
+                backfill: false,
+                processed_at: Instant::now(),
             })
             .await?;
         indexer_mailbox
@@ -1615,6 +1783,8 @@ mod tests {
                 docs: Vec::new(),
                 checkpoint_delta: SourceCheckpointDelta::from_range(6..8),
                 force_commit: false,
+                backfill: false,
+                processed_at: Instant::now(),
             })
             .await?;
         universe
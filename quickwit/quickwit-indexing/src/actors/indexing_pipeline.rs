@@ -18,6 +18,7 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::BTreeSet;
+use std::num::NonZeroU32;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -46,7 +47,7 @@ use tracing::{debug, error, info, instrument};
 use super::MergePlanner;
 use crate::actors::doc_processor::DocProcessor;
 use crate::actors::index_serializer::IndexSerializer;
-use crate::actors::publisher::PublisherType;
+use crate::actors::publisher::{PublisherConfig, PublisherType};
 use crate::actors::sequencer::Sequencer;
 use crate::actors::uploader::UploaderType;
 use crate::actors::{Indexer, Packager, Publisher, Uploader};
@@ -328,11 +329,16 @@ impl IndexingPipeline {
             .create_mailbox::<SourceActor>("SourceActor", QueueCapacity::Unbounded);
 
         // Publisher
-        let publisher = Publisher::new(
+        let publisher_config = PublisherConfig {
+            max_retries_per_minute: self.params.max_publish_retries_per_minute,
+            ..Default::default()
+        };
+        let publisher = Publisher::new_with_config(
             PublisherType::MainPublisher,
             self.params.metastore.clone(),
             Some(self.params.merge_planner_mailbox.clone()),
             Some(source_mailbox.clone()),
+            publisher_config,
         );
         let (publisher_mailbox, publisher_handle) = ctx
             .spawn_actor()
@@ -586,6 +592,7 @@ pub struct IndexingPipelineParams {
     pub indexing_settings: IndexingSettings,
     pub split_store: IndexingSplitStore,
     pub max_concurrent_split_uploads_index: usize,
+    pub max_publish_retries_per_minute: NonZeroU32,
     pub cooperative_indexing_permits: Option<Arc<Semaphore>>,
 
     // Merge-related parameters
@@ -604,7 +611,7 @@ pub struct IndexingPipelineParams {
 
 #[cfg(test)]
 mod tests {
-    use std::num::NonZeroUsize;
+    use std::num::{NonZeroU32, NonZeroUsize};
     use std::path::PathBuf;
     use std::sync::Arc;
 
@@ -723,6 +730,7 @@ mod tests {
             cooperative_indexing_permits: None,
             merge_planner_mailbox,
             event_broker,
+            max_publish_retries_per_minute: NonZeroU32::MIN,
         };
         let pipeline = IndexingPipeline::new(pipeline_params);
         let (_pipeline_mailbox, pipeline_handle) = universe.spawn_builder().spawn(pipeline);
@@ -830,6 +838,7 @@ mod tests {
             cooperative_indexing_permits: None,
             merge_planner_mailbox,
             event_broker: Default::default(),
+            max_publish_retries_per_minute: NonZeroU32::MIN,
         };
         let pipeline = IndexingPipeline::new(pipeline_params);
         let (_pipeline_mailbox, pipeline_handler) = universe.spawn_builder().spawn(pipeline);
@@ -900,6 +909,7 @@ mod tests {
             merge_io_throughput_limiter_opt: None,
             merge_scheduler_service: universe.get_or_spawn_one(),
             event_broker: Default::default(),
+            max_publish_retries_per_minute: NonZeroU32::MIN,
         };
         let merge_pipeline = MergePipeline::new(merge_pipeline_params, universe.spawn_ctx());
         let merge_planner_mailbox = merge_pipeline.merge_planner_mailbox().clone();
@@ -923,6 +933,7 @@ mod tests {
             cooperative_indexing_permits: None,
             merge_planner_mailbox: merge_planner_mailbox.clone(),
             event_broker: Default::default(),
+            max_publish_retries_per_minute: NonZeroU32::MIN,
         };
         let indexing_pipeline = IndexingPipeline::new(indexing_pipeline_params);
         let (_indexing_pipeline_mailbox, indexing_pipeline_handler) =
@@ -1043,6 +1054,7 @@ mod tests {
             cooperative_indexing_permits: None,
             merge_planner_mailbox,
             event_broker: Default::default(),
+            max_publish_retries_per_minute: NonZeroU32::MIN,
         };
         let pipeline = IndexingPipeline::new(pipeline_params);
         let (_pipeline_mailbox, pipeline_handler) = universe.spawn_builder().spawn(pipeline);
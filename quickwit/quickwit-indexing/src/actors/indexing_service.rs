@@ -19,6 +19,7 @@
 
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display, Formatter};
+use std::num::NonZeroU32;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -128,6 +129,7 @@ pub struct IndexingService {
     counters: IndexingServiceCounters,
     local_split_store: Arc<LocalSplitStore>,
     max_concurrent_split_uploads: usize,
+    max_publish_retries_per_minute: NonZeroU32,
     merge_pipeline_handles: HashMap<MergePipelineId, MergePipelineHandle>,
     cooperative_indexing_permits: Option<Arc<Semaphore>>,
     merge_io_throughput_limiter_opt: Option<Limiter>,
@@ -191,6 +193,7 @@ impl IndexingService {
             indexing_pipelines: Default::default(),
             counters: Default::default(),
             max_concurrent_split_uploads: indexer_config.max_concurrent_split_uploads,
+            max_publish_retries_per_minute: indexer_config.max_publish_retries_per_minute,
             merge_pipeline_handles: HashMap::new(),
             merge_io_throughput_limiter_opt,
             cooperative_indexing_permits,
@@ -304,6 +307,7 @@ impl IndexingService {
             merge_policy: merge_policy.clone(),
             merge_io_throughput_limiter_opt: self.merge_io_throughput_limiter_opt.clone(),
             max_concurrent_split_uploads: self.max_concurrent_split_uploads,
+            max_publish_retries_per_minute: self.max_publish_retries_per_minute,
             event_broker: self.event_broker.clone(),
         };
 
@@ -327,6 +331,7 @@ impl IndexingService {
             indexing_settings: index_config.indexing_settings.clone(),
             split_store,
             max_concurrent_split_uploads_index,
+            max_publish_retries_per_minute: self.max_publish_retries_per_minute,
             cooperative_indexing_permits: self.cooperative_indexing_permits.clone(),
             // Merge-related parameters
             merge_policy,
@@ -18,8 +18,9 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::BTreeSet;
-use std::ops::RangeInclusive;
+use std::ops::{Bound, RangeInclusive};
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -36,12 +37,13 @@ use quickwit_doc_mapper::DocMapper;
 use quickwit_metastore::SplitMetadata;
 use quickwit_proto::indexing::IndexingPipelineId;
 use quickwit_proto::metastore::{
-    DeleteTask, ListDeleteTasksRequest, MarkSplitsForDeletionRequest, MetastoreService,
-    MetastoreServiceClient,
+    DeleteQuery, DeleteTask, ListDeleteTasksRequest, MarkSplitsForDeletionRequest,
+    MetastoreService, MetastoreServiceClient,
 };
 use quickwit_proto::types::PipelineUid;
-use quickwit_query::get_quickwit_fastfield_normalizer_manager;
-use quickwit_query::query_ast::QueryAst;
+use quickwit_query::query_ast::{BoolQuery, QueryAst, RangeQuery};
+use quickwit_query::{get_quickwit_fastfield_normalizer_manager, JsonLiteral};
+use serde::Serialize;
 use tantivy::directory::{Advice, DirectoryClone, MmapDirectory, RamDirectory};
 use tantivy::tokenizer::TokenizerManager;
 use tantivy::{DateTime, Directory, Index, IndexMeta, IndexWriter, SegmentId, SegmentReader};
@@ -53,6 +55,32 @@ use crate::controlled_directory::ControlledDirectory;
 use crate::merge_policy::MergeOperationType;
 use crate::models::{IndexedSplit, IndexedSplitBatch, MergeScratch, PublishLock, SplitAttrs};
 
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct MergeExecutorCounters {
+    /// Number of merge or delete operations currently being executed.
+    pub num_ongoing_operations: u64,
+    /// Number of merge operations successfully executed.
+    pub num_completed_merges: u64,
+    /// Number of delete operations successfully executed.
+    pub num_completed_deletes: u64,
+}
+
+/// Counts an operation as ongoing until the guard is dropped, including when the operation fails.
+struct OngoingOperationGuard(Arc<AtomicU64>);
+
+impl OngoingOperationGuard {
+    fn new(num_ongoing_operations: Arc<AtomicU64>) -> Self {
+        num_ongoing_operations.fetch_add(1, Ordering::Relaxed);
+        Self(num_ongoing_operations)
+    }
+}
+
+impl Drop for OngoingOperationGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 #[derive(Clone)]
 pub struct MergeExecutor {
     pipeline_id: IndexingPipelineId,
@@ -60,17 +88,24 @@ pub struct MergeExecutor {
     doc_mapper: Arc<dyn DocMapper>,
     io_controls: IoControls,
     merge_packager_mailbox: Mailbox<Packager>,
+    counters: MergeExecutorCounters,
+    num_ongoing_operations: Arc<AtomicU64>,
 }
 
 #[async_trait]
 impl Actor for MergeExecutor {
-    type ObservableState = ();
+    type ObservableState = MergeExecutorCounters;
 
     fn runtime_handle(&self) -> Handle {
         RuntimeType::Blocking.get_runtime_handle()
     }
 
-    fn observable_state(&self) -> Self::ObservableState {}
+    fn observable_state(&self) -> Self::ObservableState {
+        MergeExecutorCounters {
+            num_ongoing_operations: self.num_ongoing_operations.load(Ordering::Relaxed),
+            ..self.counters.clone()
+        }
+    }
 
     fn queue_capacity(&self) -> QueueCapacity {
         QueueCapacity::Bounded(1)
@@ -93,6 +128,8 @@ impl Handler<MergeScratch> for MergeExecutor {
     ) -> Result<(), ActorExitStatus> {
         let start = Instant::now();
         let merge_task = merge_scratch.merge_task;
+        let _ongoing_operation_guard =
+            OngoingOperationGuard::new(self.num_ongoing_operations.clone());
         let indexed_split_opt: Option<IndexedSplit> = match merge_task.operation_type {
             MergeOperationType::Merge => Some(
                 self.process_merge(
@@ -122,6 +159,10 @@ impl Handler<MergeScratch> for MergeExecutor {
                 .await?
             }
         };
+        match merge_task.operation_type {
+            MergeOperationType::Merge => self.counters.num_completed_merges += 1,
+            MergeOperationType::DeleteAndMerge => self.counters.num_completed_deletes += 1,
+        }
         if let Some(indexed_split) = indexed_split_opt {
             info!(
                 merged_num_docs = %indexed_split.split_attrs.num_docs,
@@ -259,6 +300,9 @@ pub fn merge_split_attrs(
         uncompressed_docs_size_in_bytes,
         delete_opstamp,
         num_merge_ops: max_merge_ops(splits) + 1,
+        // A merge only remains a backfill split if all of its inputs are.
+        backfill: splits.iter().all(|split| split.backfill),
+        oldest_doc_instant_opt: None,
     }
 }
 
@@ -284,6 +328,8 @@ impl MergeExecutor {
             doc_mapper,
             io_controls,
             merge_packager_mailbox,
+            counters: MergeExecutorCounters::default(),
+            num_ongoing_operations: Arc::default(),
         }
     }
 
@@ -446,6 +492,8 @@ impl MergeExecutor {
                 uncompressed_docs_size_in_bytes,
                 delete_opstamp: last_delete_opstamp,
                 num_merge_ops: split.num_merge_ops,
+                backfill: split.backfill,
+                oldest_doc_instant_opt: None,
             },
             index: merged_index,
             split_scratch_directory: merge_scratch_directory,
@@ -499,12 +547,8 @@ impl MergeExecutor {
                 let delete_query = delete_task
                     .delete_query
                     .expect("A delete task must have a delete query.");
-                let query_ast: QueryAst = serde_json::from_str(&delete_query.query_ast)
-                    .context("invalid query_ast json")?;
-                // We ignore the docmapper default fields when we consider delete query.
-                // We reparse the query here defensivley, but actually, it should already have been
-                // done in the delete task rest handler.
-                let parsed_query_ast = query_ast.parse_user_query(&[]).context("invalid query")?;
+                let parsed_query_ast =
+                    delete_query_ast(&delete_query, doc_mapper.timestamp_field_name())?;
                 debug!(
                     "Delete all documents matched by query `{:?}`",
                     parsed_query_ast
@@ -541,6 +585,46 @@ impl MergeExecutor {
     }
 }
 
+/// Builds the query matching the documents to delete for `delete_query`. The splits a delete task
+/// applies to are selected by time range, but a split may straddle the bounds of the range, so the
+/// query is also restricted to documents whose timestamp is within
+/// `[start_timestamp, end_timestamp)`.
+fn delete_query_ast(
+    delete_query: &DeleteQuery,
+    timestamp_field_name_opt: Option<&str>,
+) -> anyhow::Result<QueryAst> {
+    let query_ast: QueryAst =
+        serde_json::from_str(&delete_query.query_ast).context("invalid query_ast json")?;
+    // We ignore the docmapper default fields when we consider delete query.
+    // We reparse the query here defensivley, but actually, it should already have been
+    // done in the delete task rest handler.
+    let parsed_query_ast = query_ast.parse_user_query(&[]).context("invalid query")?;
+
+    let Some(timestamp_field_name) = timestamp_field_name_opt else {
+        return Ok(parsed_query_ast);
+    };
+    if delete_query.start_timestamp.is_none() && delete_query.end_timestamp.is_none() {
+        return Ok(parsed_query_ast);
+    }
+    let time_range_query = RangeQuery {
+        field: timestamp_field_name.to_string(),
+        lower_bound: delete_query
+            .start_timestamp
+            .map(|start_timestamp| Bound::Included(JsonLiteral::Number(start_timestamp.into())))
+            .unwrap_or(Bound::Unbounded),
+        upper_bound: delete_query
+            .end_timestamp
+            .map(|end_timestamp| Bound::Excluded(JsonLiteral::Number(end_timestamp.into())))
+            .unwrap_or(Bound::Unbounded),
+    };
+    let bool_query = BoolQuery {
+        must: vec![parsed_query_ast],
+        filter: vec![time_range_query.into()],
+        ..Default::default()
+    };
+    Ok(bool_query.into())
+}
+
 fn open_index<T: Into<Box<dyn Directory>>>(
     directory: T,
     tokenizer_manager: &TokenizerManager,
@@ -562,9 +646,7 @@ mod tests {
     use quickwit_metastore::{
         ListSplitsRequestExt, MetastoreServiceStreamSplitsExt, SplitMetadata, StageSplitsRequestExt,
     };
-    use quickwit_proto::metastore::{
-        DeleteQuery, ListSplitsRequest, PublishSplitsRequest, StageSplitsRequest,
-    };
+    use quickwit_proto::metastore::{ListSplitsRequest, PublishSplitsRequest, StageSplitsRequest};
     use serde_json::Value as JsonValue;
     use tantivy::{Document, ReloadPolicy, TantivyDocument};
 
@@ -572,6 +654,23 @@ mod tests {
     use crate::merge_policy::{MergeOperation, MergeTask};
     use crate::{get_tantivy_directory_from_split_bundle, new_split_id, TestSandbox};
 
+    #[test]
+    fn test_ongoing_operation_guard() {
+        fn failing_operation(num_ongoing_operations: Arc<AtomicU64>) -> anyhow::Result<()> {
+            let _ongoing_operation_guard = OngoingOperationGuard::new(num_ongoing_operations);
+            anyhow::bail!("merge failed")
+        }
+        let num_ongoing_operations = Arc::new(AtomicU64::new(0));
+        let guard = OngoingOperationGuard::new(num_ongoing_operations.clone());
+        assert_eq!(num_ongoing_operations.load(Ordering::Relaxed), 1);
+
+        assert!(failing_operation(num_ongoing_operations.clone()).is_err());
+        assert_eq!(num_ongoing_operations.load(Ordering::Relaxed), 1);
+
+        drop(guard);
+        assert_eq!(num_ongoing_operations.load(Ordering::Relaxed), 0);
+    }
+
     #[tokio::test]
     async fn test_merge_executor() -> anyhow::Result<()> {
         let doc_mapping_yaml = r#"
@@ -645,7 +744,10 @@ mod tests {
             .spawn_builder()
             .spawn(merge_executor);
         merge_executor_mailbox.send_message(merge_scratch).await?;
-        merge_executor_handle.process_pending_and_observe().await;
+        let merge_executor_counters = merge_executor_handle.process_pending_and_observe().await;
+        assert_eq!(merge_executor_counters.num_ongoing_operations, 0);
+        assert_eq!(merge_executor_counters.num_completed_merges, 1);
+        assert_eq!(merge_executor_counters.num_completed_deletes, 0);
         let packager_msgs: Vec<IndexedSplitBatch> = merge_packager_inbox.drain_for_test_typed();
         assert_eq!(packager_msgs.len(), 1);
         let split_attrs_after_merge = &packager_msgs[0].splits[0].split_attrs;
@@ -696,6 +798,7 @@ mod tests {
         index_id: &str,
         docs: Vec<JsonValue>,
         delete_query: &str,
+        delete_time_range: (Option<i64>, Option<i64>),
         result_docs: Vec<JsonValue>,
     ) -> anyhow::Result<()> {
         quickwit_common::setup_logging_for_tests();
@@ -724,8 +827,8 @@ mod tests {
         metastore
             .create_delete_task(DeleteQuery {
                 index_uid: index_uid.to_string(),
-                start_timestamp: None,
-                end_timestamp: None,
+                start_timestamp: delete_time_range.0,
+                end_timestamp: delete_time_range.1,
                 query_ast: quickwit_query::query_ast::qast_json_helper(delete_query, &["body"]),
             })
             .await?;
@@ -790,9 +893,12 @@ mod tests {
         delete_task_executor_mailbox
             .send_message(merge_scratch)
             .await?;
-        delete_task_executor_handle
+        let delete_task_executor_counters = delete_task_executor_handle
             .process_pending_and_observe()
             .await;
+        assert_eq!(delete_task_executor_counters.num_ongoing_operations, 0);
+        assert_eq!(delete_task_executor_counters.num_completed_merges, 0);
+        assert_eq!(delete_task_executor_counters.num_completed_deletes, 1);
 
         let packager_msgs: Vec<IndexedSplitBatch> = merge_packager_inbox.drain_for_test_typed();
         if !result_docs.is_empty() {
@@ -863,6 +969,7 @@ mod tests {
                 serde_json::json!({"body": "delete", "ts": 1634928208 }),
             ],
             "body:delete",
+            (None, None),
             vec![serde_json::json!({"body": ["info"], "ts": ["2021-06-29T00:56:48Z"] })],
         )
         .await
@@ -879,6 +986,7 @@ mod tests {
                 serde_json::json!({"body": "delete", "ts": 1634928209 }),
             ],
             "body: IN [delete]",
+            (None, None),
             vec![
                 serde_json::json!({"body": ["info"], "ts": ["2021-06-29T00:56:48Z"] }),
                 serde_json::json!({"body": ["info"], "ts": ["2021-06-29T00:56:49Z"] }),
@@ -887,6 +995,129 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_delete_within_time_range_and_merge_executor() -> anyhow::Result<()> {
+        aux_test_delete_and_merge_executor(
+            "test-delete-within-time-range-and-merge-executor",
+            vec![
+                serde_json::json!({"body": "delete", "ts": 1624928208 }),
+                serde_json::json!({"body": "delete", "ts": 1634928208 }),
+            ],
+            "body:delete",
+            (Some(1630000000), Some(1640000000)),
+            vec![serde_json::json!({"body": ["delete"], "ts": ["2021-06-29T00:56:48Z"] })],
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_delete_and_merge_executor_skips_already_applied_delete_tasks(
+    ) -> anyhow::Result<()> {
+        quickwit_common::setup_logging_for_tests();
+        let universe = Universe::with_accelerated_time();
+        let doc_mapping_yaml = r#"
+            field_mappings:
+              - name: body
+                type: text
+        "#;
+        let test_sandbox = TestSandbox::create(
+            "test-delete-and-merge-skips-applied-deletes",
+            doc_mapping_yaml,
+            "",
+            &["body"],
+        )
+        .await?;
+        let index_uid = test_sandbox.index_uid();
+        let pipeline_id = IndexingPipelineId {
+            index_uid: index_uid.clone(),
+            node_id: "unknown".to_string(),
+            pipeline_uid: PipelineUid::from_u128(0u128),
+            source_id: "unknown".to_string(),
+        };
+        test_sandbox
+            .add_documents(vec![
+                serde_json::json!({"body": "info"}),
+                serde_json::json!({"body": "delete"}),
+            ])
+            .await?;
+        let mut metastore = test_sandbox.metastore();
+        let delete_task = metastore
+            .create_delete_task(DeleteQuery {
+                index_uid: index_uid.to_string(),
+                start_timestamp: None,
+                end_timestamp: None,
+                query_ast: quickwit_query::query_ast::qast_json_helper("body:delete", &["body"]),
+            })
+            .await?;
+        let splits = metastore
+            .list_splits(ListSplitsRequest::try_from_index_uid(index_uid.clone()).unwrap())
+            .await
+            .unwrap()
+            .collect_splits()
+            .await
+            .unwrap();
+
+        // The split already incorporates the delete task.
+        let mut split_metadata = splits[0].split_metadata.clone();
+        split_metadata.delete_opstamp = delete_task.opstamp;
+
+        let merge_scratch_directory = TempDirectory::for_test();
+        let downloaded_splits_directory =
+            merge_scratch_directory.named_temp_child("downloaded-splits-")?;
+        let split_filename = split_file(split_metadata.split_id());
+        let dest_filepath = downloaded_splits_directory.path().join(&split_filename);
+        test_sandbox
+            .storage()
+            .copy_to_file(Path::new(&split_filename), &dest_filepath)
+            .await?;
+        let tantivy_dir = get_tantivy_directory_from_split_bundle(&dest_filepath).unwrap();
+        let merge_operation = MergeOperation::new_delete_and_merge_operation(split_metadata);
+        let merge_task = MergeTask::from_merge_operation_for_test(merge_operation);
+        let merge_scratch = MergeScratch {
+            merge_task,
+            tantivy_dirs: vec![tantivy_dir],
+            merge_scratch_directory,
+            downloaded_splits_directory,
+        };
+        let (merge_packager_mailbox, merge_packager_inbox) = universe.create_test_mailbox();
+        let delete_task_executor = MergeExecutor::new(
+            pipeline_id,
+            metastore.clone(),
+            test_sandbox.doc_mapper(),
+            IoControls::default(),
+            merge_packager_mailbox,
+        );
+        let (delete_task_executor_mailbox, delete_task_executor_handle) =
+            universe.spawn_builder().spawn(delete_task_executor);
+        delete_task_executor_mailbox
+            .send_message(merge_scratch)
+            .await?;
+        delete_task_executor_handle
+            .process_pending_and_observe()
+            .await;
+
+        let packager_msgs: Vec<IndexedSplitBatch> = merge_packager_inbox.drain_for_test_typed();
+        assert!(packager_msgs.is_empty());
+
+        let splits = metastore
+            .list_splits(ListSplitsRequest::try_from_index_uid(index_uid).unwrap())
+            .await
+            .unwrap()
+            .collect_splits()
+            .await
+            .unwrap();
+        assert_eq!(splits.len(), 1);
+        assert_eq!(
+            splits[0].split_state,
+            quickwit_metastore::SplitState::Published
+        );
+        assert_eq!(splits[0].split_metadata.num_docs, 2);
+
+        test_sandbox.assert_quit().await;
+        universe.assert_quit().await;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_delete_all() -> anyhow::Result<()> {
         aux_test_delete_and_merge_executor(
@@ -896,6 +1127,7 @@ mod tests {
                 serde_json::json!({"body": "delete", "ts": 1634928209 }),
             ],
             "body:delete",
+            (None, None),
             Vec::new(),
         )
         .await
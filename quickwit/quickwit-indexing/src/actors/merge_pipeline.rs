@@ -17,6 +17,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::num::NonZeroU32;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -43,7 +44,7 @@ use tracing::{debug, error, info, instrument};
 use super::MergeSchedulerService;
 use crate::actors::indexing_pipeline::wait_duration_before_retry;
 use crate::actors::merge_split_downloader::MergeSplitDownloader;
-use crate::actors::publisher::PublisherType;
+use crate::actors::publisher::{PublisherConfig, PublisherType};
 use crate::actors::{MergeExecutor, MergePlanner, Packager, Publisher, Uploader, UploaderType};
 use crate::merge_policy::MergePolicy;
 use crate::models::MergeStatistics;
@@ -235,11 +236,16 @@ impl MergePipeline {
         );
 
         // Merge publisher
-        let merge_publisher = Publisher::new(
+        let merge_publisher_config = PublisherConfig {
+            max_retries_per_minute: self.params.max_publish_retries_per_minute,
+            ..Default::default()
+        };
+        let merge_publisher = Publisher::new_with_config(
             PublisherType::MergePublisher,
             self.params.metastore.clone(),
             Some(self.merge_planner_mailbox.clone()),
             None,
+            merge_publisher_config,
         );
         let (merge_publisher_mailbox, merge_publisher_handler) = ctx
             .spawn_actor()
@@ -467,6 +473,7 @@ pub struct MergePipelineParams {
     pub split_store: IndexingSplitStore,
     pub merge_policy: Arc<dyn MergePolicy>,
     pub max_concurrent_split_uploads: usize, //< TODO share with the indexing pipeline.
+    pub max_publish_retries_per_minute: NonZeroU32,
     pub merge_io_throughput_limiter_opt: Option<Limiter>,
     pub event_broker: EventBroker,
 }
@@ -530,6 +537,7 @@ mod tests {
             max_concurrent_split_uploads: 2,
             merge_io_throughput_limiter_opt: None,
             event_broker: Default::default(),
+            max_publish_retries_per_minute: NonZeroU32::MIN,
         };
         let pipeline = MergePipeline::new(pipeline_params, universe.spawn_ctx());
         let (_pipeline_mailbox, pipeline_handler) = universe.spawn_builder().spawn(pipeline);
@@ -586,6 +586,68 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_merge_planner_dismiss_mature_splits() -> anyhow::Result<()> {
+        let universe = Universe::with_accelerated_time();
+        let (merge_split_downloader_mailbox, merge_split_downloader_inbox) =
+            universe.create_test_mailbox();
+        let index_uid = IndexUid::new_with_random_ulid("test-index");
+        let pipeline_id = IndexingPipelineId {
+            index_uid: index_uid.clone(),
+            source_id: "test-source".to_string(),
+            node_id: "test-node".to_string(),
+            pipeline_uid: PipelineUid::default(),
+        };
+        let merge_policy_config = ConstWriteAmplificationMergePolicyConfig {
+            merge_factor: 2,
+            max_merge_factor: 2,
+            max_merge_ops: 3,
+            ..Default::default()
+        };
+        let indexing_settings = IndexingSettings {
+            merge_policy: MergePolicyConfig::ConstWriteAmplification(merge_policy_config),
+            ..Default::default()
+        };
+        let merge_policy: Arc<dyn MergePolicy> = merge_policy_from_settings(&indexing_settings);
+        let merge_planner = MergePlanner::new(
+            pipeline_id,
+            Vec::new(),
+            merge_policy,
+            merge_split_downloader_mailbox,
+            universe.get_or_spawn_one(),
+        );
+        let (merge_planner_mailbox, merge_planner_handle) =
+            universe.spawn_builder().spawn(merge_planner);
+
+        let mature_split = |split_id: &str| SplitMetadata {
+            maturity: SplitMaturity::Mature,
+            ..split_metadata_for_test(&index_uid, split_id, 0, 1_000, 0)
+        };
+        let message = NewSplits {
+            new_splits: vec![
+                mature_split("a_mature"),
+                split_metadata_for_test(&index_uid, "b_immature", 0, 1_000, 0),
+                mature_split("c_mature"),
+                split_metadata_for_test(&index_uid, "d_immature", 0, 1_000, 0),
+            ],
+        };
+        merge_planner_mailbox.send_message(message).await?;
+        merge_planner_handle.process_pending_and_observe().await;
+
+        let merge_tasks = merge_split_downloader_inbox.drain_for_test_typed::<MergeTask>();
+        assert_eq!(merge_tasks.len(), 1);
+
+        let merged_split_ids: Vec<&str> = merge_tasks[0]
+            .splits
+            .iter()
+            .map(|split| split.split_id())
+            .sorted()
+            .collect();
+        assert_eq!(merged_split_ids, ["b_immature", "d_immature"]);
+        universe.assert_quit().await;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_merge_planner_inherit_mailbox_with_splits_bug_3847() -> anyhow::Result<()> {
         let universe = Universe::with_accelerated_time();
@@ -24,8 +24,9 @@ use quickwit_actors::{Actor, ActorContext, ActorExitStatus, Handler, Mailbox, Qu
 use quickwit_common::io::IoControls;
 use quickwit_common::temp_dir::{self, TempDirectory};
 use quickwit_metastore::SplitMetadata;
+use quickwit_storage::StorageErrorKind;
 use tantivy::Directory;
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, instrument, warn};
 
 use super::MergeExecutor;
 use crate::merge_policy::MergeTask;
@@ -76,13 +77,17 @@ impl Handler<MergeTask> for MergeSplitDownloader {
             .join("downloaded-splits")
             .tempdir_in(merge_scratch_directory.path())
             .map_err(|error| anyhow::anyhow!(error))?;
-        let tantivy_dirs = self
+        let Some(tantivy_dirs) = self
             .download_splits(
                 merge_task.splits_as_slice(),
                 downloaded_splits_directory.path(),
                 ctx,
             )
-            .await?;
+            .await?
+        else {
+            // Dropping the merge task releases the merge operation and its splits.
+            return Ok(());
+        };
         let msg = MergeScratch {
             merge_task,
             merge_scratch_directory,
@@ -95,12 +100,16 @@ impl Handler<MergeTask> for MergeSplitDownloader {
 }
 
 impl MergeSplitDownloader {
+    /// Downloads the splits to merge into `download_directory`.
+    ///
+    /// Returns `None` if one of the splits no longer exists in the storage, for instance because
+    /// it was garbage collected in the meantime. The merge operation should then be aborted.
     async fn download_splits(
         &self,
         splits: &[SplitMetadata],
         download_directory: &Path,
         ctx: &ActorContext<Self>,
-    ) -> Result<Vec<Box<dyn Directory>>, quickwit_actors::ActorExitStatus> {
+    ) -> Result<Option<Vec<Box<dyn Directory>>>, quickwit_actors::ActorExitStatus> {
         // we download all of the split files in the scratch directory.
         let mut tantivy_dirs = Vec::new();
         for split in splits {
@@ -117,17 +126,29 @@ impl MergeSplitDownloader {
                 .set_progress(ctx.progress().clone())
                 .set_kill_switch(ctx.kill_switch().clone());
             let _protect_guard = ctx.protect_zone();
-            let tantivy_dir = self
+            let tantivy_dir = match self
                 .split_store
                 .fetch_and_open_split(split.split_id(), download_directory, &io_controls)
                 .await
-                .map_err(|error| {
+            {
+                Ok(tantivy_dir) => tantivy_dir,
+                Err(error) if error.kind() == StorageErrorKind::NotFound => {
+                    warn!(
+                        split_id = split.split_id(),
+                        "split to merge does not exist anymore, aborting merge operation"
+                    );
+                    return Ok(None);
+                }
+                Err(error) => {
                     let split_id = split.split_id();
-                    anyhow::anyhow!(error).context(format!("failed to download split `{split_id}`"))
-                })?;
+                    return Err(anyhow::anyhow!(error)
+                        .context(format!("failed to download split `{split_id}`"))
+                        .into());
+                }
+            };
             tantivy_dirs.push(tantivy_dir);
         }
-        Ok(tantivy_dirs)
+        Ok(Some(tantivy_dirs))
     }
 }
 
@@ -136,7 +157,7 @@ mod tests {
     use std::iter;
     use std::sync::Arc;
 
-    use quickwit_actors::Universe;
+    use quickwit_actors::{ObservationType, Universe};
     use quickwit_common::split_file;
     use quickwit_storage::{PutPayload, RamStorageBuilder, SplitPayloadBuilder};
 
@@ -207,4 +228,49 @@ mod tests {
         universe.assert_quit().await;
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_merge_split_downloader_aborts_merge_on_missing_split() -> anyhow::Result<()> {
+        let scratch_directory = TempDirectory::for_test();
+        let splits_to_merge: Vec<SplitMetadata> = ["split-1", "split-2"]
+            .into_iter()
+            .map(|split_id| SplitMetadata::for_test(split_id.to_string()))
+            .collect();
+
+        let split_store = {
+            // `split-2` is missing, as if it had been garbage collected.
+            let buffer = SplitPayloadBuilder::get_split_payload(&[], &[], &[1, 2, 3])?
+                .read_all()
+                .await?;
+            let ram_storage = RamStorageBuilder::default()
+                .put(&split_file("split-1"), &buffer)
+                .build();
+            IndexingSplitStore::create_without_local_store_for_test(Arc::new(ram_storage))
+        };
+
+        let universe = Universe::with_accelerated_time();
+        let (merge_executor_mailbox, merge_executor_inbox) = universe.create_test_mailbox();
+        let merge_split_downloader = MergeSplitDownloader {
+            scratch_directory,
+            split_store,
+            executor_mailbox: merge_executor_mailbox,
+            io_controls: IoControls::default(),
+        };
+        let (merge_split_downloader_mailbox, merge_split_downloader_handler) =
+            universe.spawn_builder().spawn(merge_split_downloader);
+        let merge_operation: MergeOperation = MergeOperation::new_merge_operation(splits_to_merge);
+        let merge_task = MergeTask::from_merge_operation_for_test(merge_operation);
+        merge_split_downloader_mailbox
+            .send_message(merge_task)
+            .await?;
+        let observation = merge_split_downloader_handler
+            .process_pending_and_observe()
+            .await;
+        assert_eq!(observation.obs_type, ObservationType::Alive);
+
+        let merge_scratches = merge_executor_inbox.drain_for_test();
+        assert!(merge_scratches.is_empty());
+        universe.assert_quit().await;
+        Ok(())
+    }
 }
@@ -41,13 +41,18 @@ pub use indexing_pipeline::{IndexingPipeline, IndexingPipelineParams};
 pub use indexing_service::{
     IndexingService, IndexingServiceCounters, MergePipelineId, INDEXING_DIR_NAME,
 };
-pub use merge_executor::{combine_partition_ids, merge_split_attrs, MergeExecutor};
+pub use merge_executor::{
+    combine_partition_ids, merge_split_attrs, MergeExecutor, MergeExecutorCounters,
+};
 pub use merge_pipeline::MergePipeline;
 pub use merge_planner::MergePlanner;
 pub use merge_scheduler_service::{schedule_merge, MergePermit, MergeSchedulerService};
 pub use merge_split_downloader::MergeSplitDownloader;
 pub use packager::Packager;
-pub use publisher::{Publisher, PublisherCounters, PublisherType};
+pub use publisher::{
+    CircuitBreakerState, ForceFlush, Publisher, PublisherConfig, PublisherCounters, PublisherType,
+    UnsupportedSplitFormat,
+};
 pub use quickwit_proto::indexing::IndexingError;
 pub use sequencer::Sequencer;
 pub use uploader::{SplitsUpdateMailbox, Uploader, UploaderCounters, UploaderType};
@@ -528,6 +528,8 @@ mod tests {
                 replaced_split_ids: Vec::new(),
                 delete_opstamp: 0,
                 num_merge_ops: 0,
+                backfill: false,
+                oldest_doc_instant_opt: None,
             },
             index,
             split_scratch_directory,
@@ -17,23 +17,223 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::HashMap;
+use std::num::{NonZeroU32, NonZeroUsize};
+use std::time::Duration;
+
 use anyhow::Context;
 use async_trait::async_trait;
 use fail::fail_point;
-use quickwit_actors::{Actor, ActorContext, Handler, Mailbox, QueueCapacity};
-use quickwit_proto::metastore::{MetastoreService, MetastoreServiceClient, PublishSplitsRequest};
+use quickwit_actors::{
+    Actor, ActorContext, ActorExitStatus, Envelope, Handler, Mailbox, QueueCapacity,
+};
+use quickwit_common::rate_limiter::{RateLimiter, RateLimiterSettings};
+use quickwit_common::retry::{RetryParams, Retryable};
+use quickwit_common::tower::ConstantRate;
+use quickwit_metastore::checkpoint::{IndexCheckpointDelta, PartitionId};
+use quickwit_metastore::SPLIT_FORMAT_VERSION;
+use quickwit_proto::metastore::{
+    MetastoreError, MetastoreService, MetastoreServiceClient, PublishSplitsRequest,
+};
+use quickwit_proto::types::{IndexUid, Position};
 use serde::Serialize;
-use tracing::{info, instrument, warn};
+use thiserror::Error;
+use tokio::sync::oneshot;
+use tracing::{debug, info, instrument, warn, Span};
 
 use crate::actors::MergePlanner;
+use crate::metrics::INDEXER_METRICS;
 use crate::models::{NewSplits, SplitsUpdate};
 use crate::source::{SourceActor, SuggestTruncate};
 
+const PUBLISHER_MAX_BATCH_SIZE: usize = 32;
+
+const CIRCUIT_BREAKER_FAILURE_THRESHOLD: usize = 3;
+
+const CIRCUIT_BREAKER_COOLDOWN: Duration = Duration::from_secs(10);
+
+const DEFAULT_MAX_RETRIES_PER_MINUTE: u32 = 30;
+
+/// Settings of a [`Publisher`], see [`Publisher::new_with_config`].
+#[derive(Clone, Debug)]
+pub struct PublisherConfig {
+    /// Maximum number of pending messages drained from the publisher mailbox at once. The splits
+    /// updates drained together are published with as few metastore calls as possible.
+    pub max_batch_size: NonZeroUsize,
+    /// Maximum number of times per minute that the publisher retries publishes that failed on
+    /// transient metastore errors, all publishes taken together. Each retry still waits for the
+    /// backoff delay of [`PublisherConfig::retry_params`]. Once the budget is exhausted, failing
+    /// publishes trip the circuit breaker and wait for its cooldown instead.
+    pub max_retries_per_minute: NonZeroU32,
+    /// Delays between the attempts of a publish that fails on transient metastore errors, and
+    /// maximum number of attempts after which the publisher gives up and fails.
+    pub retry_params: RetryParams,
+    /// Number of consecutive failed metastore calls after which the circuit breaker opens.
+    pub circuit_breaker_failure_threshold: usize,
+    /// Time during which the publisher refrains from calling the metastore once the circuit
+    /// breaker is open.
+    pub circuit_breaker_cooldown: Duration,
+}
+
+impl Default for PublisherConfig {
+    fn default() -> Self {
+        PublisherConfig {
+            max_batch_size: NonZeroUsize::new(PUBLISHER_MAX_BATCH_SIZE).unwrap(),
+            max_retries_per_minute: NonZeroU32::new(DEFAULT_MAX_RETRIES_PER_MINUTE).unwrap(),
+            retry_params: RetryParams::default(),
+            circuit_breaker_failure_threshold: CIRCUIT_BREAKER_FAILURE_THRESHOLD,
+            circuit_breaker_cooldown: CIRCUIT_BREAKER_COOLDOWN,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Serialize)]
 pub struct PublisherCounters {
     pub num_published_splits: u64,
     pub num_replace_operations: u64,
     pub num_empty_splits: u64,
+    /// Number of retries that were not attempted because the retry budget was exhausted.
+    pub num_shed_retries: u64,
+    pub circuit_breaker_state: CircuitBreakerState,
+}
+
+impl PublisherCounters {
+    fn record_splits_update(&mut self, splits_update: &SplitsUpdate) {
+        if splits_update.new_splits.is_empty() {
+            self.num_empty_splits += 1;
+        } else if splits_update.replaced_split_ids.is_empty() {
+            self.num_published_splits += 1;
+        } else {
+            self.num_replace_operations += 1;
+        }
+    }
+
+    fn add(&mut self, other: &PublisherCounters) {
+        self.num_published_splits += other.num_published_splits;
+        self.num_replace_operations += other.num_replace_operations;
+        self.num_empty_splits += other.num_empty_splits;
+    }
+}
+
+/// Asks the publisher to publish the splits updates pending in its mailbox right away, without
+/// stopping it.
+///
+/// This message should be sent with high priority, so that it does not wait behind the splits
+/// updates it is meant to flush. The reply is sent once the splits updates received together with
+/// it, up to the publisher batch size, have been published.
+#[derive(Debug)]
+pub struct ForceFlush;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitBreakerState {
+    /// Metastore calls go through.
+    #[default]
+    Closed,
+    /// Metastore calls are suspended until the cooldown elapses.
+    Open,
+    /// A single metastore call is let through to probe whether the metastore has recovered.
+    HalfOpen,
+}
+
+/// Tracks consecutive metastore failures so that the publisher stops hammering an unavailable
+/// metastore.
+#[derive(Clone, Debug)]
+struct CircuitBreaker {
+    failure_threshold: usize,
+    num_consecutive_failures: usize,
+    state: CircuitBreakerState,
+}
+
+impl CircuitBreaker {
+    fn new(failure_threshold: usize) -> Self {
+        CircuitBreaker {
+            failure_threshold,
+            num_consecutive_failures: 0,
+            state: CircuitBreakerState::Closed,
+        }
+    }
+
+    fn state(&self) -> CircuitBreakerState {
+        self.state
+    }
+
+    fn record_success(&mut self) {
+        self.num_consecutive_failures = 0;
+        self.state = CircuitBreakerState::Closed;
+    }
+
+    fn record_failure(&mut self) {
+        self.num_consecutive_failures += 1;
+
+        if self.state == CircuitBreakerState::HalfOpen
+            || self.num_consecutive_failures >= self.failure_threshold
+        {
+            self.state = CircuitBreakerState::Open;
+        }
+    }
+
+    /// Lets a single probe through once the cooldown has elapsed.
+    fn half_open(&mut self) {
+        if self.state == CircuitBreakerState::Open {
+            self.state = CircuitBreakerState::HalfOpen;
+        }
+    }
+
+    /// Opens the circuit breaker regardless of the number of consecutive failures.
+    fn open(&mut self) {
+        self.state = CircuitBreakerState::Open;
+    }
+}
+
+/// Token bucket that the retries of all the publishes of a publisher draw from, on top of their
+/// exponential backoff, so that the number of retries per unit of time is capped no matter how
+/// many publishes are failing.
+#[derive(Clone, Debug)]
+struct RetryBudget {
+    rate_limiter: RateLimiter,
+    num_shed_retries: u64,
+}
+
+impl RetryBudget {
+    fn new(max_retries_per_minute: u32) -> Self {
+        let max_retries_per_minute = max_retries_per_minute as u64;
+        let rate_limiter_settings = RateLimiterSettings {
+            burst_limit: max_retries_per_minute,
+            rate_limit: ConstantRate::new(max_retries_per_minute, Duration::from_secs(60)),
+            refill_period: Duration::from_secs(60),
+        };
+        RetryBudget {
+            rate_limiter: RateLimiter::from_settings(rate_limiter_settings),
+            num_shed_retries: 0,
+        }
+    }
+
+    /// Returns whether a retry can be attempted. Otherwise, the retry is shed.
+    fn try_acquire(&mut self) -> bool {
+        if self.rate_limiter.acquire(1) {
+            return true;
+        }
+        self.num_shed_retries += 1;
+        false
+    }
+}
+
+/// Error returned when a split was written in a format more recent than the one supported by this
+/// node, typically by a newer indexer in a mixed-version cluster.
+#[derive(Debug, Error)]
+#[error(
+    "split `{split_id}` has format version {format_version}, but this node supports versions up \
+     to {SPLIT_FORMAT_VERSION}"
+)]
+pub struct UnsupportedSplitFormat {
+    pub split_id: String,
+    pub format_version: u32,
+}
+
+/// Returns whether the metastore might accept the same request later.
+fn is_transient_error(error: &MetastoreError) -> bool {
+    error.is_retryable() || matches!(error, MetastoreError::Unavailable(_))
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -51,6 +251,142 @@ impl PublisherType {
     }
 }
 
+/// One or several [`SplitsUpdate`] folded together so that they can be published with a single
+/// metastore call.
+struct SplitsUpdateBatch {
+    splits_update: SplitsUpdate,
+    // The parent spans of the folded updates, linked to the span of the publication.
+    follows_from_spans: Vec<Span>,
+    // The counters to record once the batch is published.
+    counters: PublisherCounters,
+}
+
+impl From<SplitsUpdate> for SplitsUpdateBatch {
+    fn from(splits_update: SplitsUpdate) -> Self {
+        let mut counters = PublisherCounters::default();
+        counters.record_splits_update(&splits_update);
+        SplitsUpdateBatch {
+            splits_update,
+            follows_from_spans: Vec::new(),
+            counters,
+        }
+    }
+}
+
+impl SplitsUpdateBatch {
+    /// Folds `other` into the batch. Updates that target a different index, publish token, or
+    /// publish lock, or carry a non-contiguous checkpoint delta, are returned as is. So are
+    /// updates that replace splits, such as merges: those are always published one at a time.
+    fn try_push(&mut self, other: SplitsUpdate) -> Result<(), SplitsUpdate> {
+        let splits_update = &mut self.splits_update;
+
+        if splits_update.index_uid != other.index_uid
+            || splits_update.publish_token_opt != other.publish_token_opt
+            || splits_update.publish_lock != other.publish_lock
+            || !splits_update.replaced_split_ids.is_empty()
+            || !other.replaced_split_ids.is_empty()
+        {
+            return Err(other);
+        }
+        let checkpoint_delta_opt = match (
+            &splits_update.checkpoint_delta_opt,
+            &other.checkpoint_delta_opt,
+        ) {
+            (None, None) => None,
+            (Some(checkpoint_delta), Some(other_checkpoint_delta))
+                if checkpoint_delta.source_id == other_checkpoint_delta.source_id =>
+            {
+                let mut source_delta = checkpoint_delta.source_delta.clone();
+
+                if source_delta
+                    .extend(other_checkpoint_delta.source_delta.clone())
+                    .is_err()
+                {
+                    return Err(other);
+                }
+                Some(IndexCheckpointDelta {
+                    source_id: checkpoint_delta.source_id.clone(),
+                    source_delta,
+                })
+            }
+            _ => return Err(other),
+        };
+        self.counters.record_splits_update(&other);
+
+        splits_update.checkpoint_delta_opt = checkpoint_delta_opt;
+        splits_update.new_splits.extend(other.new_splits);
+        splits_update
+            .oldest_doc_instants
+            .extend(other.oldest_doc_instants);
+        self.follows_from_spans.push(other.parent_span);
+        Ok(())
+    }
+}
+
+/// Sorts the splits updates by split ID. Split IDs are ULIDs generated monotonically by
+/// [`new_split_id`](crate::new_split_id), so this orders the updates, and the checkpoint deltas
+/// they carry, by creation time, even within the same millisecond, regardless of the order in
+/// which they reached the mailbox. Within each update, the new splits are sorted as well, so that
+/// the splits are staged in a deterministic order.
+///
+/// Updates without new splits have no natural position: they act as barriers and only the
+/// updates in between them are reordered. The sort is stable.
+///
+/// The checkpoint deltas must be published in source order. If sorting a run of updates by split
+/// ID would publish a delta before one that precedes it in its partition, the run keeps its
+/// arrival order, which the sequencer upstream of the publisher keeps in source order.
+fn sort_splits_updates(splits_updates: &mut [SplitsUpdate]) {
+    for splits_update in splits_updates.iter_mut() {
+        splits_update
+            .new_splits
+            .sort_by(|left, right| left.split_id.cmp(&right.split_id));
+    }
+    let is_barrier = |splits_update: &SplitsUpdate| splits_update.new_splits.is_empty();
+    let cmp_first_split_ids = |left: &SplitsUpdate, right: &SplitsUpdate| {
+        left.new_splits[0]
+            .split_id
+            .cmp(&right.new_splits[0].split_id)
+    };
+    for splits_updates_run in splits_updates.split_mut(is_barrier) {
+        let mut sorted_run: Vec<&SplitsUpdate> = splits_updates_run.iter().collect();
+        sorted_run.sort_by(|&left, &right| cmp_first_split_ids(left, right));
+
+        if !are_checkpoint_deltas_in_order(sorted_run) {
+            warn!("split ID order and checkpoint order disagree, keeping arrival order");
+            continue;
+        }
+        splits_updates_run.sort_by(cmp_first_split_ids);
+    }
+}
+
+/// Returns whether, within each partition, every checkpoint delta carried by the splits updates
+/// starts at or after the end of the deltas carried by the updates before it.
+fn are_checkpoint_deltas_in_order<'a>(
+    splits_updates: impl IntoIterator<Item = &'a SplitsUpdate>,
+) -> bool {
+    let mut partition_positions: HashMap<(&IndexUid, &str, PartitionId), Position> = HashMap::new();
+
+    for splits_update in splits_updates {
+        let Some(checkpoint_delta) = &splits_update.checkpoint_delta_opt else {
+            continue;
+        };
+        for (partition_id, partition_delta) in checkpoint_delta.source_delta.iter() {
+            let partition_key = (
+                &splits_update.index_uid,
+                checkpoint_delta.source_id.as_str(),
+                partition_id,
+            );
+            if let Some(position) = partition_positions.get(&partition_key) {
+                if partition_delta.from < *position {
+                    return false;
+                }
+            }
+            partition_positions.insert(partition_key, partition_delta.to);
+        }
+    }
+    true
+}
+
 #[derive(Clone)]
 pub struct Publisher {
     publisher_type: PublisherType,
@@ -58,14 +394,36 @@ pub struct Publisher {
     merge_planner_mailbox_opt: Option<Mailbox<MergePlanner>>,
     source_mailbox_opt: Option<Mailbox<SourceActor>>,
     counters: PublisherCounters,
+    circuit_breaker: CircuitBreaker,
+    circuit_breaker_cooldown: Duration,
+    retry_budget: RetryBudget,
+    retry_params: RetryParams,
+    max_batch_size: NonZeroUsize,
 }
 
 impl Publisher {
+    /// Creates a publisher with the default [`PublisherConfig`].
     pub fn new(
         publisher_type: PublisherType,
         metastore: MetastoreServiceClient,
         merge_planner_mailbox_opt: Option<Mailbox<MergePlanner>>,
         source_mailbox_opt: Option<Mailbox<SourceActor>>,
+    ) -> Publisher {
+        Publisher::new_with_config(
+            publisher_type,
+            metastore,
+            merge_planner_mailbox_opt,
+            source_mailbox_opt,
+            PublisherConfig::default(),
+        )
+    }
+
+    pub fn new_with_config(
+        publisher_type: PublisherType,
+        metastore: MetastoreServiceClient,
+        merge_planner_mailbox_opt: Option<Mailbox<MergePlanner>>,
+        source_mailbox_opt: Option<Mailbox<SourceActor>>,
+        config: PublisherConfig,
     ) -> Publisher {
         Publisher {
             publisher_type,
@@ -73,42 +431,127 @@ impl Publisher {
             merge_planner_mailbox_opt,
             source_mailbox_opt,
             counters: PublisherCounters::default(),
+            circuit_breaker: CircuitBreaker::new(config.circuit_breaker_failure_threshold),
+            circuit_breaker_cooldown: config.circuit_breaker_cooldown,
+            retry_budget: RetryBudget::new(config.max_retries_per_minute.get()),
+            retry_params: config.retry_params,
+            max_batch_size: config.max_batch_size,
         }
     }
-}
 
-#[async_trait]
-impl Actor for Publisher {
-    type ObservableState = PublisherCounters;
+    /// Publishes splits, retrying on transient metastore errors with an exponential backoff. The
+    /// publisher fails once [`RetryParams::max_attempts`] attempts have failed.
+    ///
+    /// After [`PublisherConfig::circuit_breaker_failure_threshold`] consecutive failures, the
+    /// circuit breaker opens: the publisher pauses and does not call the metastore until the
+    /// cooldown elapses. The breaker then half-opens and a single probe is sent. Retries draw
+    /// from the retry budget of the publisher: when it is exhausted, the retry is shed and the
+    /// circuit breaker opens right away. Other errors fail the publisher right away.
+    ///
+    /// The publisher does not check that the index exists before publishing: the metastore
+    /// already rejects the request with a `NotFound` index error, which is not transient and fails
+    /// the publisher with a clear error. A prior `index_exists` call would cost an extra metastore
+    /// round trip per publish and could still race with the deletion of the index.
+    ///
+    /// Progress is recorded after each failed attempt, so that a publisher retrying against a
+    /// struggling metastore is not mistaken for a stuck one and killed by its supervisor.
+    async fn publish_splits(
+        &mut self,
+        publish_splits_request: PublishSplitsRequest,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        let mut num_attempts = 0;
 
-    fn observable_state(&self) -> Self::ObservableState {
-        self.counters.clone()
-    }
+        loop {
+            let circuit_breaker_state = self.circuit_breaker.state();
 
-    fn name(&self) -> String {
-        self.publisher_type.actor_name().to_string()
-    }
+            if circuit_breaker_state == CircuitBreakerState::Open {
+                ctx.observe(self);
+                ctx.protect_future(ctx.sleep(self.circuit_breaker_cooldown))
+                    .await;
 
-    fn queue_capacity(&self) -> QueueCapacity {
-        match self.publisher_type {
-            PublisherType::MainPublisher => QueueCapacity::Bounded(1),
-            PublisherType::MergePublisher => QueueCapacity::Unbounded,
+                if ctx.kill_switch().is_dead() {
+                    return Err(ActorExitStatus::Killed);
+                }
+                self.circuit_breaker.half_open();
+            }
+            let publish_splits_future = self
+                .metastore
+                .publish_splits(publish_splits_request.clone());
+            let publish_splits_res = ctx.protect_future(publish_splits_future).await;
+            num_attempts += 1;
+
+            match publish_splits_res {
+                Ok(_) => {
+                    self.circuit_breaker.record_success();
+                    return Ok(());
+                }
+                Err(error) if is_transient_error(&error) => {
+                    self.circuit_breaker.record_failure();
+
+                    if num_attempts >= self.retry_params.max_attempts {
+                        return Err(anyhow::Error::from(error)
+                            .context(format!(
+                                "failed to publish splits after {num_attempts} attempts"
+                            ))
+                            .into());
+                    }
+                    let has_retry_budget = self.retry_budget.try_acquire();
+                    warn!(
+                        error=?error,
+                        num_attempts,
+                        circuit_breaker_state=?self.circuit_breaker.state(),
+                        has_retry_budget,
+                        "failed to publish splits, retrying"
+                    );
+                    if !has_retry_budget {
+                        self.circuit_breaker.open();
+                    }
+                    ctx.record_progress();
+
+                    // Once open, the circuit breaker makes the next attempt wait for the cooldown.
+                    if self.circuit_breaker.state() != CircuitBreakerState::Open {
+                        let delay = self.retry_params.compute_delay(num_attempts);
+                        ctx.protect_future(ctx.sleep(delay)).await;
+
+                        if ctx.kill_switch().is_dead() {
+                            return Err(ActorExitStatus::Killed);
+                        }
+                    }
+                }
+                Err(error) => {
+                    return Err(anyhow::Error::from(error)
+                        .context("failed to publish splits")
+                        .into());
+                }
+            }
         }
     }
-}
-
-#[async_trait]
-impl Handler<SplitsUpdate> for Publisher {
-    type Reply = ();
 
-    #[instrument(name="publisher", parent=split_update.parent_span.id(),  skip(self, ctx))]
-    async fn handle(
+    #[instrument(
+        name = "publisher",
+        parent = batch.splits_update.parent_span.id(),
+        skip_all,
+        fields(split_update = ?batch.splits_update),
+    )]
+    /// Publishes a batch of splits updates and returns the counters to record.
+    async fn publish(
         &mut self,
-        split_update: SplitsUpdate,
+        batch: SplitsUpdateBatch,
         ctx: &ActorContext<Self>,
-    ) -> Result<(), quickwit_actors::ActorExitStatus> {
+    ) -> Result<PublisherCounters, ActorExitStatus> {
         fail_point!("publisher:before");
 
+        let SplitsUpdateBatch {
+            splits_update,
+            follows_from_spans,
+            counters,
+        } = batch;
+        let publish_span = Span::current();
+
+        for follows_from_span in &follows_from_spans {
+            publish_span.follows_from(follows_from_span);
+        }
         let SplitsUpdate {
             index_uid,
             new_splits,
@@ -116,9 +559,27 @@ impl Handler<SplitsUpdate> for Publisher {
             checkpoint_delta_opt,
             publish_lock,
             publish_token_opt,
+            oldest_doc_instants,
             ..
-        } = split_update;
+        } = splits_update;
 
+        // An update carrying neither splits nor a checkpoint delta has nothing to publish.
+        let is_noop = new_splits.is_empty()
+            && replaced_split_ids.is_empty()
+            && checkpoint_delta_opt
+                .as_ref()
+                .map_or(true, IndexCheckpointDelta::is_empty);
+        if is_noop {
+            debug!(index_uid=%index_uid, "skipping publish of empty splits update");
+            return Ok(PublisherCounters::default());
+        }
+        if let Some(split) = new_splits.iter().find(|split| !split.is_format_supported()) {
+            let unsupported_split_format = UnsupportedSplitFormat {
+                split_id: split.split_id.clone(),
+                format_version: split.format_version,
+            };
+            return Err(anyhow::Error::from(unsupported_split_format).into());
+        }
         let index_checkpoint_delta_json_opt = checkpoint_delta_opt
             .as_ref()
             .map(serde_json::to_string)
@@ -136,18 +597,27 @@ impl Handler<SplitsUpdate> for Publisher {
                 index_checkpoint_delta_json_opt,
                 publish_token_opt: publish_token_opt.clone(),
             };
-            ctx.protect_future(self.metastore.publish_splits(publish_splits_request))
-                .await
-                .context("failed to publish splits")?;
+            self.publish_splits(publish_splits_request, ctx).await?;
         } else {
             // TODO: Remove the junk right away?
             info!(
                 split_ids=?split_ids,
                 "Splits' publish lock is dead."
             );
-            return Ok(());
+            return Ok(PublisherCounters::default());
         }
         info!(new_splits=?split_ids, checkpoint_delta=?checkpoint_delta_opt, "publish-new-splits");
+
+        if !oldest_doc_instants.is_empty() {
+            let searchable_latency_secs = INDEXER_METRICS
+                .searchable_latency_secs
+                .with_label_values([index_uid.index_id()]);
+
+            for oldest_doc_instant in oldest_doc_instants {
+                searchable_latency_secs.observe(oldest_doc_instant.elapsed().as_secs_f64());
+            }
+        }
+
         if let Some(source_mailbox) = self.source_mailbox_opt.as_ref() {
             if let Some(checkpoint) = checkpoint_delta_opt {
                 // We voluntarily do not log anything here.
@@ -156,12 +626,11 @@ impl Handler<SplitsUpdate> for Publisher {
                 // considered an error. For instance, if the source is a
                 // FileSource, it will terminate upon EOF and drop its
                 // mailbox.
-                let suggest_truncate_res = ctx
-                    .send_message(
-                        source_mailbox,
-                        SuggestTruncate(checkpoint.source_delta.get_source_checkpoint()),
-                    )
-                    .await;
+                let suggest_truncate = SuggestTruncate {
+                    checkpoint: checkpoint.source_delta.get_source_checkpoint(),
+                    published_split_ids: split_ids.clone(),
+                };
+                let suggest_truncate_res = ctx.send_message(source_mailbox, suggest_truncate).await;
                 if let Err(send_truncate_err) = suggest_truncate_res {
                     warn!(error=?send_truncate_err, "failed to send truncate message from publisher to source");
                 }
@@ -178,33 +647,202 @@ impl Handler<SplitsUpdate> for Publisher {
                     .send_message(merge_planner_mailbox, NewSplits { new_splits })
                     .await;
             }
+        }
+        fail_point!("publisher:after");
+        Ok(counters)
+    }
+
+    /// Sorts the splits updates, folds the consecutive updates of each index together, and
+    /// publishes the resulting batches. Once they are all published, replies to the senders of
+    /// the updates.
+    async fn publish_splits_updates(
+        &mut self,
+        mut splits_updates: Vec<SplitsUpdate>,
+        reply_txs: Vec<oneshot::Sender<()>>,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        sort_splits_updates(&mut splits_updates);
+
+        let mut pending_batches: Vec<SplitsUpdateBatch> = Vec::new();
+
+        for splits_update in splits_updates {
+            // Updates can only be folded into the last pending batch of their index, so that they
+            // are published in order.
+            let last_index_batch_opt = pending_batches.iter_mut().rev().find(|pending_batch| {
+                pending_batch.splits_update.index_uid == splits_update.index_uid
+            });
+            let splits_update = match last_index_batch_opt {
+                Some(last_index_batch) => match last_index_batch.try_push(splits_update) {
+                    Ok(()) => continue,
+                    Err(splits_update) => splits_update,
+                },
+                None => splits_update,
+            };
+            pending_batches.push(SplitsUpdateBatch::from(splits_update));
+        }
+        self.publish_batches(pending_batches, ctx).await?;
+
+        for reply_tx in reply_txs {
+            // The sender may not be waiting for the reply.
+            let _ = reply_tx.send(());
+        }
+        Ok(())
+    }
+
+    /// Publishes batches of splits updates, one after the other.
+    async fn publish_batches(
+        &mut self,
+        batches: Vec<SplitsUpdateBatch>,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        for batch in batches {
+            let counters = self.publish(batch, ctx).await?;
+            self.counters.add(&counters);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Actor for Publisher {
+    type ObservableState = PublisherCounters;
+
+    fn observable_state(&self) -> Self::ObservableState {
+        PublisherCounters {
+            num_shed_retries: self.retry_budget.num_shed_retries,
+            circuit_breaker_state: self.circuit_breaker.state(),
+            ..self.counters.clone()
+        }
+    }
+
+    fn name(&self) -> String {
+        self.publisher_type.actor_name().to_string()
+    }
+
+    fn queue_capacity(&self) -> QueueCapacity {
+        match self.publisher_type {
+            PublisherType::MainPublisher => QueueCapacity::Bounded(1),
+            PublisherType::MergePublisher => QueueCapacity::Unbounded,
+        }
+    }
 
-            if replaced_split_ids.is_empty() {
-                self.counters.num_published_splits += 1;
-            } else {
-                self.counters.num_replace_operations += 1;
+    fn max_batch_size(&self) -> usize {
+        self.max_batch_size.get()
+    }
+
+    /// Splits that were already uploaded are published upon quitting, but dropped upon kill.
+    fn drain_mailbox_on_quit(&self) -> bool {
+        true
+    }
+
+    /// Sorts consecutive `SplitsUpdate` messages by split ID, folds those of the same index
+    /// together, and publishes each resulting batch with a single metastore call. Other messages,
+    /// such as commands, are handled in order, after the preceding updates have been published,
+    /// except for [`ForceFlush`] messages, which are handled last.
+    ///
+    /// The senders of `SplitsUpdate` messages are replied to once their updates are published.
+    async fn process_batch(
+        &mut self,
+        batch: Vec<Envelope<Self>>,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        let mut pending_splits_updates: Vec<SplitsUpdate> = Vec::new();
+        let mut pending_reply_txs: Vec<oneshot::Sender<()>> = Vec::new();
+        let mut force_flush_envelopes: Vec<Envelope<Self>> = Vec::new();
+
+        for mut envelope in batch {
+            if let Some((splits_update, reply_tx)) =
+                envelope.message_and_reply_tx_typed::<SplitsUpdate>()
+            {
+                pending_splits_updates.push(splits_update);
+                pending_reply_txs.push(reply_tx);
+                continue;
             }
-        } else {
-            self.counters.num_empty_splits += 1;
+            // High priority `ForceFlush` messages come first in the batch: they are only replied
+            // to once the splits updates that follow them have been published.
+            if envelope.is_message_of_type::<ForceFlush>() {
+                force_flush_envelopes.push(envelope);
+                continue;
+            }
+            if !pending_splits_updates.is_empty() {
+                self.publish_splits_updates(
+                    std::mem::take(&mut pending_splits_updates),
+                    std::mem::take(&mut pending_reply_txs),
+                    ctx,
+                )
+                .await?;
+            }
+            envelope.handle_message(self, ctx).await?;
         }
-        fail_point!("publisher:after");
+        if !pending_splits_updates.is_empty() {
+            self.publish_splits_updates(pending_splits_updates, pending_reply_txs, ctx)
+                .await?;
+        }
+        for mut force_flush_envelope in force_flush_envelopes {
+            force_flush_envelope.handle_message(self, ctx).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler<ForceFlush> for Publisher {
+    type Reply = ();
+
+    /// By the time this handler is called, the splits updates received before or together with the
+    /// message have already been published, see `process_batch`.
+    async fn handle(
+        &mut self,
+        _force_flush: ForceFlush,
+        _ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        debug!("force-flushed {}", self.publisher_type.actor_name());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Handler<SplitsUpdate> for Publisher {
+    type Reply = ();
+
+    async fn handle(
+        &mut self,
+        split_update: SplitsUpdate,
+        ctx: &ActorContext<Self>,
+    ) -> Result<(), ActorExitStatus> {
+        let counters = self
+            .publish(SplitsUpdateBatch::from(split_update), ctx)
+            .await?;
+        self.counters.add(&counters);
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use quickwit_actors::Universe;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+
+    use quickwit_actors::{ActorState, Universe};
+    use quickwit_config::IndexConfig;
     use quickwit_metastore::checkpoint::{
         IndexCheckpointDelta, PartitionId, SourceCheckpoint, SourceCheckpointDelta,
     };
-    use quickwit_metastore::{PublishSplitsRequestExt, SplitMetadata};
-    use quickwit_proto::metastore::EmptyResponse;
+    use quickwit_metastore::{
+        metastore_for_test, CreateIndexRequestExt, ListSplitsRequestExt, ListSplitsResponseExt,
+        MetastoreServiceExt, PublishSplitsRequestExt, SplitMetadata, SplitState,
+        StageSplitsRequestExt,
+    };
+    use quickwit_proto::metastore::{
+        CreateIndexRequest, EmptyResponse, EntityKind, ListSplitsRequest, StageSplitsRequest,
+    };
     use quickwit_proto::types::{IndexUid, Position};
     use tracing::Span;
 
     use super::*;
     use crate::models::PublishLock;
+    use crate::new_split_id;
 
     #[tokio::test]
     async fn test_publisher_publish_operation() {
@@ -253,6 +891,7 @@ mod tests {
                 publish_token_opt: None,
                 merge_task: None,
                 parent_span: tracing::Span::none(),
+                oldest_doc_instants: Vec::new(),
             })
             .await
             .is_ok());
@@ -260,19 +899,18 @@ mod tests {
         let publisher_observation = publisher_handle.process_pending_and_observe().await.state;
         assert_eq!(publisher_observation.num_published_splits, 1);
 
-        let suggest_truncate_checkpoints: Vec<SourceCheckpoint> = source_inbox
-            .drain_for_test_typed::<SuggestTruncate>()
-            .into_iter()
-            .map(|msg| msg.0)
-            .collect();
+        let suggest_truncates: Vec<SuggestTruncate> =
+            source_inbox.drain_for_test_typed::<SuggestTruncate>();
 
-        assert_eq!(suggest_truncate_checkpoints.len(), 1);
+        assert_eq!(suggest_truncates.len(), 1);
         assert_eq!(
-            suggest_truncate_checkpoints[0]
+            suggest_truncates[0]
+                .checkpoint
                 .position_for_partition(&PartitionId::default())
                 .unwrap(),
             &Position::offset(2u64)
         );
+        assert_eq!(suggest_truncates[0].published_split_ids, ["split"]);
 
         let merger_msgs: Vec<NewSplits> = merge_planner_inbox.drain_for_test_typed::<NewSplits>();
         assert_eq!(merger_msgs.len(), 1);
@@ -281,49 +919,104 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_publisher_publish_operation_with_empty_splits() {
+    async fn test_publisher_records_searchable_latency() {
         let universe = Universe::with_accelerated_time();
         let mut mock_metastore = MetastoreServiceClient::mock();
         mock_metastore
             .expect_publish_splits()
-            .withf(|publish_splits_request| {
-                let checkpoint_delta: IndexCheckpointDelta = publish_splits_request
-                    .deserialize_index_checkpoint()
-                    .unwrap()
-                    .unwrap();
-                publish_splits_request.index_uid == "index:11111111111111111111111111"
-                    && checkpoint_delta.source_id == "source"
-                    && publish_splits_request.staged_split_ids.is_empty()
-                    && publish_splits_request.replaced_split_ids.is_empty()
-                    && checkpoint_delta.source_delta == SourceCheckpointDelta::from_range(1..3)
-            })
             .times(1)
             .returning(|_| Ok(EmptyResponse {}));
-        let (merge_planner_mailbox, merge_planner_inbox) = universe.create_test_mailbox();
-
-        let (source_mailbox, source_inbox) = universe.create_test_mailbox();
-
         let publisher = Publisher::new(
             PublisherType::MainPublisher,
             MetastoreServiceClient::from(mock_metastore),
-            Some(merge_planner_mailbox),
-            Some(source_mailbox),
+            None,
+            None,
         );
         let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
 
-        assert!(publisher_mailbox
+        let index_id = "test-index-searchable-latency";
+        let oldest_doc_instant = Instant::now().checked_sub(Duration::from_secs(2)).unwrap();
+        publisher_mailbox
             .send_message(SplitsUpdate {
-                index_uid: "index:11111111111111111111111111".to_string().into(),
-                new_splits: Vec::new(),
+                index_uid: format!("{index_id}:11111111111111111111111111").into(),
+                new_splits: vec![
+                    SplitMetadata {
+                        split_id: "split-1".to_string(),
+                        ..Default::default()
+                    },
+                    SplitMetadata {
+                        split_id: "split-2".to_string(),
+                        ..Default::default()
+                    },
+                ],
                 replaced_split_ids: Vec::new(),
-                checkpoint_delta_opt: Some(IndexCheckpointDelta {
-                    source_id: "source".to_string(),
-                    source_delta: SourceCheckpointDelta::from_range(1..3),
-                }),
+                checkpoint_delta_opt: None,
                 publish_lock: PublishLock::default(),
                 publish_token_opt: None,
                 merge_task: None,
                 parent_span: tracing::Span::none(),
+                oldest_doc_instants: vec![oldest_doc_instant, Instant::now()],
+            })
+            .await
+            .unwrap();
+        publisher_handle.process_pending_and_observe().await;
+
+        let searchable_latency_secs = INDEXER_METRICS
+            .searchable_latency_secs
+            .with_label_values([index_id]);
+        assert_eq!(searchable_latency_secs.get_sample_count(), 2);
+
+        let sample_sum = searchable_latency_secs.get_sample_sum();
+        assert!(sample_sum >= 2.0);
+        assert!(sample_sum < 60.0);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_publisher_publish_operation_with_empty_splits() {
+        let universe = Universe::with_accelerated_time();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_publish_splits()
+            .withf(|publish_splits_request| {
+                let checkpoint_delta: IndexCheckpointDelta = publish_splits_request
+                    .deserialize_index_checkpoint()
+                    .unwrap()
+                    .unwrap();
+                publish_splits_request.index_uid == "index:11111111111111111111111111"
+                    && checkpoint_delta.source_id == "source"
+                    && publish_splits_request.staged_split_ids.is_empty()
+                    && publish_splits_request.replaced_split_ids.is_empty()
+                    && checkpoint_delta.source_delta == SourceCheckpointDelta::from_range(1..3)
+            })
+            .times(1)
+            .returning(|_| Ok(EmptyResponse {}));
+        let (merge_planner_mailbox, merge_planner_inbox) = universe.create_test_mailbox();
+
+        let (source_mailbox, source_inbox) = universe.create_test_mailbox();
+
+        let publisher = Publisher::new(
+            PublisherType::MainPublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            Some(merge_planner_mailbox),
+            Some(source_mailbox),
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+
+        assert!(publisher_mailbox
+            .send_message(SplitsUpdate {
+                index_uid: "index:11111111111111111111111111".to_string().into(),
+                new_splits: Vec::new(),
+                replaced_split_ids: Vec::new(),
+                checkpoint_delta_opt: Some(IndexCheckpointDelta {
+                    source_id: "source".to_string(),
+                    source_delta: SourceCheckpointDelta::from_range(1..3),
+                }),
+                publish_lock: PublishLock::default(),
+                publish_token_opt: None,
+                merge_task: None,
+                parent_span: tracing::Span::none(),
+                oldest_doc_instants: Vec::new(),
             })
             .await
             .is_ok());
@@ -336,7 +1029,7 @@ mod tests {
         let suggest_truncate_checkpoints: Vec<SourceCheckpoint> = source_inbox
             .drain_for_test_typed::<SuggestTruncate>()
             .into_iter()
-            .map(|msg| msg.0)
+            .map(|msg| msg.checkpoint)
             .collect();
 
         assert_eq!(suggest_truncate_checkpoints.len(), 1);
@@ -352,6 +1045,51 @@ mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_publisher_skips_empty_splits_update() {
+        let universe = Universe::with_accelerated_time();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore.expect_publish_splits().never();
+        let (merge_planner_mailbox, merge_planner_inbox) = universe.create_test_mailbox();
+        let (source_mailbox, source_inbox) = universe.create_test_mailbox();
+
+        let publisher = Publisher::new(
+            PublisherType::MainPublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            Some(merge_planner_mailbox),
+            Some(source_mailbox),
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+
+        publisher_mailbox
+            .send_message(SplitsUpdate {
+                index_uid: "index:11111111111111111111111111".to_string().into(),
+                new_splits: Vec::new(),
+                replaced_split_ids: Vec::new(),
+                checkpoint_delta_opt: None,
+                publish_lock: PublishLock::default(),
+                publish_token_opt: None,
+                merge_task: None,
+                parent_span: Span::none(),
+                oldest_doc_instants: Vec::new(),
+            })
+            .await
+            .unwrap();
+
+        let publisher_observation = publisher_handle.process_pending_and_observe().await.state;
+        assert_eq!(publisher_observation.num_published_splits, 0);
+        assert_eq!(publisher_observation.num_empty_splits, 0);
+        assert_eq!(publisher_observation.num_replace_operations, 0);
+
+        assert!(source_inbox
+            .drain_for_test_typed::<SuggestTruncate>()
+            .is_empty());
+        assert!(merge_planner_inbox
+            .drain_for_test_typed::<NewSplits>()
+            .is_empty());
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_publisher_replace_operation() {
         let universe = Universe::with_accelerated_time();
@@ -388,6 +1126,7 @@ mod tests {
             publish_token_opt: None,
             merge_task: None,
             parent_span: Span::none(),
+            oldest_doc_instants: Vec::new(),
         };
         assert!(publisher_mailbox
             .send_message(publisher_message)
@@ -402,6 +1141,725 @@ mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_publisher_publishes_drained_splits_updates_at_once() {
+        let universe = Universe::with_accelerated_time();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_publish_splits()
+            .withf(|publish_splits_request| {
+                publish_splits_request.index_uid == "index:11111111111111111111111111"
+                    && publish_splits_request.staged_split_ids[..] == ["split1", "split2"]
+                    && publish_splits_request.replaced_split_ids.is_empty()
+            })
+            .times(1)
+            .returning(|_| Ok(EmptyResponse {}));
+        let (merge_planner_mailbox, merge_planner_inbox) = universe.create_test_mailbox();
+        let publisher = Publisher::new(
+            PublisherType::MergePublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            Some(merge_planner_mailbox),
+            None,
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        let publish_lock = PublishLock::default();
+
+        publisher_handle.pause();
+        let mut reply_rxs = Vec::new();
+
+        for split_id in ["split1", "split2"] {
+            let splits_update = SplitsUpdate {
+                publish_lock: publish_lock.clone(),
+                ..splits_update_for_test(split_id)
+            };
+            let reply_rx = publisher_mailbox.send_message(splits_update).await.unwrap();
+            reply_rxs.push(reply_rx);
+        }
+        publisher_handle.resume();
+
+        // Each sender gets a reply once its update is published.
+        for reply_rx in reply_rxs {
+            reply_rx.await.unwrap();
+        }
+        let publisher_observation = publisher_handle.process_pending_and_observe().await.state;
+        assert_eq!(publisher_observation.num_published_splits, 2);
+
+        let merge_planner_msgs = merge_planner_inbox.drain_for_test_typed::<NewSplits>();
+        assert_eq!(merge_planner_msgs.len(), 1);
+        assert_eq!(merge_planner_msgs[0].new_splits.len(), 2);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_publisher_publishes_merges_one_at_a_time() {
+        let universe = Universe::with_accelerated_time();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        let mut sequence = mockall::Sequence::new();
+        for (new_split_id, replaced_split_id) in [("split2", "split1"), ("split4", "split3")] {
+            mock_metastore
+                .expect_publish_splits()
+                .withf(move |publish_splits_request| {
+                    publish_splits_request.staged_split_ids[..] == [new_split_id]
+                        && publish_splits_request.replaced_split_ids[..] == [replaced_split_id]
+                })
+                .times(1)
+                .in_sequence(&mut sequence)
+                .returning(|_| Ok(EmptyResponse {}));
+        }
+        let (merge_planner_mailbox, merge_planner_inbox) = universe.create_test_mailbox();
+        let publisher = Publisher::new(
+            PublisherType::MergePublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            Some(merge_planner_mailbox),
+            None,
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        let publish_lock = PublishLock::default();
+
+        publisher_handle.pause();
+        for (new_split_id, replaced_split_id) in [("split2", "split1"), ("split4", "split3")] {
+            let splits_update = SplitsUpdate {
+                replaced_split_ids: vec![replaced_split_id.to_string()],
+                publish_lock: publish_lock.clone(),
+                ..splits_update_for_test(new_split_id)
+            };
+            publisher_mailbox.send_message(splits_update).await.unwrap();
+        }
+        publisher_handle.resume();
+
+        let publisher_observation = publisher_handle.process_pending_and_observe().await.state;
+        assert_eq!(publisher_observation.num_replace_operations, 2);
+
+        let merge_planner_msgs = merge_planner_inbox.drain_for_test_typed::<NewSplits>();
+        assert_eq!(merge_planner_msgs.len(), 2);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_publisher_sorts_out_of_order_splits_updates() {
+        let universe = Universe::with_accelerated_time();
+        // These split IDs are most likely generated within the same millisecond.
+        let split_ids: Vec<String> = (0..3).map(|_| new_split_id()).collect();
+        let expected_split_ids = split_ids.clone();
+
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_publish_splits()
+            .withf(move |publish_splits_request| {
+                let checkpoint_delta: IndexCheckpointDelta = publish_splits_request
+                    .deserialize_index_checkpoint()
+                    .unwrap()
+                    .unwrap();
+                publish_splits_request.staged_split_ids == expected_split_ids
+                    && checkpoint_delta.source_delta == SourceCheckpointDelta::from_range(1..7)
+            })
+            .times(1)
+            .returning(|_| Ok(EmptyResponse {}));
+        let (source_mailbox, source_inbox) = universe.create_test_mailbox();
+        let publisher = Publisher::new(
+            PublisherType::MergePublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            None,
+            Some(source_mailbox),
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        let publish_lock = PublishLock::default();
+
+        publisher_handle.pause();
+        for (new_split_ids, checkpoint_range) in [
+            (&[&split_ids[2]][..], 5..7),
+            (&[&split_ids[1], &split_ids[0]][..], 1..5),
+        ] {
+            publisher_mailbox
+                .send_message(SplitsUpdate {
+                    index_uid: "index:11111111111111111111111111".to_string().into(),
+                    new_splits: new_split_ids
+                        .iter()
+                        .map(|split_id| SplitMetadata::for_test(split_id.to_string()))
+                        .collect(),
+                    replaced_split_ids: Vec::new(),
+                    checkpoint_delta_opt: Some(IndexCheckpointDelta {
+                        source_id: "source".to_string(),
+                        source_delta: SourceCheckpointDelta::from_range(checkpoint_range),
+                    }),
+                    publish_lock: publish_lock.clone(),
+                    publish_token_opt: None,
+                    merge_task: None,
+                    parent_span: Span::none(),
+                    oldest_doc_instants: Vec::new(),
+                })
+                .await
+                .unwrap();
+        }
+        publisher_handle.resume();
+
+        let publisher_observation = publisher_handle.process_pending_and_observe().await.state;
+        assert_eq!(publisher_observation.num_published_splits, 2);
+
+        let suggest_truncate_checkpoints: Vec<SourceCheckpoint> = source_inbox
+            .drain_for_test_typed::<SuggestTruncate>()
+            .into_iter()
+            .map(|msg| msg.checkpoint)
+            .collect();
+        assert_eq!(suggest_truncate_checkpoints.len(), 1);
+        assert_eq!(
+            suggest_truncate_checkpoints[0]
+                .position_for_partition(&PartitionId::default())
+                .unwrap(),
+            &Position::offset(6u64)
+        );
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_publisher_keeps_checkpoint_order_over_split_id_order() {
+        let universe = Universe::with_accelerated_time();
+        let split_ids: Vec<String> = (0..2).map(|_| new_split_id()).collect();
+        // The older split carries the later checkpoint delta.
+        let expected_split_ids = vec![split_ids[1].clone(), split_ids[0].clone()];
+
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_publish_splits()
+            .withf(move |publish_splits_request| {
+                let checkpoint_delta: IndexCheckpointDelta = publish_splits_request
+                    .deserialize_index_checkpoint()
+                    .unwrap()
+                    .unwrap();
+                publish_splits_request.staged_split_ids == expected_split_ids
+                    && checkpoint_delta.source_delta == SourceCheckpointDelta::from_range(1..7)
+            })
+            .times(1)
+            .returning(|_| Ok(EmptyResponse {}));
+        let publisher = Publisher::new(
+            PublisherType::MergePublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            None,
+            None,
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        let publish_lock = PublishLock::default();
+
+        publisher_handle.pause();
+        for (split_id, checkpoint_range) in [(&split_ids[1], 1..5), (&split_ids[0], 5..7)] {
+            publisher_mailbox
+                .send_message(SplitsUpdate {
+                    index_uid: "index:11111111111111111111111111".to_string().into(),
+                    new_splits: vec![SplitMetadata::for_test(split_id.to_string())],
+                    replaced_split_ids: Vec::new(),
+                    checkpoint_delta_opt: Some(IndexCheckpointDelta {
+                        source_id: "source".to_string(),
+                        source_delta: SourceCheckpointDelta::from_range(checkpoint_range),
+                    }),
+                    publish_lock: publish_lock.clone(),
+                    publish_token_opt: None,
+                    merge_task: None,
+                    parent_span: Span::none(),
+                    oldest_doc_instants: Vec::new(),
+                })
+                .await
+                .unwrap();
+        }
+        publisher_handle.resume();
+
+        let publisher_observation = publisher_handle.process_pending_and_observe().await.state;
+        assert_eq!(publisher_observation.num_published_splits, 2);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_publisher_force_flush() {
+        let universe = Universe::new();
+        let num_published_splits = Arc::new(AtomicUsize::new(0));
+        let num_published_splits_clone = num_published_splits.clone();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_publish_splits()
+            .returning(move |publish_splits_request| {
+                num_published_splits_clone.fetch_add(
+                    publish_splits_request.staged_split_ids.len(),
+                    Ordering::Relaxed,
+                );
+                Ok(EmptyResponse {})
+            });
+        let publisher = Publisher::new(
+            PublisherType::MergePublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            None,
+            None,
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+
+        for split_id in ["split1", "split2"] {
+            publisher_mailbox
+                .send_message(SplitsUpdate {
+                    index_uid: "index:11111111111111111111111111".to_string().into(),
+                    new_splits: vec![SplitMetadata::for_test(split_id.to_string())],
+                    replaced_split_ids: Vec::new(),
+                    checkpoint_delta_opt: None,
+                    publish_lock: PublishLock::default(),
+                    publish_token_opt: None,
+                    merge_task: None,
+                    parent_span: Span::none(),
+                    oldest_doc_instants: Vec::new(),
+                })
+                .await
+                .unwrap();
+        }
+        let force_flush_rx = publisher_mailbox
+            .send_message_with_high_priority(ForceFlush)
+            .unwrap();
+        force_flush_rx.await.unwrap();
+
+        assert_eq!(num_published_splits.load(Ordering::Relaxed), 2);
+        assert_eq!(publisher_handle.state(), ActorState::Running);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_publisher_flushes_at_max_batch_size() {
+        let universe = Universe::with_accelerated_time();
+        let num_staged_splits_per_publish = Arc::new(Mutex::new(Vec::new()));
+        let num_staged_splits_per_publish_clone = num_staged_splits_per_publish.clone();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_publish_splits()
+            .times(2)
+            .returning(move |publish_splits_request| {
+                num_staged_splits_per_publish_clone
+                    .lock()
+                    .unwrap()
+                    .push(publish_splits_request.staged_split_ids.len());
+                Ok(EmptyResponse {})
+            });
+        let publisher_config = PublisherConfig {
+            max_batch_size: NonZeroUsize::new(2).unwrap(),
+            ..Default::default()
+        };
+        let publisher = Publisher::new_with_config(
+            PublisherType::MergePublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            None,
+            None,
+            publisher_config,
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        let publish_lock = PublishLock::default();
+
+        publisher_handle.pause();
+        for split_id in ["split1", "split2", "split3"] {
+            let splits_update = SplitsUpdate {
+                publish_lock: publish_lock.clone(),
+                oldest_doc_instants: Vec::new(),
+                ..splits_update_for_test(split_id)
+            };
+            publisher_mailbox.send_message(splits_update).await.unwrap();
+        }
+        publisher_handle.resume();
+
+        let publisher_observation = publisher_handle.process_pending_and_observe().await.state;
+        assert_eq!(publisher_observation.num_published_splits, 3);
+        assert_eq!(*num_staged_splits_per_publish.lock().unwrap(), [2, 1]);
+        universe.assert_quit().await;
+    }
+
+    fn splits_update_for_test(split_id: &str) -> SplitsUpdate {
+        SplitsUpdate {
+            index_uid: "index:11111111111111111111111111".to_string().into(),
+            new_splits: vec![SplitMetadata::for_test(split_id.to_string())],
+            replaced_split_ids: Vec::new(),
+            checkpoint_delta_opt: None,
+            publish_lock: PublishLock::default(),
+            publish_token_opt: None,
+            merge_task: None,
+            parent_span: Span::none(),
+            oldest_doc_instants: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publisher_publishes_pending_splits_on_quit() {
+        let universe = Universe::with_accelerated_time();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_publish_splits()
+            .withf(|publish_splits_request| {
+                publish_splits_request.staged_split_ids[..] == ["split"]
+            })
+            .times(1)
+            .returning(|_| Ok(EmptyResponse {}));
+        let publisher = Publisher::new(
+            PublisherType::MainPublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            None,
+            None,
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        publisher_handle.pause();
+        publisher_mailbox
+            .send_message(splits_update_for_test("split"))
+            .await
+            .unwrap();
+
+        let (exit_status, publisher_counters) = publisher_handle.quit().await;
+        assert!(matches!(exit_status, ActorExitStatus::Quit));
+        assert_eq!(publisher_counters.num_published_splits, 1);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_publisher_drops_pending_splits_on_kill() {
+        let universe = Universe::with_accelerated_time();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore.expect_publish_splits().never();
+        let publisher = Publisher::new(
+            PublisherType::MainPublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            None,
+            None,
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        publisher_handle.pause();
+        publisher_mailbox
+            .send_message(splits_update_for_test("split"))
+            .await
+            .unwrap();
+
+        let (exit_status, publisher_counters) = publisher_handle.kill().await;
+        assert!(matches!(exit_status, ActorExitStatus::Killed));
+        assert_eq!(publisher_counters.num_published_splits, 0);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_publisher_publishes_supported_split_format() {
+        let universe = Universe::with_accelerated_time();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_publish_splits()
+            .times(1)
+            .returning(|_| Ok(EmptyResponse {}));
+        let publisher = Publisher::new(
+            PublisherType::MainPublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            None,
+            None,
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        let mut splits_update = splits_update_for_test("split");
+        splits_update.new_splits[0].format_version = SPLIT_FORMAT_VERSION;
+        publisher_mailbox.send_message(splits_update).await.unwrap();
+
+        let publisher_observation = publisher_handle.process_pending_and_observe().await.state;
+        assert_eq!(publisher_observation.num_published_splits, 1);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_publisher_rejects_future_split_format() {
+        let universe = Universe::with_accelerated_time();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore.expect_publish_splits().never();
+        let publisher = Publisher::new(
+            PublisherType::MainPublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            None,
+            None,
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        let mut splits_update = splits_update_for_test("split");
+        splits_update.new_splits[0].format_version = SPLIT_FORMAT_VERSION + 1;
+        publisher_mailbox.send_message(splits_update).await.unwrap();
+
+        let (exit_status, publisher_counters) = publisher_handle.join().await;
+        let ActorExitStatus::Failure(error) = exit_status else {
+            panic!("expected the publisher to fail, got `{exit_status:?}`");
+        };
+        let unsupported_split_format = error.downcast_ref::<UnsupportedSplitFormat>().unwrap();
+        assert_eq!(unsupported_split_format.split_id, "split");
+        assert_eq!(
+            unsupported_split_format.format_version,
+            SPLIT_FORMAT_VERSION + 1
+        );
+        assert_eq!(publisher_counters.num_published_splits, 0);
+        universe.assert_quit().await;
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let mut circuit_breaker = CircuitBreaker::new(3);
+        assert_eq!(circuit_breaker.state(), CircuitBreakerState::Closed);
+
+        circuit_breaker.record_failure();
+        circuit_breaker.record_failure();
+        assert_eq!(circuit_breaker.state(), CircuitBreakerState::Closed);
+
+        circuit_breaker.record_success();
+        circuit_breaker.record_failure();
+        circuit_breaker.record_failure();
+        assert_eq!(circuit_breaker.state(), CircuitBreakerState::Closed);
+
+        circuit_breaker.record_failure();
+        assert_eq!(circuit_breaker.state(), CircuitBreakerState::Open);
+
+        circuit_breaker.half_open();
+        assert_eq!(circuit_breaker.state(), CircuitBreakerState::HalfOpen);
+
+        // A failed probe opens the breaker again.
+        circuit_breaker.record_failure();
+        assert_eq!(circuit_breaker.state(), CircuitBreakerState::Open);
+
+        circuit_breaker.half_open();
+        circuit_breaker.record_success();
+        assert_eq!(circuit_breaker.state(), CircuitBreakerState::Closed);
+
+        // Half-opening a closed breaker is a no-op.
+        circuit_breaker.half_open();
+        assert_eq!(circuit_breaker.state(), CircuitBreakerState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_publisher_circuit_breaker_probes_metastore_after_cooldown() {
+        let universe = Universe::with_accelerated_time();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        let mut num_calls = 0;
+        mock_metastore
+            .expect_publish_splits()
+            .times(CIRCUIT_BREAKER_FAILURE_THRESHOLD + 2)
+            .returning(move |_| {
+                num_calls += 1;
+                // The breaker trips after the first failures, and the first probe fails too.
+                if num_calls <= CIRCUIT_BREAKER_FAILURE_THRESHOLD + 1 {
+                    return Err(MetastoreError::Unavailable("metastore is down".to_string()));
+                }
+                Ok(EmptyResponse {})
+            });
+        let publisher = Publisher::new(
+            PublisherType::MainPublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            None,
+            None,
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        publisher_mailbox
+            .send_message(splits_update_for_test("split"))
+            .await
+            .unwrap();
+
+        let publisher_observation = publisher_handle.process_pending_and_observe().await.state;
+        assert_eq!(publisher_observation.num_published_splits, 1);
+        assert_eq!(
+            publisher_observation.circuit_breaker_state,
+            CircuitBreakerState::Closed
+        );
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_publisher_fails_on_missing_index() {
+        let universe = Universe::with_accelerated_time();
+        let mut metastore = metastore_for_test();
+        assert!(!metastore.index_exists("index").await.unwrap());
+
+        let publisher = Publisher::new(PublisherType::MainPublisher, metastore, None, None);
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        publisher_mailbox
+            .send_message(splits_update_for_test("split"))
+            .await
+            .unwrap();
+
+        let (exit_status, publisher_counters) = publisher_handle.join().await;
+        let ActorExitStatus::Failure(error) = exit_status else {
+            panic!("the publisher should have failed, got exit status `{exit_status:?}`");
+        };
+        assert!(matches!(
+            error.downcast_ref::<MetastoreError>(),
+            Some(MetastoreError::NotFound(EntityKind::Index { index_id })) if index_id == "index"
+        ));
+        assert_eq!(publisher_counters.num_published_splits, 0);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_publisher_publishes_to_existing_index() {
+        let universe = Universe::with_accelerated_time();
+        let mut metastore = metastore_for_test();
+        let index_config = IndexConfig::for_test("index", "ram:///indexes/index");
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        let index_uid: IndexUid = metastore
+            .create_index(create_index_request)
+            .await
+            .unwrap()
+            .index_uid
+            .into();
+        assert!(metastore.index_exists("index").await.unwrap());
+
+        let split_metadata = SplitMetadata::for_test("split".to_string());
+        let stage_splits_request =
+            StageSplitsRequest::try_from_split_metadata(index_uid.clone(), &split_metadata)
+                .unwrap();
+        metastore.stage_splits(stage_splits_request).await.unwrap();
+
+        let publisher = Publisher::new(PublisherType::MainPublisher, metastore.clone(), None, None);
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        publisher_mailbox
+            .send_message(SplitsUpdate {
+                index_uid: index_uid.clone(),
+                ..splits_update_for_test("split")
+            })
+            .await
+            .unwrap();
+
+        let publisher_observation = publisher_handle.process_pending_and_observe().await.state;
+        assert_eq!(publisher_observation.num_published_splits, 1);
+
+        let list_splits_request = ListSplitsRequest::try_from_index_uid(index_uid).unwrap();
+        let splits = metastore
+            .list_splits(list_splits_request)
+            .await
+            .unwrap()
+            .collect_splits()
+            .await
+            .unwrap();
+        assert_eq!(splits.len(), 1);
+        assert_eq!(splits[0].split_metadata.split_id, "split");
+        assert_eq!(splits[0].split_state, SplitState::Published);
+        universe.assert_quit().await;
+    }
+
+    #[test]
+    fn test_retry_budget() {
+        let mut retry_budget = RetryBudget::new(2);
+        assert!(retry_budget.try_acquire());
+        assert!(retry_budget.try_acquire());
+        assert!(!retry_budget.try_acquire());
+        assert!(!retry_budget.try_acquire());
+        assert_eq!(retry_budget.num_shed_retries, 2);
+    }
+
+    #[tokio::test]
+    async fn test_publisher_sheds_retries_once_retry_budget_is_exhausted() {
+        let universe = Universe::with_accelerated_time();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        let mut num_calls = 0;
+        mock_metastore
+            .expect_publish_splits()
+            .times(3)
+            .returning(move |_| {
+                num_calls += 1;
+                // The first failure consumes the whole budget, so the second one trips the
+                // breaker instead of being retried right away.
+                if num_calls <= 2 {
+                    return Err(MetastoreError::Unavailable("metastore is down".to_string()));
+                }
+                Ok(EmptyResponse {})
+            });
+        let publisher_config = PublisherConfig {
+            max_retries_per_minute: NonZeroU32::MIN,
+            ..Default::default()
+        };
+        let publisher = Publisher::new_with_config(
+            PublisherType::MainPublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            None,
+            None,
+            publisher_config,
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        publisher_mailbox
+            .send_message(splits_update_for_test("split"))
+            .await
+            .unwrap();
+
+        let publisher_observation = publisher_handle.process_pending_and_observe().await.state;
+        assert_eq!(publisher_observation.num_published_splits, 1);
+        assert_eq!(publisher_observation.num_shed_retries, 1);
+        assert_eq!(
+            publisher_observation.circuit_breaker_state,
+            CircuitBreakerState::Closed
+        );
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_publisher_fails_after_max_attempts() {
+        let universe = Universe::with_accelerated_time();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_publish_splits()
+            .times(3)
+            .returning(|_| Err(MetastoreError::Unavailable("metastore is down".to_string())));
+        let publisher_config = PublisherConfig {
+            retry_params: RetryParams {
+                max_attempts: 3,
+                ..RetryParams::for_test()
+            },
+            circuit_breaker_failure_threshold: 10,
+            ..Default::default()
+        };
+        let publisher = Publisher::new_with_config(
+            PublisherType::MainPublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            None,
+            None,
+            publisher_config,
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        publisher_mailbox
+            .send_message(splits_update_for_test("split"))
+            .await
+            .unwrap();
+
+        let (exit_status, publisher_counters) = publisher_handle.join().await;
+        let ActorExitStatus::Failure(error) = exit_status else {
+            panic!("the publisher should have failed, got exit status `{exit_status:?}`");
+        };
+        assert!(matches!(
+            error.downcast_ref::<MetastoreError>(),
+            Some(MetastoreError::Unavailable(_))
+        ));
+        assert_eq!(publisher_counters.num_published_splits, 0);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_publisher_fails_on_non_transient_metastore_error() {
+        let universe = Universe::with_accelerated_time();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_publish_splits()
+            .times(1)
+            .returning(|_| {
+                Err(MetastoreError::FailedPrecondition {
+                    entity: EntityKind::Splits {
+                        split_ids: vec!["split".to_string()],
+                    },
+                    message: "splits are not staged".to_string(),
+                })
+            });
+        let publisher = Publisher::new(
+            PublisherType::MainPublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            None,
+            None,
+        );
+        let (publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+        publisher_mailbox
+            .send_message(splits_update_for_test("split"))
+            .await
+            .unwrap();
+
+        let (exit_status, publisher_counters) = publisher_handle.join().await;
+        assert!(matches!(exit_status, ActorExitStatus::Failure(_)));
+        assert_eq!(publisher_counters.num_published_splits, 0);
+        assert_eq!(
+            publisher_counters.circuit_breaker_state,
+            CircuitBreakerState::Closed
+        );
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn publisher_acquires_publish_lock() {
         let universe = Universe::with_accelerated_time();
@@ -430,6 +1888,7 @@ mod tests {
                 publish_token_opt: None,
                 merge_task: None,
                 parent_span: Span::none(),
+                oldest_doc_instants: Vec::new(),
             })
             .await
             .unwrap();
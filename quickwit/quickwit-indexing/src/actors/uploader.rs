@@ -22,6 +22,7 @@ use std::iter::FromIterator;
 use std::mem;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{bail, Context};
 use async_trait::async_trait;
@@ -30,13 +31,14 @@ use itertools::Itertools;
 use once_cell::sync::OnceCell;
 use quickwit_actors::{Actor, ActorContext, ActorExitStatus, Handler, Mailbox, QueueCapacity};
 use quickwit_common::pubsub::EventBroker;
+use quickwit_common::retry::{RetryParams, Retryable};
 use quickwit_common::spawn_named_task;
 use quickwit_metastore::checkpoint::IndexCheckpointDelta;
 use quickwit_metastore::{SplitMetadata, StageSplitsRequestExt};
 use quickwit_proto::metastore::{MetastoreService, MetastoreServiceClient, StageSplitsRequest};
 use quickwit_proto::search::{ReportSplit, ReportSplitsRequest};
 use quickwit_proto::types::{IndexUid, PublishToken};
-use quickwit_storage::SplitPayloadBuilder;
+use quickwit_storage::{SplitPayloadBuilder, StorageError};
 use serde::Serialize;
 use tokio::sync::oneshot::Sender;
 use tokio::sync::{oneshot, Semaphore, SemaphorePermit};
@@ -60,6 +62,14 @@ use crate::split_store::IndexingSplitStore;
 static CONCURRENT_UPLOAD_PERMITS_INDEX: OnceCell<Semaphore> = OnceCell::new();
 static CONCURRENT_UPLOAD_PERMITS_MERGE: OnceCell<Semaphore> = OnceCell::new();
 
+/// Failed split uploads are retried with an exponential backoff. The storage clients already retry
+/// the failed requests: these retries cover the failures that outlast them.
+const UPLOAD_SPLIT_RETRY_PARAMS: RetryParams = RetryParams {
+    base_delay: Duration::from_secs(1),
+    max_delay: Duration::from_secs(30),
+    max_attempts: 5,
+};
+
 #[derive(Clone, Copy, Debug)]
 pub enum UploaderType {
     IndexUploader,
@@ -346,14 +356,18 @@ impl Handler<PackagedSplitBatch> for Uploader {
 
                 let mut packaged_splits_and_metadata = Vec::with_capacity(batch.splits.len());
 
-                event_broker.publish(ReportSplitsRequest { report_splits });
+                event_broker.publish(ReportSplitsRequest {
+                    report_splits,
+                    deleted_split_ids: Vec::new(),
+                });
 
                 for (packaged_split, metadata) in batch.splits.into_iter().zip(split_metadata_list) {
-                    let upload_result = upload_split(
+                    let upload_result = upload_split_with_retry(
                         &packaged_split,
                         &metadata,
                         &split_store,
                         counters.clone(),
+                        &ctx_clone,
                     )
                     .await;
 
@@ -417,6 +431,7 @@ impl Handler<EmptySplit> for Uploader {
             publish_token_opt: empty_split.publish_token_opt,
             merge_task: None,
             parent_span: empty_split.batch_parent_span,
+            oldest_doc_instants: Vec::new(),
         };
 
         split_update_sender.send(splits_update, ctx).await?;
@@ -438,6 +453,10 @@ fn make_publish_operation(
         .iter()
         .flat_map(|(split, _)| split.split_attrs.replaced_split_ids.clone())
         .collect::<HashSet<_>>();
+    let oldest_doc_instants = packaged_splits_and_metadatas
+        .iter()
+        .filter_map(|(split, _)| split.split_attrs.oldest_doc_instant_opt)
+        .collect();
     SplitsUpdate {
         index_uid,
         new_splits: packaged_splits_and_metadatas
@@ -450,9 +469,72 @@ fn make_publish_operation(
         publish_token_opt,
         merge_task,
         parent_span,
+        oldest_doc_instants,
+    }
+}
+
+/// Uploads a split, retrying attempts that failed with a retryable storage error according to
+/// [`UPLOAD_SPLIT_RETRY_PARAMS`]. The split is durably stored once this returns `Ok`.
+async fn upload_split_with_retry(
+    packaged_split: &PackagedSplit,
+    split_metadata: &SplitMetadata,
+    split_store: &IndexingSplitStore,
+    counters: UploaderCounters,
+    ctx: &ActorContext<Uploader>,
+) -> anyhow::Result<()> {
+    let mut num_attempts = 0;
+
+    loop {
+        let upload_result = upload_split(
+            packaged_split,
+            split_metadata,
+            split_store,
+            counters.clone(),
+        )
+        .await;
+
+        let upload_error = match upload_result {
+            Ok(()) => return Ok(()),
+            Err(upload_error) => upload_error,
+        };
+        num_attempts += 1;
+
+        if !is_retryable_upload_error(&upload_error)
+            || num_attempts >= UPLOAD_SPLIT_RETRY_PARAMS.max_attempts
+            || ctx.kill_switch().is_dead()
+        {
+            INDEXER_METRICS
+                .split_upload_errors_total
+                .with_label_values(["failed"])
+                .inc();
+            return Err(upload_error.context(format!(
+                "failed to upload split after {num_attempts} attempt(s)"
+            )));
+        }
+        INDEXER_METRICS
+            .split_upload_errors_total
+            .with_label_values(["retried"])
+            .inc();
+        let delay = UPLOAD_SPLIT_RETRY_PARAMS.compute_delay(num_attempts);
+        warn!(
+            split_id=packaged_split.split_id(),
+            num_attempts,
+            delay_ms=delay.as_millis(),
+            error=?upload_error,
+            "failed to upload split, retrying"
+        );
+        ctx.sleep(delay).await;
     }
 }
 
+/// Only transient storage errors are worth retrying. Other errors, such as missing credentials or
+/// a split that cannot be read from the scratch directory, would fail the same way again.
+fn is_retryable_upload_error(upload_error: &anyhow::Error) -> bool {
+    upload_error
+        .downcast_ref::<StorageError>()
+        .is_some_and(|storage_error| storage_error.is_retryable())
+}
+
 #[instrument(
     level = "info"
     name = "upload",
@@ -485,16 +567,17 @@ async fn upload_split(
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
-    use std::time::Duration;
+    use std::time::{Duration, Instant};
 
     use quickwit_actors::{ObservationType, Universe};
     use quickwit_common::pubsub::EventSubscriber;
     use quickwit_common::temp_dir::TempDirectory;
+    use quickwit_common::uri::Uri;
     use quickwit_metastore::checkpoint::{IndexCheckpointDelta, SourceCheckpointDelta};
     use quickwit_proto::indexing::IndexingPipelineId;
     use quickwit_proto::metastore::EmptyResponse;
     use quickwit_proto::types::PipelineUid;
-    use quickwit_storage::RamStorage;
+    use quickwit_storage::{MockStorage, RamStorage, StorageErrorKind};
     use tantivy::DateTime;
     use tokio::sync::oneshot;
 
@@ -547,6 +630,7 @@ mod tests {
             source_id: "test-source".to_string(),
             source_delta: SourceCheckpointDelta::from_range(3..15),
         });
+        let oldest_doc_instant = Instant::now();
         uploader_mailbox
             .send_message(PackagedSplitBatch::new(
                 vec![PackagedSplit {
@@ -563,6 +647,8 @@ mod tests {
                         split_id: "test-split".to_string(),
                         delete_opstamp: 10,
                         num_merge_ops: 0,
+                        backfill: false,
+                        oldest_doc_instant_opt: Some(oldest_doc_instant),
                     },
                     serialized_split_fields: Vec::new(),
                     split_scratch_directory,
@@ -597,6 +683,7 @@ mod tests {
             new_splits,
             checkpoint_delta_opt,
             replaced_split_ids,
+            oldest_doc_instants,
             ..
         } = publisher_message;
 
@@ -610,6 +697,7 @@ mod tests {
             SourceCheckpointDelta::from_range(3..15)
         );
         assert!(replaced_split_ids.is_empty());
+        assert_eq!(oldest_doc_instants, [oldest_doc_instant]);
         let mut files = ram_storage.list_files().await;
         files.sort();
         assert_eq!(&files, &[PathBuf::from("test-split.split")]);
@@ -675,6 +763,8 @@ mod tests {
                 ],
                 delete_opstamp: 0,
                 num_merge_ops: 0,
+                backfill: false,
+                oldest_doc_instant_opt: None,
             },
             serialized_split_fields: Vec::new(),
             split_scratch_directory: split_scratch_directory_1,
@@ -699,6 +789,8 @@ mod tests {
                 ],
                 delete_opstamp: 0,
                 num_merge_ops: 0,
+                backfill: false,
+                oldest_doc_instant_opt: None,
             },
             serialized_split_fields: Vec::new(),
             split_scratch_directory: split_scratch_directory_2,
@@ -817,6 +909,8 @@ mod tests {
                         split_id: "test-split".to_string(),
                         delete_opstamp: 10,
                         num_merge_ops: 0,
+                        backfill: false,
+                        oldest_doc_instant_opt: None,
                     },
                     serialized_split_fields: Vec::new(),
                     split_scratch_directory,
@@ -849,6 +943,177 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_uploader_retries_failed_upload() -> anyhow::Result<()> {
+        let pipeline_id = IndexingPipelineId {
+            index_uid: IndexUid::from("test-index-retry:11111111111111111111111111"),
+            source_id: "test-source".to_string(),
+            node_id: "test-node".to_string(),
+            pipeline_uid: PipelineUid::default(),
+        };
+        let universe = Universe::with_accelerated_time();
+        let (publisher_mailbox, publisher_inbox) = universe.create_test_mailbox::<Publisher>();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_stage_splits()
+            .times(1)
+            .returning(|_| Ok(EmptyResponse {}));
+        let mut mock_storage = MockStorage::default();
+        mock_storage
+            .expect_uri()
+            .return_const(Uri::for_test("ram:///indexes"));
+        let num_put_attempts = Arc::new(AtomicU64::new(0));
+        let num_put_attempts_clone = num_put_attempts.clone();
+        mock_storage
+            .expect_put()
+            .times(2)
+            .returning(move |_path, _put_payload| {
+                if num_put_attempts_clone.fetch_add(1, Ordering::SeqCst) == 0 {
+                    return Err(
+                        StorageErrorKind::Io.with_error(anyhow::anyhow!("connection reset"))
+                    );
+                }
+                Ok(())
+            });
+        let split_store =
+            IndexingSplitStore::create_without_local_store_for_test(Arc::new(mock_storage));
+        let uploader = Uploader::new(
+            UploaderType::IndexUploader,
+            MetastoreServiceClient::from(mock_metastore),
+            Arc::new(NopMergePolicy),
+            split_store,
+            SplitsUpdateMailbox::Publisher(publisher_mailbox),
+            4,
+            EventBroker::default(),
+        );
+        let (uploader_mailbox, uploader_handle) = universe.spawn_builder().spawn(uploader);
+        uploader_mailbox
+            .send_message(PackagedSplitBatch::new(
+                vec![PackagedSplit {
+                    split_attrs: SplitAttrs {
+                        partition_id: 3u64,
+                        pipeline_id,
+                        time_range: None,
+                        uncompressed_docs_size_in_bytes: 1_000,
+                        num_docs: 10,
+                        replaced_split_ids: Vec::new(),
+                        split_id: "test-split".to_string(),
+                        delete_opstamp: 10,
+                        num_merge_ops: 0,
+                        backfill: false,
+                        oldest_doc_instant_opt: None,
+                    },
+                    serialized_split_fields: Vec::new(),
+                    split_scratch_directory: TempDirectory::for_test(),
+                    tags: Default::default(),
+                    hotcache_bytes: Vec::new(),
+                    split_files: Vec::new(),
+                }],
+                None,
+                PublishLock::default(),
+                None,
+                None,
+                Span::none(),
+            ))
+            .await?;
+        let SplitsUpdate { new_splits, .. } = publisher_inbox.recv_typed_message().await.unwrap();
+        assert_eq!(new_splits.len(), 1);
+        assert_eq!(new_splits[0].split_id(), "test-split");
+        assert_eq!(num_put_attempts.load(Ordering::SeqCst), 2);
+
+        let uploader_observation = uploader_handle.process_pending_and_observe().await;
+        assert_eq!(uploader_observation.obs_type, ObservationType::Alive);
+        assert_eq!(
+            uploader_observation
+                .state
+                .num_uploaded_splits
+                .load(Ordering::SeqCst),
+            1
+        );
+        universe.assert_quit().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_uploader_does_not_retry_permanent_upload_errors() -> anyhow::Result<()> {
+        let pipeline_id = IndexingPipelineId {
+            index_uid: IndexUid::from("test-index-no-retry:11111111111111111111111111"),
+            source_id: "test-source".to_string(),
+            node_id: "test-node".to_string(),
+            pipeline_uid: PipelineUid::default(),
+        };
+        let universe = Universe::with_accelerated_time();
+        let (publisher_mailbox, publisher_inbox) = universe.create_test_mailbox::<Publisher>();
+        let mut mock_metastore = MetastoreServiceClient::mock();
+        mock_metastore
+            .expect_stage_splits()
+            .times(1)
+            .returning(|_| Ok(EmptyResponse {}));
+        let mut mock_storage = MockStorage::default();
+        mock_storage
+            .expect_uri()
+            .return_const(Uri::for_test("ram:///indexes"));
+        let num_put_attempts = Arc::new(AtomicU64::new(0));
+        let num_put_attempts_clone = num_put_attempts.clone();
+        mock_storage
+            .expect_put()
+            .times(1)
+            .returning(move |_path, _put_payload| {
+                num_put_attempts_clone.fetch_add(1, Ordering::SeqCst);
+                Err(StorageErrorKind::Unauthorized.with_error(anyhow::anyhow!("access denied")))
+            });
+        let split_store =
+            IndexingSplitStore::create_without_local_store_for_test(Arc::new(mock_storage));
+        let uploader = Uploader::new(
+            UploaderType::IndexUploader,
+            MetastoreServiceClient::from(mock_metastore),
+            Arc::new(NopMergePolicy),
+            split_store,
+            SplitsUpdateMailbox::Publisher(publisher_mailbox),
+            4,
+            EventBroker::default(),
+        );
+        let (uploader_mailbox, uploader_handle) = universe.spawn_builder().spawn(uploader);
+        uploader_mailbox
+            .send_message(PackagedSplitBatch::new(
+                vec![PackagedSplit {
+                    split_attrs: SplitAttrs {
+                        partition_id: 3u64,
+                        pipeline_id,
+                        time_range: None,
+                        uncompressed_docs_size_in_bytes: 1_000,
+                        num_docs: 10,
+                        replaced_split_ids: Vec::new(),
+                        split_id: "test-split".to_string(),
+                        delete_opstamp: 10,
+                        num_merge_ops: 0,
+                        backfill: false,
+                        oldest_doc_instant_opt: None,
+                    },
+                    serialized_split_fields: Vec::new(),
+                    split_scratch_directory: TempDirectory::for_test(),
+                    tags: Default::default(),
+                    hotcache_bytes: Vec::new(),
+                    split_files: Vec::new(),
+                }],
+                None,
+                PublishLock::default(),
+                None,
+                None,
+                Span::none(),
+            ))
+            .await?;
+        // Leaves enough time for all the retries to happen if the error were deemed retryable.
+        universe.sleep(Duration::from_secs(60)).await;
+        assert_eq!(num_put_attempts.load(Ordering::SeqCst), 1);
+        assert!(publisher_inbox.drain_for_test().is_empty());
+
+        let (exit_status, _) = uploader_handle.quit().await;
+        assert!(matches!(exit_status, ActorExitStatus::Killed));
+        universe.assert_quit().await;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_uploader_with_empty_splits() -> anyhow::Result<()> {
         let universe = Universe::new();
@@ -996,6 +1261,8 @@ mod tests {
                         split_id: SPLIT_ULID_STR.to_string(),
                         delete_opstamp: 10,
                         num_merge_ops: 0,
+                        backfill: false,
+                        oldest_doc_instant_opt: None,
                     },
                     serialized_split_fields: Vec::new(),
                     split_scratch_directory,
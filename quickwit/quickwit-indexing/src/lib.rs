@@ -19,6 +19,9 @@
 
 #![deny(clippy::disallowed_methods)]
 
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
 use quickwit_actors::{Mailbox, Universe};
 use quickwit_cluster::Cluster;
 use quickwit_common::pubsub::EventBroker;
@@ -28,6 +31,7 @@ use quickwit_proto::indexing::PipelineMetrics;
 use quickwit_proto::metastore::MetastoreServiceClient;
 use quickwit_storage::StorageResolver;
 use tracing::info;
+use ulid::{Generator, Ulid};
 
 use crate::actors::MergeSchedulerService;
 pub use crate::actors::{
@@ -60,8 +64,19 @@ pub use self::source::check_source_connectivity;
 /// Schema used for the OpenAPI generation which are apart of this crate.
 pub struct IndexingApiSchemas;
 
+/// Returns a new split ID. Split IDs are ULIDs generated monotonically, so that the IDs of the
+/// splits created by this process sort in creation order, even within the same millisecond.
 pub fn new_split_id() -> String {
-    ulid::Ulid::new().to_string()
+    static SPLIT_ID_GENERATOR: Lazy<Mutex<Generator>> = Lazy::new(|| Mutex::new(Generator::new()));
+
+    let mut split_id_generator = SPLIT_ID_GENERATOR
+        .lock()
+        .expect("the lock should not be poisoned");
+    split_id_generator
+        .generate()
+        // The random part of the ULID overflowed, which in practice never happens.
+        .unwrap_or_else(|_| Ulid::new())
+        .to_string()
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -98,3 +113,25 @@ pub async fn start_indexing_service(
     let (indexing_service, _) = universe.spawn_builder().spawn(indexing_service);
     Ok(indexing_service)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_split_id_is_monotonic() {
+        let split_ids: Vec<String> = (0..1_000).map(|_| new_split_id()).collect();
+
+        for split_id_pair in split_ids.windows(2) {
+            assert!(split_id_pair[0] < split_id_pair[1]);
+        }
+        // The first 10 characters of a ULID encode its timestamp in milliseconds, so this checks
+        // that some of the split IDs were generated within the same millisecond.
+        let num_distinct_timestamps = split_ids
+            .iter()
+            .map(|split_id| &split_id[..10])
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+        assert!(num_distinct_timestamps < split_ids.len());
+    }
+}
@@ -19,7 +19,8 @@
 
 use once_cell::sync::Lazy;
 use quickwit_common::metrics::{
-    new_counter_vec, new_gauge, new_gauge_vec, IntCounterVec, IntGauge, IntGaugeVec,
+    new_counter_vec, new_gauge, new_gauge_vec, new_histogram_vec_with_buckets, HistogramVec,
+    IntCounterVec, IntGauge, IntGaugeVec,
 };
 
 pub struct IndexerMetrics {
@@ -27,9 +28,11 @@ pub struct IndexerMetrics {
     pub processed_bytes: IntCounterVec<2>,
     pub backpressure_micros: IntCounterVec<1>,
     pub available_concurrent_upload_permits: IntGaugeVec<1>,
+    pub split_upload_errors_total: IntCounterVec<1>,
     pub ongoing_merge_operations: IntGauge,
     pub pending_merge_operations: IntGauge,
     pub pending_merge_bytes: IntGauge,
+    pub searchable_latency_secs: HistogramVec<1>,
 }
 
 impl Default for IndexerMetrics {
@@ -62,6 +65,12 @@ impl Default for IndexerMetrics {
                 "quickwit_indexing",
                 ["component"],
             ),
+            split_upload_errors_total: new_counter_vec(
+                "split_upload_errors_total",
+                "Number of failed split upload attempts by outcome in [retried, failed]",
+                "quickwit_indexing",
+                ["outcome"],
+            ),
             ongoing_merge_operations: new_gauge(
                 "ongoing_merge_operations",
                 "Number of ongoing merge operations",
@@ -77,6 +86,16 @@ impl Default for IndexerMetrics {
                 "Number of pending merge bytes",
                 "quickwit_indexing",
             ),
+            searchable_latency_secs: new_histogram_vec_with_buckets(
+                "searchable_latency_secs",
+                "Time elapsed between the processing of the oldest document of a split and the \
+                 publication of the split, in seconds, by index",
+                "quickwit_indexing",
+                ["index"],
+                vec![
+                    0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0, 3600.0,
+                ],
+            ),
         }
     }
 }
@@ -110,6 +110,8 @@ impl IndexedSplitBuilder {
                 time_range: None,
                 delete_opstamp: last_delete_opstamp,
                 num_merge_ops: 0,
+                backfill: false,
+                oldest_doc_instant_opt: None,
             },
             index_writer,
             split_scratch_directory,
@@ -167,6 +169,8 @@ pub struct IndexedSplitBatch {
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum CommitTrigger {
+    /// The next batch does not share the backfill status of the current workbench.
+    BackfillBoundary,
     Drained,
     ForceCommit,
     MemoryLimit,
@@ -18,6 +18,7 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use std::fmt;
+use std::time::Instant;
 
 use quickwit_metastore::checkpoint::SourceCheckpointDelta;
 use tantivy::{DateTime, TantivyDocument};
@@ -43,6 +44,10 @@ pub struct ProcessedDocBatch {
     pub docs: Vec<ProcessedDoc>,
     pub checkpoint_delta: SourceCheckpointDelta,
     pub force_commit: bool,
+    /// See [`crate::models::RawDocBatch::backfill`].
+    pub backfill: bool,
+    /// Instant at which the `DocProcessor` finished processing the batch.
+    pub processed_at: Instant,
 }
 
 impl fmt::Debug for ProcessedDocBatch {
@@ -51,6 +56,7 @@ impl fmt::Debug for ProcessedDocBatch {
             .field("num_docs", &self.docs.len())
             .field("checkpoint_delta", &self.checkpoint_delta)
             .field("force_commit", &self.force_commit)
+            .field("backfill", &self.backfill)
             .finish()
     }
 }
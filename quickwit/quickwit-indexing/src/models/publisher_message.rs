@@ -18,6 +18,7 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use std::fmt;
+use std::time::Instant;
 
 use itertools::Itertools;
 use quickwit_metastore::checkpoint::IndexCheckpointDelta;
@@ -41,6 +42,9 @@ pub struct SplitsUpdate {
     /// If `None`, the split batch was built in the `IndexingPipeline`.
     pub merge_task: Option<MergeTask>,
     pub parent_span: Span,
+    /// For each new split built in the `IndexingPipeline`, the instant at which its oldest
+    /// document was processed. Used to measure how long documents take to become searchable.
+    pub oldest_doc_instants: Vec<Instant>,
 }
 
 impl fmt::Debug for SplitsUpdate {
@@ -27,6 +27,9 @@ pub struct RawDocBatch {
     pub docs: Vec<Bytes>,
     pub checkpoint_delta: SourceCheckpointDelta,
     pub force_commit: bool,
+    /// Backfill batches do not carry a checkpoint delta: the splits built from their documents
+    /// are published without updating the checkpoint of the source.
+    pub backfill: bool,
 }
 
 impl RawDocBatch {
@@ -39,6 +42,7 @@ impl RawDocBatch {
             docs,
             checkpoint_delta,
             force_commit,
+            backfill: false,
         }
     }
 
@@ -47,6 +51,7 @@ impl RawDocBatch {
             docs: Vec::with_capacity(capacity),
             checkpoint_delta: SourceCheckpointDelta::default(),
             force_commit: false,
+            backfill: false,
         }
     }
 
@@ -66,6 +71,7 @@ impl RawDocBatch {
             docs,
             checkpoint_delta,
             force_commit: false,
+            backfill: false,
         }
     }
 }
@@ -77,6 +83,7 @@ impl fmt::Debug for RawDocBatch {
             .field("num_docs", &self.num_docs())
             .field("checkpoint_delta", &self.checkpoint_delta)
             .field("force_commit", &self.force_commit)
+            .field("backfill", &self.backfill)
             .finish()
     }
 }
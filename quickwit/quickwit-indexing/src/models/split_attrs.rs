@@ -21,8 +21,9 @@ use std::collections::BTreeSet;
 use std::fmt;
 use std::ops::{Range, RangeInclusive};
 use std::sync::Arc;
+use std::time::Instant;
 
-use quickwit_metastore::SplitMetadata;
+use quickwit_metastore::{SplitMetadata, SPLIT_FORMAT_VERSION};
 use quickwit_proto::indexing::IndexingPipelineId;
 use tantivy::DateTime;
 use time::OffsetDateTime;
@@ -63,6 +64,13 @@ pub struct SplitAttrs {
 
     // Number of merge operation the split has been through so far.
     pub num_merge_ops: usize,
+
+    /// Whether the split was built from backfilled documents.
+    pub backfill: bool,
+
+    /// Instant at which the oldest document of the split was processed by the `DocProcessor`.
+    /// `None` for the splits produced by merges.
+    pub oldest_doc_instant_opt: Option<Instant>,
 }
 
 impl fmt::Debug for SplitAttrs {
@@ -78,6 +86,7 @@ impl fmt::Debug for SplitAttrs {
             )
             .field("num_docs", &self.num_docs)
             .field("num_merge_ops", &self.num_merge_ops)
+            .field("backfill", &self.backfill)
             .finish()
     }
 }
@@ -109,5 +118,7 @@ pub fn create_split_metadata(
         footer_offsets,
         delete_opstamp: split_attrs.delete_opstamp,
         num_merge_ops: split_attrs.num_merge_ops,
+        format_version: SPLIT_FORMAT_VERSION,
+        backfill: split_attrs.backfill,
     }
 }
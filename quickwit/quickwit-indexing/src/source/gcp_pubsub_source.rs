@@ -215,6 +215,7 @@ impl Source for GcpPubSubSource {
     async fn suggest_truncate(
         &mut self,
         _checkpoint: SourceCheckpoint,
+        _published_split_ids: &[String],
         _ctx: &ActorContext<SourceActor>,
     ) -> anyhow::Result<()> {
         // TODO: add ack of ids
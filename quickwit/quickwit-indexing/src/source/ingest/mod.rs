@@ -628,6 +628,7 @@ impl Source for IngestSource {
     async fn suggest_truncate(
         &mut self,
         checkpoint: SourceCheckpoint,
+        _published_split_ids: &[String],
         _ctx: &SourceContext,
     ) -> anyhow::Result<()> {
         let mut truncate_up_to_positions: Vec<(ShardId, Position)> =
@@ -1843,7 +1844,10 @@ mod tests {
             (5u64.into(), Position::Beginning),
             (6u64.into(), Position::offset(66u64)),
         ]);
-        source.suggest_truncate(checkpoint, &ctx).await.unwrap();
+        source
+            .suggest_truncate(checkpoint, &[], &ctx)
+            .await
+            .unwrap();
 
         let local_shards_update = shard_positions_update_rx.recv().await.unwrap();
         let expected_local_shards_update = LocalShardPositionsUpdate::new(
@@ -52,6 +52,11 @@ pub struct IngestApiSourceCounters {
     /// Maintains the value of where we are in queue and allows
     /// setting the upper-bound of the checkpoint delta.
     pub current_offset: Option<u64>,
+    /// Position of the last backfill doc recorded in the backfill partition of the checkpoint.
+    /// Backfill docs up to this position are skipped when the queue is read again, for instance
+    /// after a restart.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backfill_offset: Option<u64>,
     pub num_docs_processed: u64,
 }
 
@@ -59,10 +64,19 @@ pub struct IngestApiSource {
     runtime_args: Arc<SourceRuntimeArgs>,
     source_id: String,
     partition_id: PartitionId,
+    /// Partition tracking the backfill docs of the queue, see [`backfill_partition_id`].
+    backfill_partition_id: PartitionId,
     ingest_api_service: Mailbox<IngestApiService>,
     counters: IngestApiSourceCounters,
 }
 
+/// Returns the ID of the partition in which the progress of the backfill docs of the queue
+/// `partition_id` is recorded. Keeping it apart from `partition_id` lets backfills be published
+/// without moving the live checkpoint.
+fn backfill_partition_id(partition_id: &PartitionId) -> PartitionId {
+    format!("{partition_id}-backfill").into()
+}
+
 impl fmt::Debug for IngestApiSource {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "IngestApiSource {{ source_id: {} }}", self.source_id)
@@ -89,14 +103,20 @@ impl IngestApiSource {
             .position_for_partition(&partition_id)
             .map(|position| position.as_u64().expect("offset should be stored as u64"));
         let current_offset = previous_offset;
+        let backfill_partition_id = backfill_partition_id(&partition_id);
+        let backfill_offset: Option<u64> = checkpoint
+            .position_for_partition(&backfill_partition_id)
+            .map(|position| position.as_u64().expect("offset should be stored as u64"));
         let ingest_api_source = IngestApiSource {
             runtime_args,
             source_id,
             partition_id,
+            backfill_partition_id,
             ingest_api_service,
             counters: IngestApiSourceCounters {
                 previous_offset,
                 current_offset,
+                backfill_offset,
                 num_docs_processed: 0,
             },
         };
@@ -106,11 +126,13 @@ impl IngestApiSource {
     async fn send_suggest_truncate_to_ingest_service(
         &self,
         up_to_position_included: u64,
+        published_split_ids: &[String],
         ctx: &ActorContext<SourceActor>,
     ) -> anyhow::Result<()> {
         let suggest_truncate_req = SuggestTruncateRequest {
             index_id: self.runtime_args.index_id().to_string(),
             up_to_position_included,
+            published_split_ids: published_split_ids.to_vec(),
         };
         ctx.ask_for_res(&self.ingest_api_service, suggest_truncate_req)
             .await?;
@@ -122,6 +144,13 @@ impl IngestApiSource {
         self.counters.current_offset = Some(current_offset);
         self.counters.previous_offset = Some(current_offset);
     }
+
+    /// Unlike [`Self::update_counters`], leaves `previous_offset` untouched: backfill docs only
+    /// advance the backfill partition of the checkpoint.
+    fn update_counters_for_backfill(&mut self, current_offset: u64, num_docs: u64) {
+        self.counters.num_docs_processed += num_docs;
+        self.counters.current_offset = Some(current_offset);
+    }
 }
 
 #[async_trait]
@@ -132,7 +161,7 @@ impl Source for IngestApiSource {
         ctx: &SourceContext,
     ) -> Result<(), ActorExitStatus> {
         if let Some(position) = self.counters.previous_offset {
-            self.send_suggest_truncate_to_ingest_service(position, ctx)
+            self.send_suggest_truncate_to_ingest_service(position, &[], ctx)
                 .await?;
         }
         Ok(())
@@ -168,13 +197,56 @@ impl Source for IngestApiSource {
 
         // TODO use a timestamp (in the raw doc batch) given by at ingest time to be more accurate.
         let mut raw_doc_batch = RawDocBatch::with_capacity(doc_batch.num_docs());
-        for doc in doc_batch.iter() {
+        let mut backfill_doc_batch = RawDocBatch {
+            backfill: true,
+            ..Default::default()
+        };
+        for (position, doc) in (first_position..).zip(doc_batch.iter()) {
             match doc {
                 DocCommand::Ingest { payload } => raw_doc_batch.docs.push(payload),
-                DocCommand::Commit => raw_doc_batch.force_commit = true,
+                DocCommand::Commit => {
+                    raw_doc_batch.force_commit = true;
+                    backfill_doc_batch.force_commit = true;
+                }
+                DocCommand::Backfill { payload } => {
+                    // The docs up to the backfill offset are already published. They are read
+                    // again when the live checkpoint lags behind the backfill one.
+                    if self
+                        .counters
+                        .backfill_offset
+                        .map_or(true, |backfill_offset| position > backfill_offset)
+                    {
+                        backfill_doc_batch.docs.push(payload);
+                    }
+                }
             }
         }
         let current_offset = first_position + doc_batch.num_docs() as u64 - 1;
+
+        // The backfill batch is sent first so that its splits are published before the
+        // checkpoint moves past its docs.
+        if !backfill_doc_batch.docs.is_empty() {
+            let num_backfill_docs = backfill_doc_batch.docs.len() as u64;
+            backfill_doc_batch
+                .checkpoint_delta
+                .record_partition_delta(
+                    self.backfill_partition_id.clone(),
+                    self.counters
+                        .backfill_offset
+                        .map(Position::offset)
+                        .unwrap_or_default(),
+                    Position::offset(current_offset),
+                )
+                .map_err(anyhow::Error::from)?;
+            ctx.send_message(batch_sink, backfill_doc_batch).await?;
+            self.counters.backfill_offset = Some(current_offset);
+
+            if raw_doc_batch.docs.is_empty() {
+                self.update_counters_for_backfill(current_offset, num_backfill_docs);
+                return Ok(Duration::default());
+            }
+            self.counters.num_docs_processed += num_backfill_docs;
+        }
         let partition_id = self.partition_id.clone();
         raw_doc_batch
             .checkpoint_delta
@@ -196,14 +268,19 @@ impl Source for IngestApiSource {
     async fn suggest_truncate(
         &mut self,
         checkpoint: SourceCheckpoint,
+        published_split_ids: &[String],
         ctx: &ActorContext<SourceActor>,
     ) -> anyhow::Result<()> {
         if let Some(Position::Offset(offset)) =
             checkpoint.position_for_partition(&self.partition_id)
         {
             let up_to_position_included = offset.as_u64().expect("offset should be stored as u64");
-            self.send_suggest_truncate_to_ingest_service(up_to_position_included, ctx)
-                .await?;
+            self.send_suggest_truncate_to_ingest_service(
+                up_to_position_included,
+                published_split_ids,
+                ctx,
+            )
+            .await?;
         }
         Ok(())
     }
@@ -343,6 +420,217 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_ingest_api_source_backfill_does_not_advance_checkpoint() -> anyhow::Result<()> {
+        let universe = Universe::with_accelerated_time();
+        let metastore = metastore_for_test();
+        let index_id = append_random_suffix("test-ingest-api-source-backfill");
+        let index_uid = IndexUid::new_with_random_ulid(&index_id);
+        let temp_dir = tempfile::tempdir()?;
+        let queues_dir_path = temp_dir.path();
+
+        let ingest_api_service =
+            init_ingest_api(&universe, queues_dir_path, &IngestApiConfig::default()).await?;
+        let partition_id: PartitionId = ingest_api_service.ask(GetPartitionId).await?.into();
+        let (doc_processor_mailbox, doc_processor_inbox) = universe.create_test_mailbox();
+        let source_config = make_source_config();
+        let ctx = SourceRuntimeArgs::for_test(
+            index_uid,
+            source_config,
+            metastore,
+            queues_dir_path.to_path_buf(),
+        );
+        let ingest_api_source = IngestApiSource::try_new(ctx, SourceCheckpoint::default()).await?;
+        let ingest_api_source_actor = SourceActor {
+            source: Box::new(ingest_api_source),
+            doc_processor_mailbox,
+        };
+        let (_ingest_api_source_mailbox, ingest_api_source_handle) =
+            universe.spawn_builder().spawn(ingest_api_source_actor);
+        let mut checkpoint = SourceCheckpoint::default();
+
+        let ingest_req = make_ingest_request(index_id.clone(), 1, 10, CommitType::Auto);
+        ingest_api_service
+            .ask_for_res(ingest_req)
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+        universe.sleep(Duration::from_secs(2)).await;
+        ingest_api_source_handle.process_pending_and_observe().await;
+
+        let doc_batches: Vec<RawDocBatch> = doc_processor_inbox.drain_for_test_typed();
+        assert_eq!(doc_batches.len(), 1);
+        assert!(!doc_batches[0].backfill);
+        for doc_batch in doc_batches {
+            checkpoint.try_apply_delta(doc_batch.checkpoint_delta)?;
+        }
+        assert_eq!(
+            checkpoint.position_for_partition(&partition_id),
+            Some(&Position::offset(9u64))
+        );
+
+        let mut doc_batch_builder = DocBatchBuilder::new(index_id.clone());
+        for doc_id in 0..5 {
+            doc_batch_builder.backfill_doc(format!("backfill doc {doc_id}").as_bytes());
+        }
+        let backfill_req = IngestRequest {
+            doc_batches: vec![doc_batch_builder.build()],
+            commit: CommitType::Auto.into(),
+        };
+        ingest_api_service
+            .ask_for_res(backfill_req)
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+        universe.sleep(Duration::from_secs(2)).await;
+        let counters = ingest_api_source_handle
+            .process_pending_and_observe()
+            .await
+            .state;
+        assert_eq!(
+            counters,
+            serde_json::json!({
+                "previous_offset": 9u64,
+                "current_offset": 14u64,
+                "backfill_offset": 14u64,
+                "num_docs_processed": 15u64
+            })
+        );
+        let doc_batches: Vec<RawDocBatch> = doc_processor_inbox.drain_for_test_typed();
+        assert_eq!(doc_batches.len(), 1);
+        assert!(doc_batches[0].backfill);
+        assert_eq!(doc_batches[0].num_docs(), 5);
+        for doc_batch in doc_batches {
+            checkpoint.try_apply_delta(doc_batch.checkpoint_delta)?;
+        }
+        assert_eq!(
+            checkpoint.position_for_partition(&partition_id),
+            Some(&Position::offset(9u64))
+        );
+        assert_eq!(
+            checkpoint.position_for_partition(&backfill_partition_id(&partition_id)),
+            Some(&Position::offset(14u64))
+        );
+
+        // The next live batch covers the positions of the backfill docs.
+        let ingest_req = make_ingest_request(index_id.clone(), 1, 3, CommitType::Auto);
+        ingest_api_service
+            .ask_for_res(ingest_req)
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+        universe.sleep(Duration::from_secs(2)).await;
+        ingest_api_source_handle.process_pending_and_observe().await;
+
+        let doc_batches: Vec<RawDocBatch> = doc_processor_inbox.drain_for_test_typed();
+        assert_eq!(doc_batches.len(), 1);
+        assert!(!doc_batches[0].backfill);
+        for doc_batch in doc_batches {
+            checkpoint.try_apply_delta(doc_batch.checkpoint_delta)?;
+        }
+        assert_eq!(
+            checkpoint.position_for_partition(&partition_id),
+            Some(&Position::offset(17u64))
+        );
+        ingest_api_source_handle.quit().await;
+        universe.assert_quit().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ingest_api_source_backfill_is_not_reindexed_after_restart() -> anyhow::Result<()>
+    {
+        let universe = Universe::with_accelerated_time();
+        let metastore = metastore_for_test();
+        let index_id = append_random_suffix("test-ingest-api-source-backfill-restart");
+        let index_uid = IndexUid::new_with_random_ulid(&index_id);
+        let temp_dir = tempfile::tempdir()?;
+        let queues_dir_path = temp_dir.path();
+
+        let ingest_api_service =
+            init_ingest_api(&universe, queues_dir_path, &IngestApiConfig::default()).await?;
+        let partition_id: PartitionId = ingest_api_service.ask(GetPartitionId).await?.into();
+        let create_queue_req = CreateQueueIfNotExistsRequest {
+            queue_id: index_id.clone(),
+        };
+        ingest_api_service.ask_for_res(create_queue_req).await?;
+
+        // Positions 0 to 9 are live docs, 10 to 14 backfill docs, and 15 to 17 live docs.
+        let ingest_req = make_ingest_request(index_id.clone(), 1, 10, CommitType::Auto);
+        ingest_api_service
+            .ask_for_res(ingest_req)
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+        let mut doc_batch_builder = DocBatchBuilder::new(index_id.clone());
+        for doc_id in 0..5 {
+            doc_batch_builder.backfill_doc(format!("backfill doc {doc_id}").as_bytes());
+        }
+        let backfill_req = IngestRequest {
+            doc_batches: vec![doc_batch_builder.build()],
+            commit: CommitType::Auto.into(),
+        };
+        ingest_api_service
+            .ask_for_res(backfill_req)
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+        let ingest_req = make_ingest_request(index_id.clone(), 1, 3, CommitType::Auto);
+        ingest_api_service
+            .ask_for_res(ingest_req)
+            .await
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+
+        // The backfill docs were published before the restart, but not the last live docs.
+        let checkpoint = SourceCheckpoint::from_iter(vec![
+            (partition_id.clone(), Position::offset(9u64)),
+            (
+                backfill_partition_id(&partition_id),
+                Position::offset(14u64),
+            ),
+        ]);
+        let (doc_processor_mailbox, doc_processor_inbox) = universe.create_test_mailbox();
+        let source_config = make_source_config();
+        let ctx = SourceRuntimeArgs::for_test(
+            index_uid,
+            source_config,
+            metastore,
+            queues_dir_path.to_path_buf(),
+        );
+        let ingest_api_source = IngestApiSource::try_new(ctx, checkpoint).await?;
+        let ingest_api_source_actor = SourceActor {
+            source: Box::new(ingest_api_source),
+            doc_processor_mailbox,
+        };
+        let (_ingest_api_source_mailbox, ingest_api_source_handle) =
+            universe.spawn_builder().spawn(ingest_api_source_actor);
+        universe.sleep(Duration::from_secs(2)).await;
+        let counters = ingest_api_source_handle
+            .process_pending_and_observe()
+            .await
+            .state;
+        assert_eq!(
+            counters,
+            serde_json::json!({
+                "previous_offset": 17u64,
+                "current_offset": 17u64,
+                "backfill_offset": 14u64,
+                "num_docs_processed": 3u64
+            })
+        );
+        let doc_batches: Vec<RawDocBatch> = doc_processor_inbox.drain_for_test_typed();
+        assert_eq!(doc_batches.len(), 1);
+        assert!(!doc_batches[0].backfill);
+        assert_eq!(doc_batches[0].num_docs(), 3);
+        assert_eq!(
+            doc_batches[0].checkpoint_delta,
+            SourceCheckpointDelta::from_partition_delta(
+                partition_id,
+                Position::offset(9u64),
+                Position::offset(17u64)
+            )
+            .unwrap()
+        );
+        ingest_api_source_handle.quit().await;
+        universe.assert_quit().await;
+        Ok(())
+    }
+
     /// See #2310
     #[tokio::test]
     async fn test_ingest_api_source_partition_id_changes() -> anyhow::Result<()> {
@@ -565,6 +853,7 @@ mod tests {
             .ask_for_res(SuggestTruncateRequest {
                 index_id: index_id.clone(),
                 up_to_position_included: 40001,
+                published_split_ids: Vec::new(),
             })
             .await
             .map_err(|err| anyhow::anyhow!(err.to_string()))?;
@@ -631,6 +920,7 @@ mod tests {
             .ask_for_res(SuggestTruncateRequest {
                 index_id: index_id.clone(),
                 up_to_position_included: 39999,
+                published_split_ids: Vec::new(),
             })
             .await
             .unwrap();
@@ -520,6 +520,7 @@ impl Source for KafkaSource {
     async fn suggest_truncate(
         &mut self,
         checkpoint: SourceCheckpoint,
+        _published_split_ids: &[String],
         _ctx: &SourceContext,
     ) -> anyhow::Result<()> {
         self.truncate(checkpoint)?;
@@ -315,6 +315,7 @@ impl Source for KinesisSource {
                 docs,
                 checkpoint_delta,
                 force_commit: false,
+                backfill: false,
             };
             ctx.send_message(indexer_mailbox, batch).await?;
         }
@@ -257,12 +257,16 @@ pub trait Source: Send + 'static {
     /// For instance, message queue like kafka are meant to be shared by different
     /// client, and rely on a retention strategy to delete messages.
     ///
+    /// `published_split_ids` are the IDs of the splits whose publication produced the
+    /// checkpoint.
+    ///
     /// Returning an error has no effect on the source actor itself or the
     /// indexing pipeline, as truncation is just "a suggestion".
     /// The error will however be logged.
     async fn suggest_truncate(
         &mut self,
         _checkpoint: SourceCheckpoint,
+        _published_split_ids: &[String],
         _ctx: &SourceContext,
     ) -> anyhow::Result<()> {
         Ok(())
@@ -451,7 +455,11 @@ pub async fn check_source_connectivity(
 }
 
 #[derive(Debug)]
-pub struct SuggestTruncate(pub SourceCheckpoint);
+pub struct SuggestTruncate {
+    pub checkpoint: SourceCheckpoint,
+    /// IDs of the splits whose publication produced `checkpoint`.
+    pub published_split_ids: Vec<String>,
+}
 
 #[async_trait]
 impl Handler<SuggestTruncate> for SourceActor {
@@ -462,8 +470,15 @@ impl Handler<SuggestTruncate> for SourceActor {
         suggest_truncate: SuggestTruncate,
         ctx: &SourceContext,
     ) -> Result<(), ActorExitStatus> {
-        let SuggestTruncate(checkpoint) = suggest_truncate;
-        if let Err(err) = self.source.suggest_truncate(checkpoint, ctx).await {
+        let SuggestTruncate {
+            checkpoint,
+            published_split_ids,
+        } = suggest_truncate;
+        if let Err(err) = self
+            .source
+            .suggest_truncate(checkpoint, &published_split_ids, ctx)
+            .await
+        {
             // Failing to process suggest truncate does not
             // kill the source nor the indexing pipeline, but we log the error.
             error!(err=?err, "suggest-truncate-error");
@@ -495,6 +510,7 @@ impl BatchBuilder {
             docs: self.docs,
             checkpoint_delta: self.checkpoint_delta,
             force_commit: self.force_commit,
+            backfill: false,
         }
     }
 
@@ -255,6 +255,7 @@ impl Source for PulsarSource {
     async fn suggest_truncate(
         &mut self,
         checkpoint: SourceCheckpoint,
+        _published_split_ids: &[String],
         _ctx: &ActorContext<SourceActor>,
     ) -> anyhow::Result<()> {
         self.try_ack_messages(checkpoint).await
@@ -664,7 +665,10 @@ mod pulsar_broker_tests {
         checkpoint
             .try_apply_delta(checkpoints!(partition => truncate_to))
             .expect("Create checkpoint");
-        let truncate = SuggestTruncate(checkpoint);
+        let truncate = SuggestTruncate {
+            checkpoint,
+            published_split_ids: Vec::new(),
+        };
         source_handle
             .mailbox()
             .send_message(truncate)
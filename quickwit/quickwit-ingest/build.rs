@@ -22,7 +22,10 @@ use quickwit_codegen::{Codegen, ProstConfig};
 fn main() {
     // Legacy ingest codegen
     let mut prost_config = ProstConfig::default();
-    prost_config.bytes(["DocBatch.doc_buffer"]);
+    prost_config.bytes(["DocBatch.doc_buffer"]).field_attribute(
+        "IngestResponse.published_split_ids",
+        "#[serde(default, skip_serializing_if = \"Vec::is_empty\")]",
+    );
 
     Codegen::builder()
         .with_protos(&["src/ingest_service.proto"])
@@ -41,6 +41,11 @@ pub struct IngestRequest {
 pub struct IngestResponse {
     #[prost(uint64, tag = "1")]
     pub num_docs_for_processing: u64,
+    /// IDs of the splits the documents were published in. Only set when the request waited for
+    /// its documents to be committed.
+    #[prost(string, repeated, tag = "2")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub published_split_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 /// Fetch messages with position strictly after `start_after`.
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
@@ -96,6 +101,10 @@ pub struct SuggestTruncateRequest {
     pub index_id: ::prost::alloc::string::String,
     #[prost(uint64, tag = "2")]
     pub up_to_position_included: u64,
+    /// IDs of the splits whose publication made the records up to `up_to_position_included`
+    /// searchable.
+    #[prost(string, repeated, tag = "3")]
+    pub published_split_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
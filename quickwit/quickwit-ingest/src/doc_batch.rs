@@ -28,8 +28,15 @@ use crate::DocBatch;
 pub enum DocCommand<T>
 where T: Buf
 {
-    Ingest { payload: T },
+    Ingest {
+        payload: T,
+    },
     Commit,
+    /// Ingests a document as part of a backfill: the split it ends up in is published without
+    /// advancing the checkpoint of the source.
+    Backfill {
+        payload: T,
+    },
     // ... more to come?
 }
 
@@ -40,6 +47,7 @@ where T: Buf
 pub enum DocCommandCode {
     IngestV1 = 0,
     CommitV1 = 1,
+    BackfillV1 = 2,
 }
 
 impl From<u8> for DocCommandCode {
@@ -47,6 +55,7 @@ impl From<u8> for DocCommandCode {
         match value {
             0 => DocCommandCode::IngestV1,
             1 => DocCommandCode::CommitV1,
+            2 => DocCommandCode::BackfillV1,
             other => panic!("Encountered unknown command: code {other}"),
         }
     }
@@ -60,6 +69,7 @@ where T: Buf + Default
         match self {
             DocCommand::Ingest { payload: _ } => DocCommandCode::IngestV1,
             DocCommand::Commit => DocCommandCode::CommitV1,
+            DocCommand::Backfill { payload: _ } => DocCommandCode::BackfillV1,
         }
     }
 
@@ -68,6 +78,7 @@ where T: Buf + Default
         match buf.get_u8().into() {
             DocCommandCode::IngestV1 => DocCommand::Ingest { payload: buf },
             DocCommandCode::CommitV1 => DocCommand::Commit,
+            DocCommandCode::BackfillV1 => DocCommand::Backfill { payload: buf },
         }
     }
 
@@ -83,6 +94,7 @@ where T: Buf + Default
         self.code_chunk().chain(match self {
             DocCommand::Ingest { payload } => payload,
             DocCommand::Commit => T::default(),
+            DocCommand::Backfill { payload } => payload,
         })
     }
 
@@ -90,6 +102,7 @@ where T: Buf + Default
         match self {
             DocCommand::Ingest { payload: _ } => &[DocCommandCode::IngestV1 as u8],
             DocCommand::Commit => &[DocCommandCode::CommitV1 as u8],
+            DocCommand::Backfill { payload: _ } => &[DocCommandCode::BackfillV1 as u8],
         }
     }
 }
@@ -127,6 +140,12 @@ impl DocBatchBuilder {
         self.command(command)
     }
 
+    /// Adds a backfill command to the batch
+    pub fn backfill_doc(&mut self, payload: impl Buf + Default) -> usize {
+        let command = DocCommand::Backfill { payload };
+        self.command(command)
+    }
+
     /// Adds a commit command to the batch
     pub fn commit(&mut self) -> usize {
         let command: DocCommand<Bytes> = DocCommand::Commit;
@@ -268,6 +287,17 @@ mod tests {
                     == r_payload.copy_to_bytes(r_payload.remaining())
             }
             (DocCommand::Commit, DocCommand::Commit) => true,
+            (
+                DocCommand::Backfill {
+                    payload: mut l_payload,
+                },
+                DocCommand::Backfill {
+                    payload: mut r_payload,
+                },
+            ) => {
+                l_payload.copy_to_bytes(l_payload.remaining())
+                    == r_payload.copy_to_bytes(r_payload.remaining())
+            }
             _ => false,
         }
     }
@@ -312,6 +342,14 @@ mod tests {
             },
             DocCommand::Commit::<Bytes>
         ));
+        assert!(!commands_eq(
+            DocCommand::Ingest {
+                payload: Bytes::from("hello")
+            },
+            DocCommand::Backfill {
+                payload: Bytes::from("hello")
+            }
+        ));
     }
 
     #[test]
@@ -324,6 +362,9 @@ mod tests {
         });
         test_command_roundtrip!(DocCommand::Commit::<Bytes>);
         test_command_roundtrip!(DocCommand::Commit::<&[u8]>);
+        test_command_roundtrip!(DocCommand::Backfill {
+            payload: Bytes::from("hello")
+        });
     }
 
     #[test]
@@ -42,6 +42,8 @@ pub enum IngestServiceError {
     IoError(String),
     #[error("rate limited")]
     RateLimited,
+    #[error("request timed out: {0}")]
+    Timeout(String),
     #[error("ingest service is unavailable")]
     Unavailable,
 }
@@ -51,6 +53,7 @@ impl From<AskError<IngestServiceError>> for IngestServiceError {
         match error {
             AskError::ErrorReply(error) => error,
             AskError::MessageNotDelivered => IngestServiceError::Unavailable,
+            AskError::QueueFull => IngestServiceError::Unavailable,
             AskError::ProcessMessageError => IngestServiceError::Internal(error.to_string()),
         }
     }
@@ -102,6 +105,9 @@ impl From<tonic::Status> for IngestServiceError {
                 IngestServiceError::InvalidPosition(status.message().to_string())
             }
             tonic::Code::ResourceExhausted => IngestServiceError::RateLimited,
+            tonic::Code::DeadlineExceeded => {
+                IngestServiceError::Timeout(status.message().to_string())
+            }
             tonic::Code::Unavailable => IngestServiceError::Unavailable,
             _ => IngestServiceError::Internal(status.message().to_string()),
         }
@@ -118,7 +124,8 @@ impl ServiceError for IngestServiceError {
             IngestServiceError::InvalidPosition(_) => ServiceErrorCode::BadRequest,
             IngestServiceError::IoError { .. } => ServiceErrorCode::Internal,
             IngestServiceError::RateLimited => ServiceErrorCode::RateLimited,
-            IngestServiceError::Unavailable => ServiceErrorCode::Internal,
+            IngestServiceError::Timeout(_) => ServiceErrorCode::Timeout,
+            IngestServiceError::Unavailable => ServiceErrorCode::Unavailable,
         }
     }
 }
@@ -143,6 +150,7 @@ impl From<IngestServiceError> for tonic::Status {
             IngestServiceError::InvalidPosition(_) => tonic::Code::InvalidArgument,
             IngestServiceError::IoError { .. } => tonic::Code::Internal,
             IngestServiceError::RateLimited => tonic::Code::ResourceExhausted,
+            IngestServiceError::Timeout(_) => tonic::Code::DeadlineExceeded,
             IngestServiceError::Unavailable => tonic::Code::Unavailable,
         };
         let message = error.to_string();
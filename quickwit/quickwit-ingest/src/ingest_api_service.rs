@@ -23,7 +23,7 @@ use std::{fmt, iter};
 use async_trait::async_trait;
 use bytes::Bytes;
 use quickwit_actors::{
-    Actor, ActorContext, ActorExitStatus, DeferableReplyHandler, Handler, QueueCapacity,
+    Actor, ActorContext, ActorExitStatus, DeferableReplyHandler, Handler, Mailbox, QueueCapacity,
 };
 use quickwit_common::runtimes::RuntimeType;
 use quickwit_common::tower::Cost;
@@ -34,7 +34,7 @@ use crate::metrics::INGEST_METRICS;
 use crate::notifications::Notifications;
 use crate::{
     CommitType, CreateQueueIfNotExistsRequest, CreateQueueRequest, DocCommand, DropQueueRequest,
-    FetchRequest, FetchResponse, IngestRequest, IngestResponse, IngestServiceError,
+    FetchRequest, FetchResponse, IngestRequest, IngestResponse, IngestService, IngestServiceError,
     ListQueuesRequest, ListQueuesResponse, MemoryCapacity, Queues, SuggestTruncateRequest,
     TailRequest,
 };
@@ -126,8 +126,11 @@ impl IngestApiService {
                     reply(Ok(response));
                 } else {
                     self.notifications
-                        .register(index_positions, move || {
-                            reply(Ok(response));
+                        .register(index_positions, move |published_split_ids| {
+                            reply(Ok(IngestResponse {
+                                published_split_ids,
+                                ..response
+                            }));
                         })
                         .await;
                 }
@@ -212,6 +215,7 @@ impl IngestApiService {
         Ok((
             IngestResponse {
                 num_docs_for_processing: num_docs as u64,
+                published_split_ids: Vec::new(),
             },
             notifications,
         ))
@@ -234,7 +238,11 @@ impl IngestApiService {
         ctx: &ActorContext<Self>,
     ) -> crate::Result<()> {
         self.notifications
-            .notify(&request.index_id, request.up_to_position_included)
+            .notify(
+                &request.index_id,
+                request.up_to_position_included,
+                &request.published_split_ids,
+            )
             .await;
         self.queues
             .suggest_truncate(&request.index_id, request.up_to_position_included, ctx)
@@ -403,6 +411,45 @@ impl Handler<ListQueuesRequest> for IngestApiService {
     }
 }
 
+/// An [`IngestService`] backed by a local [`IngestApiService`] that sheds load rather than
+/// applying backpressure: ingest requests are rejected right away with
+/// [`IngestServiceError::Unavailable`] when the mailbox of the service is full. Fetch and tail
+/// requests wait for room in the mailbox as usual.
+#[derive(Debug, Clone)]
+pub struct LoadSheddingIngestApiService {
+    mailbox: Mailbox<IngestApiService>,
+}
+
+impl LoadSheddingIngestApiService {
+    pub fn new(mailbox: Mailbox<IngestApiService>) -> Self {
+        Self { mailbox }
+    }
+}
+
+#[async_trait]
+impl IngestService for LoadSheddingIngestApiService {
+    async fn ingest(&mut self, request: IngestRequest) -> crate::Result<IngestResponse> {
+        self.mailbox
+            .try_ask_for_res(request)
+            .await
+            .map_err(IngestServiceError::from)
+    }
+
+    async fn fetch(&mut self, request: FetchRequest) -> crate::Result<FetchResponse> {
+        self.mailbox
+            .ask_for_res(request)
+            .await
+            .map_err(IngestServiceError::from)
+    }
+
+    async fn tail(&mut self, request: TailRequest) -> crate::Result<FetchResponse> {
+        self.mailbox
+            .ask_for_res(request)
+            .await
+            .map_err(IngestServiceError::from)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
@@ -482,6 +529,7 @@ mod tests {
             .send_message(SuggestTruncateRequest {
                 index_id: "index-1".to_string(),
                 up_to_position_included: position,
+                published_split_ids: Vec::new(),
             })
             .await
             .unwrap();
@@ -537,12 +585,17 @@ mod tests {
             .send_message(SuggestTruncateRequest {
                 index_id: "index-1".to_string(),
                 up_to_position_included: position,
+                published_split_ids: vec!["split-1".to_string()],
             })
             .await
             .unwrap();
 
         let ingest_response = ingest_response.await.unwrap().unwrap();
         assert_eq!(ingest_response.num_docs_for_processing, 4);
+        assert_eq!(
+            ingest_response.published_split_ids,
+            vec!["split-1".to_string()]
+        );
 
         universe.assert_quit().await;
         Ok(())
@@ -38,7 +38,9 @@ use std::path::{Path, PathBuf};
 use anyhow::{bail, Context};
 pub use doc_batch::*;
 pub use errors::IngestServiceError;
-pub use ingest_api_service::{GetMemoryCapacity, GetPartitionId, IngestApiService};
+pub use ingest_api_service::{
+    GetMemoryCapacity, GetPartitionId, IngestApiService, LoadSheddingIngestApiService,
+};
 pub use ingest_service::*;
 pub use ingest_v2::*;
 pub use memory_capacity::MemoryCapacity;
@@ -320,6 +322,7 @@ mod tests {
             .ask_for_res(SuggestTruncateRequest {
                 index_id: "test-queue".to_string(),
                 up_to_position_included: 29,
+                published_split_ids: Vec::new(),
             })
             .await
             .unwrap();
@@ -18,7 +18,7 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::{HashMap, VecDeque};
-use std::sync::Arc;
+use std::sync::{self, Arc};
 
 use tokio::sync::Mutex;
 
@@ -36,11 +36,12 @@ impl Notifications {
         }
     }
 
-    /// Register index positions
+    /// Register index positions. Once all of them are notified, `notify` is called with the IDs of
+    /// the splits they were published in.
     pub async fn register(
         &self,
         index_positions: Vec<(String, u64)>,
-        notify: impl FnOnce() + Send + Sync + 'static,
+        notify: impl FnOnce(Vec<String>) + Send + Sync + 'static,
     ) {
         let mut guard = self.notifications.lock().await;
         let notification = Arc::new(Notification::new(notify));
@@ -55,16 +56,18 @@ impl Notifications {
         }
     }
 
-    /// Notify positions
-    pub async fn notify(&self, index: &String, max_position: u64) {
+    /// Notify positions up to `max_position`, which were published in the splits
+    /// `published_split_ids`.
+    pub async fn notify(&self, index: &String, max_position: u64, published_split_ids: &[String]) {
         let mut map = self.notifications.lock().await;
         if let Some(positions) = map.get_mut(index) {
             while let Some(position) = positions.front() {
                 if position.position <= max_position {
-                    positions
-                        .pop_front()
-                        .unwrap()
-                        .decrement_count_and_notify_if_last();
+                    let position = positions.pop_front().unwrap();
+                    position
+                        .notification
+                        .add_published_split_ids(published_split_ids);
+                    position.decrement_count_and_notify_if_last();
                 } else {
                     break;
                 }
@@ -77,9 +80,22 @@ impl Notifications {
 }
 
 impl Notification {
-    fn new(notify: impl FnOnce() + Send + Sync + 'static) -> Self {
+    fn new(notify: impl FnOnce(Vec<String>) + Send + Sync + 'static) -> Self {
         Self {
             notify: Box::new(notify),
+            published_split_ids: Default::default(),
+        }
+    }
+
+    fn add_published_split_ids(&self, split_ids: &[String]) {
+        let mut published_split_ids = self
+            .published_split_ids
+            .lock()
+            .expect("lock should not be poisoned");
+        for split_id in split_ids {
+            if !published_split_ids.contains(split_id) {
+                published_split_ids.push(split_id.clone());
+            }
         }
     }
 }
@@ -99,12 +115,19 @@ impl Position {
 }
 
 struct Notification {
-    notify: Box<dyn FnOnce() + Send + Sync + 'static>,
+    notify: Box<dyn FnOnce(Vec<String>) + Send + Sync + 'static>,
+    // The positions of a notification may be notified by several truncations, so the IDs of the
+    // splits they were published in are gathered until the last one.
+    published_split_ids: sync::Mutex<Vec<String>>,
 }
 
 impl Notification {
     fn notify(self) {
-        (self.notify)();
+        let published_split_ids = self
+            .published_split_ids
+            .into_inner()
+            .expect("lock should not be poisoned");
+        (self.notify)(published_split_ids);
     }
 }
 
@@ -113,6 +136,8 @@ mod tests {
     use std::sync::atomic::AtomicUsize;
     use std::sync::Arc;
 
+    use tokio::sync::oneshot;
+
     use crate::notifications::Notifications;
 
     #[tokio::test]
@@ -121,7 +146,7 @@ mod tests {
         let cleared = Arc::new(AtomicUsize::default());
         let cleared_clone = cleared.clone();
         notifications
-            .register(vec![("index1".to_string(), 10)], move || {
+            .register(vec![("index1".to_string(), 10)], move |_| {
                 assert_eq!(
                     cleared_clone.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
                     0
@@ -130,7 +155,7 @@ mod tests {
             .await;
         let cleared_clone = cleared.clone();
         notifications
-            .register(vec![("index2".to_string(), 10)], move || {
+            .register(vec![("index2".to_string(), 10)], move |_| {
                 assert_eq!(
                     cleared_clone.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
                     1
@@ -141,7 +166,7 @@ mod tests {
         notifications
             .register(
                 vec![("index1".to_string(), 20), ("index1".to_string(), 30)],
-                move || {
+                move |_| {
                     assert_eq!(
                         cleared_clone.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
                         2
@@ -150,11 +175,37 @@ mod tests {
             )
             .await;
         assert_eq!(cleared.load(std::sync::atomic::Ordering::Relaxed), 0);
-        notifications.notify(&"index1".to_string(), 20).await;
+        notifications.notify(&"index1".to_string(), 20, &[]).await;
         assert_eq!(cleared.load(std::sync::atomic::Ordering::Relaxed), 1);
-        notifications.notify(&"index2".to_string(), 100).await;
+        notifications.notify(&"index2".to_string(), 100, &[]).await;
         assert_eq!(cleared.load(std::sync::atomic::Ordering::Relaxed), 2);
-        notifications.notify(&"index1".to_string(), 100).await;
+        notifications.notify(&"index1".to_string(), 100, &[]).await;
         assert_eq!(cleared.load(std::sync::atomic::Ordering::Relaxed), 3);
     }
+
+    #[tokio::test]
+    async fn test_notifications_gather_published_split_ids() {
+        let notifications = Notifications::new();
+        let (published_split_ids_tx, published_split_ids_rx) = oneshot::channel();
+        notifications
+            .register(
+                vec![("index1".to_string(), 10), ("index2".to_string(), 10)],
+                move |published_split_ids| {
+                    published_split_ids_tx.send(published_split_ids).unwrap();
+                },
+            )
+            .await;
+        notifications
+            .notify(&"index1".to_string(), 10, &["split-1".to_string()])
+            .await;
+        notifications
+            .notify(
+                &"index2".to_string(),
+                10,
+                &["split-1".to_string(), "split-2".to_string()],
+            )
+            .await;
+        let published_split_ids = published_split_ids_rx.await.unwrap();
+        assert_eq!(published_split_ids, ["split-1", "split-2"]);
+    }
 }
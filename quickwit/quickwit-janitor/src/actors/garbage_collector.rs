@@ -25,12 +25,14 @@ use async_trait::async_trait;
 use futures::{stream, StreamExt};
 use itertools::Itertools;
 use quickwit_actors::{Actor, ActorContext, Handler};
+use quickwit_common::pubsub::EventBroker;
 use quickwit_common::shared_consts::DELETION_GRACE_PERIOD;
 use quickwit_index_management::run_garbage_collect;
 use quickwit_metastore::ListIndexesMetadataResponseExt;
 use quickwit_proto::metastore::{
     ListIndexesMetadataRequest, MetastoreService, MetastoreServiceClient,
 };
+use quickwit_proto::search::ReportSplitsRequest;
 use quickwit_storage::StorageResolver;
 use serde::Serialize;
 use tracing::{error, info};
@@ -66,17 +68,26 @@ pub struct GarbageCollectorCounters {
 struct Loop;
 
 /// An actor for collecting garbage periodically from an index.
+///
+/// The IDs of the deleted splits are published on the event broker, so that searchers evict them
+/// from their split cache.
 pub struct GarbageCollector {
     metastore: MetastoreServiceClient,
     storage_resolver: StorageResolver,
+    event_broker: EventBroker,
     counters: GarbageCollectorCounters,
 }
 
 impl GarbageCollector {
-    pub fn new(metastore: MetastoreServiceClient, storage_resolver: StorageResolver) -> Self {
+    pub fn new(
+        metastore: MetastoreServiceClient,
+        storage_resolver: StorageResolver,
+        event_broker: EventBroker,
+    ) -> Self {
         Self {
             metastore,
             storage_resolver,
+            event_broker,
             counters: GarbageCollectorCounters::default(),
         }
     }
@@ -163,6 +174,14 @@ impl GarbageCollector {
                     .iter()
                     .map(|entry| entry.file_size_bytes.as_u64() as usize)
                     .sum::<usize>();
+                let deleted_split_ids = deleted_file_entries
+                    .into_iter()
+                    .map(|deleted_entry| deleted_entry.split_id)
+                    .collect();
+                self.event_broker.publish(ReportSplitsRequest {
+                    report_splits: Vec::new(),
+                    deleted_split_ids,
+                });
             }
         }
     }
@@ -401,9 +420,16 @@ mod tests {
                 Ok(EmptyResponse {})
             });
 
+        let event_broker = EventBroker::default();
+        let (report_splits_tx, mut report_splits_rx) = tokio::sync::mpsc::unbounded_channel();
+        let _subscription_handle =
+            event_broker.subscribe(move |report_splits_request: ReportSplitsRequest| {
+                report_splits_tx.send(report_splits_request).unwrap();
+            });
         let garbage_collect_actor = GarbageCollector::new(
             MetastoreServiceClient::from(mock_metastore),
             storage_resolver,
+            event_broker,
         );
         let universe = Universe::with_accelerated_time();
         let (_mailbox, handler) = universe.spawn_builder().spawn(garbage_collect_actor);
@@ -413,6 +439,17 @@ mod tests {
         assert_eq!(state_after_initialization.num_deleted_files, 3);
         assert_eq!(state_after_initialization.num_deleted_bytes, 60);
         assert_eq!(state_after_initialization.num_failed_splits, 0);
+
+        // The deleted splits are reported so that searchers evict them from their split cache.
+        let report_splits_request = report_splits_rx.recv().await.unwrap();
+        assert!(report_splits_request.report_splits.is_empty());
+        let deleted_split_ids: HashSet<String> = report_splits_request
+            .deleted_split_ids
+            .into_iter()
+            .collect();
+        let expected_split_ids: HashSet<String> =
+            ["a", "b", "c"].into_iter().map(String::from).collect();
+        assert_eq!(deleted_split_ids, expected_split_ids);
         universe.assert_quit().await;
     }
 
@@ -480,6 +517,7 @@ mod tests {
         let garbage_collect_actor = GarbageCollector::new(
             MetastoreServiceClient::from(mock_metastore),
             storage_resolver,
+            EventBroker::default(),
         );
         let universe = Universe::with_accelerated_time();
         let (_mailbox, handle) = universe.spawn_builder().spawn(garbage_collect_actor);
@@ -533,6 +571,7 @@ mod tests {
         let garbage_collect_actor = GarbageCollector::new(
             MetastoreServiceClient::from(mock_metastore),
             storage_resolver,
+            EventBroker::default(),
         );
         let universe = Universe::with_accelerated_time();
         let (_mailbox, handle) = universe.spawn_builder().spawn(garbage_collect_actor);
@@ -571,6 +610,7 @@ mod tests {
         let garbage_collect_actor = GarbageCollector::new(
             MetastoreServiceClient::from(mock_metastore),
             storage_resolver,
+            EventBroker::default(),
         );
         let universe = Universe::with_accelerated_time();
         let (_mailbox, handle) = universe.spawn_builder().spawn(garbage_collect_actor);
@@ -653,6 +693,7 @@ mod tests {
         let garbage_collect_actor = GarbageCollector::new(
             MetastoreServiceClient::from(mock_metastore),
             storage_resolver,
+            EventBroker::default(),
         );
         let universe = Universe::with_accelerated_time();
         let (_mailbox, handle) = universe.spawn_builder().spawn(garbage_collect_actor);
@@ -740,6 +781,7 @@ mod tests {
         let garbage_collect_actor = GarbageCollector::new(
             MetastoreServiceClient::from(mock_metastore),
             storage_resolver,
+            EventBroker::default(),
         );
         let universe = Universe::with_accelerated_time();
         let (_mailbox, handle) = universe.spawn_builder().spawn(garbage_collect_actor);
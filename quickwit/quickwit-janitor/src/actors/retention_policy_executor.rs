@@ -66,14 +66,17 @@ pub struct RetentionPolicyExecutor {
     /// account deleted indexes, updated or removed retention policy on indexes.
     index_configs: HashMap<String, IndexConfig>,
     counters: RetentionPolicyExecutorCounters,
+    /// Extra time splits are retained for past their retention period, to absorb clock skew.
+    grace_period: Duration,
 }
 
 impl RetentionPolicyExecutor {
-    pub fn new(metastore: MetastoreServiceClient) -> Self {
+    pub fn new(metastore: MetastoreServiceClient, grace_period: Duration) -> Self {
         Self {
             metastore,
             index_configs: HashMap::new(),
             counters: RetentionPolicyExecutorCounters::default(),
+            grace_period,
         }
     }
 
@@ -211,6 +214,7 @@ impl Handler<Execute> for RetentionPolicyExecutor {
             message.index_uid.clone(),
             self.metastore.clone(),
             retention_policy,
+            self.grace_period,
             ctx,
         )
         .await;
@@ -255,7 +259,7 @@ mod tests {
     use mockall::Sequence;
     use quickwit_actors::Universe;
     use quickwit_common::ServiceStream;
-    use quickwit_config::RetentionPolicy;
+    use quickwit_config::{JanitorConfig, RetentionPolicy};
     use quickwit_metastore::{
         IndexMetadata, ListSplitsRequestExt, ListSplitsResponseExt, Split, SplitMetadata,
         SplitState,
@@ -403,8 +407,10 @@ mod tests {
                 )
             });
 
-        let retention_policy_executor =
-            RetentionPolicyExecutor::new(MetastoreServiceClient::from(mock_metastore));
+        let retention_policy_executor = RetentionPolicyExecutor::new(
+            MetastoreServiceClient::from(mock_metastore),
+            JanitorConfig::default().retention_policy_grace_period(),
+        );
         let universe = Universe::with_accelerated_time();
         let (mailbox, handle) = universe.spawn_builder().spawn(retention_policy_executor);
 
@@ -494,8 +500,10 @@ mod tests {
                 Ok(EmptyResponse {})
             });
 
-        let retention_policy_executor =
-            RetentionPolicyExecutor::new(MetastoreServiceClient::from(mock_metastore));
+        let retention_policy_executor = RetentionPolicyExecutor::new(
+            MetastoreServiceClient::from(mock_metastore),
+            JanitorConfig::default().retention_policy_grace_period(),
+        );
         let universe = Universe::with_accelerated_time();
         let (_mailbox, handle) = universe.spawn_builder().spawn(retention_policy_executor);
 
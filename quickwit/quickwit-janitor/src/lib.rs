@@ -53,10 +53,17 @@ pub async fn start_janitor_service(
     event_broker: EventBroker,
 ) -> anyhow::Result<Mailbox<JanitorService>> {
     info!("starting janitor service");
-    let garbage_collector = GarbageCollector::new(metastore.clone(), storage_resolver.clone());
+    let garbage_collector = GarbageCollector::new(
+        metastore.clone(),
+        storage_resolver.clone(),
+        event_broker.clone(),
+    );
     let (_, garbage_collector_handle) = universe.spawn_builder().spawn(garbage_collector);
 
-    let retention_policy_executor = RetentionPolicyExecutor::new(metastore.clone());
+    let retention_policy_executor = RetentionPolicyExecutor::new(
+        metastore.clone(),
+        config.janitor_config.retention_policy_grace_period(),
+    );
     let (_, retention_policy_executor_handle) =
         universe.spawn_builder().spawn(retention_policy_executor);
     let delete_task_service = DeleteTaskService::new(
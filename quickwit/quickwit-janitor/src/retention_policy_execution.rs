@@ -17,6 +17,8 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::time::Duration;
+
 use quickwit_actors::ActorContext;
 use quickwit_common::PrettySample;
 use quickwit_config::RetentionPolicy;
@@ -37,20 +39,26 @@ use crate::actors::RetentionPolicyExecutor;
 /// only mark them as `MarkedForDeletion`. Actual split deletion
 /// is taken care of by the garbage collector.
 ///
+/// Only published splits are considered, so running the retention policy again marks no split
+/// twice.
+///
 /// * `index_id` - The target index id.
 /// * `metastore` - The metastore managing the target index.
 /// * `retention_policy` - The retention policy to used to evaluate the splits.
+/// * `grace_period` - Extra time splits are retained for, to absorb clock skew.
 /// * `ctx_opt` - A context for reporting progress (only useful within quickwit actor).
 pub async fn run_execute_retention_policy(
     index_uid: IndexUid,
     mut metastore: MetastoreServiceClient,
     retention_policy: &RetentionPolicy,
+    grace_period: Duration,
     ctx: &ActorContext<RetentionPolicyExecutor>,
 ) -> anyhow::Result<Vec<SplitMetadata>> {
     // Select splits that are published and older than the retention period.
     let retention_period = retention_policy.retention_period()?;
     let current_timestamp = OffsetDateTime::now_utc().unix_timestamp();
-    let max_retention_timestamp = current_timestamp - retention_period.as_secs() as i64;
+    let max_retention_timestamp =
+        current_timestamp - retention_period.as_secs() as i64 - grace_period.as_secs() as i64;
     let query = ListSplitsQuery::for_index(index_uid.clone())
         .with_split_state(SplitState::Published)
         .with_time_range_end_lte(max_retention_timestamp);
@@ -96,3 +104,129 @@ pub async fn run_execute_retention_policy(
         .await?;
     Ok(expired_splits)
 }
+
+#[cfg(test)]
+mod tests {
+    use quickwit_actors::Universe;
+    use quickwit_config::IndexConfig;
+    use quickwit_metastore::{metastore_for_test, CreateIndexRequestExt, StageSplitsRequestExt};
+    use quickwit_proto::metastore::{CreateIndexRequest, PublishSplitsRequest, StageSplitsRequest};
+    use tokio::sync::watch;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_execute_retention_policy() {
+        let mut metastore = metastore_for_test();
+
+        let index_id = "test-retention-policy";
+        let index_config = IndexConfig::for_test(index_id, &format!("ram:///indexes/{index_id}"));
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        let index_uid: IndexUid = metastore
+            .create_index(create_index_request)
+            .await
+            .unwrap()
+            .index_uid
+            .into();
+
+        let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        let splits_metadata: Vec<SplitMetadata> = [
+            ("split-expired", now_timestamp - 3 * 3600),
+            // Past the retention period, but not past the grace period.
+            ("split-within-grace-period", now_timestamp - 3600 - 60),
+            ("split-recent", now_timestamp - 60),
+        ]
+        .into_iter()
+        .map(|(split_id, time_range_end)| SplitMetadata {
+            split_id: split_id.to_string(),
+            index_uid: index_uid.clone(),
+            time_range: Some(time_range_end - 60..=time_range_end),
+            ..Default::default()
+        })
+        .collect();
+        let staged_split_ids: Vec<SplitId> = splits_metadata
+            .iter()
+            .map(|split_metadata| split_metadata.split_id.clone())
+            .collect();
+        let stage_splits_request =
+            StageSplitsRequest::try_from_splits_metadata(index_uid.clone(), splits_metadata)
+                .unwrap();
+        metastore.stage_splits(stage_splits_request).await.unwrap();
+
+        let publish_splits_request = PublishSplitsRequest {
+            index_uid: index_uid.to_string(),
+            staged_split_ids,
+            ..Default::default()
+        };
+        metastore
+            .publish_splits(publish_splits_request)
+            .await
+            .unwrap();
+
+        let universe = Universe::with_accelerated_time();
+        let (mailbox, _inbox) = universe.create_test_mailbox::<RetentionPolicyExecutor>();
+        let (observable_state_tx, _observable_state_rx) = watch::channel(Default::default());
+        let ctx = ActorContext::for_test(&universe, mailbox, observable_state_tx);
+
+        let retention_policy = RetentionPolicy {
+            retention_period: "1 hour".to_string(),
+            evaluation_schedule: "hourly".to_string(),
+        };
+        let grace_period = Duration::from_secs(5 * 60);
+
+        let expired_splits = run_execute_retention_policy(
+            index_uid.clone(),
+            metastore.clone(),
+            &retention_policy,
+            grace_period,
+            &ctx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(expired_splits.len(), 1);
+        assert_eq!(expired_splits[0].split_id, "split-expired");
+
+        let query = ListSplitsQuery::for_index(index_uid.clone())
+            .with_split_state(SplitState::MarkedForDeletion);
+        let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query).unwrap();
+        let marked_split_ids = metastore
+            .list_splits(list_splits_request)
+            .await
+            .unwrap()
+            .collect_split_ids()
+            .await
+            .unwrap();
+        assert_eq!(marked_split_ids, ["split-expired"]);
+
+        let query =
+            ListSplitsQuery::for_index(index_uid.clone()).with_split_state(SplitState::Published);
+        let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query).unwrap();
+        let mut published_split_ids = metastore
+            .list_splits(list_splits_request)
+            .await
+            .unwrap()
+            .collect_split_ids()
+            .await
+            .unwrap();
+        published_split_ids.sort();
+        assert_eq!(
+            published_split_ids,
+            ["split-recent", "split-within-grace-period"]
+        );
+
+        // Expired splits are only marked for deletion once.
+        let expired_splits = run_execute_retention_policy(
+            index_uid,
+            metastore,
+            &retention_policy,
+            grace_period,
+            &ctx,
+        )
+        .await
+        .unwrap();
+        assert!(expired_splits.is_empty());
+
+        universe.assert_quit().await;
+    }
+}
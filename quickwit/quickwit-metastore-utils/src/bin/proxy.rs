@@ -234,7 +234,8 @@ impl MetastoreService for MetastoreProxyService {
         let resp = lock.client.update_splits_delete_opstamp(request).await?;
         Ok(resp)
     }
-    /// Lists delete tasks with `delete_task.opstamp` > `opstamp_start` for a given `index_id`.
+    /// Lists delete tasks with `delete_task.opstamp` > `opstamp_start` for a given `index_id`,
+    /// ordered by ascending opstamp.
     async fn list_delete_tasks(
         &self,
         request: tonic::Request<ListDeleteTasksRequest>,
@@ -48,16 +48,19 @@ pub(crate) use metastore::index_metadata::serialize::{IndexMetadataV0_7, Version
 #[cfg(feature = "postgres")]
 pub use metastore::postgres::PostgresqlMetastore;
 pub use metastore::{
-    file_backed, AddSourceRequestExt, CreateIndexRequestExt, CreateIndexResponseExt, IndexMetadata,
-    IndexMetadataResponseExt, ListIndexesMetadataResponseExt, ListSplitsQuery,
-    ListSplitsRequestExt, ListSplitsResponseExt, MetastoreServiceExt,
-    MetastoreServiceStreamSplitsExt, PublishSplitsRequestExt, StageSplitsRequestExt,
+    file_backed, poll_changes, subscribe_changes, AddSourceRequestExt, CreateIndexRequestExt,
+    CreateIndexResponseExt, IndexMetadata, IndexMetadataResponseExt,
+    ListIndexesMetadataResponseExt, ListSplitsQuery, ListSplitsRequestExt, ListSplitsResponseExt,
+    MetastoreEvent, MetastoreEventStream, MetastoreServiceExt, MetastoreServiceStreamSplitsExt,
+    PublishSplitsRequestExt, StageSplitsRequestExt, UpdateSplitStateRequestExt,
 };
 pub use metastore_factory::{MetastoreFactory, UnsupportedMetastore};
 pub use metastore_resolver::MetastoreResolver;
 use quickwit_common::is_disjoint;
 use quickwit_doc_mapper::tag_pruning::TagFilterAst;
-pub use split_metadata::{Split, SplitInfo, SplitMaturity, SplitMetadata, SplitState};
+pub use split_metadata::{
+    Split, SplitInfo, SplitMaturity, SplitMetadata, SplitState, SPLIT_FORMAT_VERSION,
+};
 pub(crate) use split_metadata_version::{SplitMetadataV0_7, VersionedSplitMetadata};
 
 #[derive(utoipa::OpenApi)]
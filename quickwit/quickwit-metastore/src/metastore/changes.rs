@@ -0,0 +1,548 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashSet;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::Stream;
+use quickwit_common::pubsub::{EventBroker, EventSubscriptionHandle};
+use quickwit_common::spawn_named_task;
+use quickwit_proto::metastore::{
+    CreateIndexRequest, DeleteIndexRequest, EntityKind, IndexMetadataRequest, ListSplitsRequest,
+    MarkSplitsForDeletionRequest, MetastoreError, MetastoreResult, MetastoreService,
+    MetastoreServiceClient, PublishSplitsRequest,
+};
+use quickwit_proto::types::{IndexId, IndexUid, SplitId};
+use time::OffsetDateTime;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use super::{
+    CreateIndexRequestExt, IndexMetadataResponseExt, ListSplitsQuery, ListSplitsRequestExt,
+    MetastoreServiceStreamSplitsExt,
+};
+use crate::SplitState;
+
+/// Change made to the metastore, emitted once the write that caused it has succeeded.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MetastoreEvent {
+    /// An index was created.
+    IndexCreated {
+        /// ID of the created index.
+        index_id: IndexId,
+    },
+    /// An index was deleted.
+    IndexDeleted {
+        /// UID of the deleted index.
+        index_uid: IndexUid,
+    },
+    /// Splits were published.
+    SplitsPublished {
+        /// UID of the index of the splits.
+        index_uid: IndexUid,
+        /// Published splits.
+        published_split_ids: Vec<SplitId>,
+        /// Splits replaced by the published splits, and marked for deletion in the process.
+        replaced_split_ids: Vec<SplitId>,
+    },
+    /// Splits were marked for deletion.
+    SplitsMarkedForDeletion {
+        /// UID of the index of the splits.
+        index_uid: IndexUid,
+        /// Splits marked for deletion.
+        split_ids: Vec<SplitId>,
+    },
+}
+
+impl MetastoreEvent {
+    fn index_id(&self) -> &str {
+        match self {
+            MetastoreEvent::IndexCreated { index_id } => index_id,
+            MetastoreEvent::IndexDeleted { index_uid }
+            | MetastoreEvent::SplitsPublished { index_uid, .. }
+            | MetastoreEvent::SplitsMarkedForDeletion { index_uid, .. } => index_uid.index_id(),
+        }
+    }
+}
+
+/// Stream of the changes made to the metastore for a given index, returned by
+/// [`subscribe_changes`] or [`poll_changes`]. Dropping the stream cancels the subscription.
+pub struct MetastoreEventStream {
+    event_rx: mpsc::UnboundedReceiver<MetastoreEvent>,
+    _subscription_handles: Vec<EventSubscriptionHandle>,
+}
+
+impl Stream for MetastoreEventStream {
+    type Item = MetastoreEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.event_rx.poll_recv(cx)
+    }
+}
+
+/// Sends the events of a given index to a [`MetastoreEventStream`].
+#[derive(Clone)]
+struct EventForwarder {
+    index_id: IndexId,
+    event_tx: mpsc::UnboundedSender<MetastoreEvent>,
+}
+
+impl EventForwarder {
+    fn forward(&self, event: MetastoreEvent) {
+        if event.index_id() == self.index_id {
+            // The stream may have been dropped in the meantime.
+            let _ = self.event_tx.send(event);
+        }
+    }
+
+    fn forward_for_index_uid(
+        &self,
+        index_uid: &str,
+        event_fn: impl FnOnce(IndexUid) -> MetastoreEvent,
+    ) {
+        // The request went through the metastore successfully, so its index UID is valid.
+        if let Ok(index_uid) = IndexUid::parse(index_uid) {
+            self.forward(event_fn(index_uid));
+        }
+    }
+}
+
+/// Subscribes to the changes made to the metastore for the index `index_id` by this node.
+///
+/// The changes are derived from the successful metastore requests published on `event_broker`,
+/// typically by an [`EventListenerLayer`](quickwit_common::tower::EventListenerLayer) stacked on
+/// the metastore client of this node, so the writes issued by other nodes are not observed: use
+/// [`poll_changes`] for those. Events are delivered asynchronously and their order is not
+/// guaranteed.
+pub fn subscribe_changes(event_broker: &EventBroker, index_id: &str) -> MetastoreEventStream {
+    let (event_tx, event_rx) = mpsc::unbounded_channel();
+    let event_forwarder = EventForwarder {
+        index_id: index_id.to_string(),
+        event_tx,
+    };
+    let subscription_handles = vec![
+        event_broker.subscribe({
+            let event_forwarder = event_forwarder.clone();
+            move |request: CreateIndexRequest| {
+                if let Ok(index_config) = request.deserialize_index_config() {
+                    event_forwarder.forward(MetastoreEvent::IndexCreated {
+                        index_id: index_config.index_id,
+                    });
+                }
+            }
+        }),
+        event_broker.subscribe({
+            let event_forwarder = event_forwarder.clone();
+            move |request: DeleteIndexRequest| {
+                event_forwarder.forward_for_index_uid(&request.index_uid, |index_uid| {
+                    MetastoreEvent::IndexDeleted { index_uid }
+                });
+            }
+        }),
+        event_broker.subscribe({
+            let event_forwarder = event_forwarder.clone();
+            move |request: PublishSplitsRequest| {
+                event_forwarder.forward_for_index_uid(&request.index_uid, |index_uid| {
+                    MetastoreEvent::SplitsPublished {
+                        index_uid,
+                        published_split_ids: request.staged_split_ids,
+                        replaced_split_ids: request.replaced_split_ids,
+                    }
+                });
+            }
+        }),
+        event_broker.subscribe(move |request: MarkSplitsForDeletionRequest| {
+            event_forwarder.forward_for_index_uid(&request.index_uid, |index_uid| {
+                MetastoreEvent::SplitsMarkedForDeletion {
+                    index_uid,
+                    split_ids: request.split_ids,
+                }
+            });
+        }),
+    ];
+    MetastoreEventStream {
+        event_rx,
+        _subscription_handles: subscription_handles,
+    }
+}
+
+/// Polls the metastore every `poll_interval` for the changes made to the index `index_id` by any
+/// node.
+///
+/// The changes are detected from the UID of the index and the publish and update timestamps of its
+/// splits, so they are observed with a delay of up to `poll_interval`. A split that changes several
+/// times between two polls is only reported in its last state, and the splits replaced by a publish
+/// are reported in a separate `SplitsMarkedForDeletion` event. The polling stops once the stream is
+/// dropped.
+pub fn poll_changes(
+    metastore: MetastoreServiceClient,
+    index_id: &str,
+    poll_interval: Duration,
+) -> MetastoreEventStream {
+    let (event_tx, event_rx) = mpsc::unbounded_channel();
+    let change_poller = ChangePoller::new(metastore, index_id.to_string());
+    spawn_named_task(
+        change_poller.run(event_tx, poll_interval),
+        "metastore_change_poller",
+    );
+    MetastoreEventStream {
+        event_rx,
+        _subscription_handles: Vec::new(),
+    }
+}
+
+struct ChangePoller {
+    metastore: MetastoreServiceClient,
+    index_id: IndexId,
+    is_initialized: bool,
+    /// UID of the index at the last poll, or `None` if the index did not exist.
+    index_uid_opt: Option<IndexUid>,
+    /// The splits that changed at or after this timestamp are listed at the next poll.
+    timestamp_cursor: i64,
+    /// The splits already reported that changed at `timestamp_cursor`, which the next poll lists
+    /// again.
+    reported_splits_at_cursor: HashSet<(SplitId, SplitState)>,
+}
+
+impl ChangePoller {
+    fn new(metastore: MetastoreServiceClient, index_id: IndexId) -> Self {
+        Self {
+            metastore,
+            index_id,
+            is_initialized: false,
+            index_uid_opt: None,
+            timestamp_cursor: OffsetDateTime::now_utc().unix_timestamp(),
+            reported_splits_at_cursor: HashSet::new(),
+        }
+    }
+
+    async fn run(
+        mut self,
+        event_tx: mpsc::UnboundedSender<MetastoreEvent>,
+        poll_interval: Duration,
+    ) {
+        let mut interval = tokio::time::interval(poll_interval);
+
+        loop {
+            interval.tick().await;
+
+            if event_tx.is_closed() {
+                return;
+            }
+            match self.poll().await {
+                Ok(events) => {
+                    for event in events {
+                        if event_tx.send(event).is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(error) => {
+                    warn!(index_id=%self.index_id, error=%error, "failed to poll metastore changes");
+                }
+            }
+        }
+    }
+
+    async fn poll(&mut self) -> MetastoreResult<Vec<MetastoreEvent>> {
+        let mut events = Vec::new();
+        let index_uid_opt = self.fetch_index_uid().await?;
+
+        if !self.is_initialized {
+            self.is_initialized = true;
+            self.index_uid_opt = index_uid_opt;
+        } else if index_uid_opt != self.index_uid_opt {
+            if let Some(index_uid) = self.index_uid_opt.take() {
+                events.push(MetastoreEvent::IndexDeleted { index_uid });
+            }
+            if index_uid_opt.is_some() {
+                events.push(MetastoreEvent::IndexCreated {
+                    index_id: self.index_id.clone(),
+                });
+            }
+            // All the splits of a new index are changes.
+            self.index_uid_opt = index_uid_opt;
+            self.timestamp_cursor = 0;
+            self.reported_splits_at_cursor.clear();
+        }
+        let Some(index_uid) = self.index_uid_opt.clone() else {
+            return Ok(events);
+        };
+        // The update timestamp of a split is greater than or equal to its publish timestamp.
+        let query = ListSplitsQuery::for_index(index_uid.clone())
+            .with_split_states([SplitState::Published, SplitState::MarkedForDeletion])
+            .with_update_timestamp_gte(self.timestamp_cursor);
+        let list_splits_request = ListSplitsRequest::try_from_list_splits_query(&query)?;
+        let splits = self
+            .metastore
+            .list_splits(list_splits_request)
+            .await?
+            .collect_splits()
+            .await?;
+
+        // The update timestamp of a published split also changes when its delete opstamp is
+        // updated, so published splits are tracked by their publish timestamp.
+        let changed_splits: Vec<(i64, SplitId, SplitState)> = splits
+            .into_iter()
+            .filter_map(|split| {
+                let change_timestamp = match split.split_state {
+                    SplitState::Published => {
+                        split.publish_timestamp.unwrap_or(split.update_timestamp)
+                    }
+                    _ => split.update_timestamp,
+                };
+                if change_timestamp < self.timestamp_cursor {
+                    return None;
+                }
+                Some((
+                    change_timestamp,
+                    split.split_metadata.split_id,
+                    split.split_state,
+                ))
+            })
+            .collect();
+
+        let Some(next_timestamp_cursor) = changed_splits
+            .iter()
+            .map(|(change_timestamp, _, _)| *change_timestamp)
+            .max()
+        else {
+            return Ok(events);
+        };
+        let mut next_reported_splits_at_cursor = HashSet::new();
+        let mut published_split_ids = Vec::new();
+        let mut marked_split_ids = Vec::new();
+
+        for (change_timestamp, split_id, split_state) in changed_splits {
+            let split_key = (split_id, split_state);
+
+            if change_timestamp == next_timestamp_cursor {
+                next_reported_splits_at_cursor.insert(split_key.clone());
+            }
+            if change_timestamp == self.timestamp_cursor
+                && self.reported_splits_at_cursor.contains(&split_key)
+            {
+                continue;
+            }
+            match split_key {
+                (split_id, SplitState::Published) => published_split_ids.push(split_id),
+                (split_id, _) => marked_split_ids.push(split_id),
+            }
+        }
+        self.timestamp_cursor = next_timestamp_cursor;
+        self.reported_splits_at_cursor = next_reported_splits_at_cursor;
+
+        if !published_split_ids.is_empty() {
+            events.push(MetastoreEvent::SplitsPublished {
+                index_uid: index_uid.clone(),
+                published_split_ids,
+                replaced_split_ids: Vec::new(),
+            });
+        }
+        if !marked_split_ids.is_empty() {
+            events.push(MetastoreEvent::SplitsMarkedForDeletion {
+                index_uid,
+                split_ids: marked_split_ids,
+            });
+        }
+        Ok(events)
+    }
+
+    async fn fetch_index_uid(&mut self) -> MetastoreResult<Option<IndexUid>> {
+        let index_metadata_request = IndexMetadataRequest::for_index_id(self.index_id.clone());
+
+        match self.metastore.index_metadata(index_metadata_request).await {
+            Ok(index_metadata_response) => {
+                let index_metadata = index_metadata_response.deserialize_index_metadata()?;
+                Ok(Some(index_metadata.index_uid))
+            }
+            Err(MetastoreError::NotFound(EntityKind::Index { .. })) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use futures::StreamExt;
+    use quickwit_common::tower::EventListenerLayer;
+    use quickwit_config::IndexConfig;
+    use quickwit_proto::metastore::{DeleteSplitsRequest, StageSplitsRequest};
+    use quickwit_storage::RamStorage;
+
+    use super::*;
+    use crate::{FileBackedMetastore, SplitMetadata, StageSplitsRequestExt};
+
+    #[tokio::test]
+    async fn test_subscribe_changes() {
+        let event_broker = EventBroker::default();
+        let event_listener_layer = EventListenerLayer::new(event_broker.clone());
+        let mut metastore = MetastoreServiceClient::tower()
+            .stack_create_index_layer(event_listener_layer.clone())
+            .stack_publish_splits_layer(event_listener_layer.clone())
+            .stack_mark_splits_for_deletion_layer(event_listener_layer)
+            .build(FileBackedMetastore::for_test(Arc::new(
+                RamStorage::default(),
+            )));
+        let mut index_events = subscribe_changes(&event_broker, "test-index");
+        let mut other_index_events = subscribe_changes(&event_broker, "other-index");
+
+        let index_config = IndexConfig::for_test("test-index", "ram:///indexes/test-index");
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        let index_uid: IndexUid = metastore
+            .create_index(create_index_request)
+            .await
+            .unwrap()
+            .index_uid
+            .into();
+
+        let event = index_events.next().await.unwrap();
+        assert_eq!(
+            event,
+            MetastoreEvent::IndexCreated {
+                index_id: "test-index".to_string()
+            }
+        );
+        let split_metadata = SplitMetadata {
+            split_id: "split".to_string(),
+            index_uid: index_uid.clone(),
+            ..Default::default()
+        };
+        let stage_splits_request =
+            StageSplitsRequest::try_from_split_metadata(index_uid.clone(), &split_metadata)
+                .unwrap();
+        metastore.stage_splits(stage_splits_request).await.unwrap();
+
+        let publish_splits_request = PublishSplitsRequest {
+            index_uid: index_uid.to_string(),
+            staged_split_ids: vec!["split".to_string()],
+            ..Default::default()
+        };
+        metastore
+            .publish_splits(publish_splits_request)
+            .await
+            .unwrap();
+
+        let event = index_events.next().await.unwrap();
+        assert_eq!(
+            event,
+            MetastoreEvent::SplitsPublished {
+                index_uid,
+                published_split_ids: vec!["split".to_string()],
+                replaced_split_ids: Vec::new(),
+            }
+        );
+        tokio::time::timeout(Duration::from_millis(100), other_index_events.next())
+            .await
+            .unwrap_err();
+    }
+
+    async fn next_event(events: &mut MetastoreEventStream) -> MetastoreEvent {
+        tokio::time::timeout(Duration::from_secs(5), events.next())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_poll_changes() {
+        let mut metastore = MetastoreServiceClient::from(FileBackedMetastore::for_test(Arc::new(
+            RamStorage::default(),
+        )));
+        let index_config = IndexConfig::for_test("test-index", "ram:///indexes/test-index");
+        let create_index_request =
+            CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+        let index_uid: IndexUid = metastore
+            .create_index(create_index_request)
+            .await
+            .unwrap()
+            .index_uid
+            .into();
+
+        let mut index_events =
+            poll_changes(metastore.clone(), "test-index", Duration::from_millis(10));
+        let split_metadata = SplitMetadata {
+            split_id: "split".to_string(),
+            index_uid: index_uid.clone(),
+            ..Default::default()
+        };
+        let stage_splits_request =
+            StageSplitsRequest::try_from_split_metadata(index_uid.clone(), &split_metadata)
+                .unwrap();
+        metastore.stage_splits(stage_splits_request).await.unwrap();
+
+        let publish_splits_request = PublishSplitsRequest {
+            index_uid: index_uid.to_string(),
+            staged_split_ids: vec!["split".to_string()],
+            ..Default::default()
+        };
+        metastore
+            .publish_splits(publish_splits_request)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            next_event(&mut index_events).await,
+            MetastoreEvent::SplitsPublished {
+                index_uid: index_uid.clone(),
+                published_split_ids: vec!["split".to_string()],
+                replaced_split_ids: Vec::new(),
+            }
+        );
+        let mark_splits_for_deletion_request =
+            MarkSplitsForDeletionRequest::new(index_uid.clone(), vec!["split".to_string()]);
+        metastore
+            .mark_splits_for_deletion(mark_splits_for_deletion_request)
+            .await
+            .unwrap();
+
+        // The published split is not reported again.
+        assert_eq!(
+            next_event(&mut index_events).await,
+            MetastoreEvent::SplitsMarkedForDeletion {
+                index_uid: index_uid.clone(),
+                split_ids: vec!["split".to_string()],
+            }
+        );
+        let delete_splits_request = DeleteSplitsRequest {
+            index_uid: index_uid.to_string(),
+            split_ids: vec!["split".to_string()],
+        };
+        metastore
+            .delete_splits(delete_splits_request)
+            .await
+            .unwrap();
+
+        let delete_index_request = DeleteIndexRequest {
+            index_uid: index_uid.to_string(),
+        };
+        metastore.delete_index(delete_index_request).await.unwrap();
+
+        assert_eq!(
+            next_event(&mut index_events).await,
+            MetastoreEvent::IndexDeleted { index_uid }
+        );
+    }
+}
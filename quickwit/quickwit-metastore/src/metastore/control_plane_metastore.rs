@@ -35,8 +35,8 @@ use quickwit_proto::metastore::{
     ListSplitsRequest, ListSplitsResponse, ListStaleSplitsRequest, MarkSplitsForDeletionRequest,
     MetastoreResult, MetastoreService, MetastoreServiceClient, MetastoreServiceStream,
     OpenShardsRequest, OpenShardsResponse, PublishSplitsRequest, ResetSourceCheckpointRequest,
-    StageSplitsRequest, ToggleSourceRequest, UpdateSplitsDeleteOpstampRequest,
-    UpdateSplitsDeleteOpstampResponse,
+    StageSplitsRequest, ToggleSourceRequest, UpdateSplitStateRequest,
+    UpdateSplitsDeleteOpstampRequest, UpdateSplitsDeleteOpstampResponse,
 };
 
 /// A [`MetastoreService`] implementation that proxies some requests to the control plane so it can
@@ -173,6 +173,13 @@ impl MetastoreService for ControlPlaneMetastore {
         self.metastore.delete_splits(request).await
     }
 
+    async fn update_split_state(
+        &mut self,
+        request: UpdateSplitStateRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        self.metastore.update_split_state(request).await
+    }
+
     async fn reset_source_checkpoint(
         &mut self,
         request: ResetSourceCheckpointRequest,
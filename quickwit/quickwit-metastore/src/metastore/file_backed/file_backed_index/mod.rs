@@ -28,7 +28,7 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::ops::Bound;
 
-use itertools::Itertools;
+use itertools::{Either, Itertools};
 use quickwit_common::PrettySample;
 use quickwit_config::{SourceConfig, INGEST_V2_SOURCE_ID};
 use quickwit_proto::metastore::{
@@ -45,6 +45,7 @@ use tracing::{info, warn};
 
 use super::MutationOccurred;
 use crate::checkpoint::IndexCheckpointDelta;
+use crate::metastore::validate_split_time_range;
 use crate::{split_tag_filter, IndexMetadata, ListSplitsQuery, Split, SplitMetadata, SplitState};
 
 /// A `FileBackedIndex` object carries an index metadata and its split metadata.
@@ -132,14 +133,7 @@ impl quickwit_config::TestableForRegression for FileBackedIndex {
 
 impl From<IndexMetadata> for FileBackedIndex {
     fn from(index_metadata: IndexMetadata) -> Self {
-        let per_source_shards = index_metadata
-            .sources
-            .keys()
-            .map(|source_id| {
-                let shards = Shards::empty(index_metadata.index_uid.clone(), source_id.clone());
-                (source_id.clone(), shards)
-            })
-            .collect();
+        let per_source_shards = empty_per_source_shards(&index_metadata);
 
         Self {
             metadata: index_metadata,
@@ -153,6 +147,17 @@ impl From<IndexMetadata> for FileBackedIndex {
     }
 }
 
+fn empty_per_source_shards(index_metadata: &IndexMetadata) -> HashMap<SourceId, Shards> {
+    index_metadata
+        .sources
+        .keys()
+        .map(|source_id| {
+            let shards = Shards::empty(index_metadata.index_uid.clone(), source_id.clone());
+            (source_id.clone(), shards)
+        })
+        .collect()
+}
+
 enum DeleteSplitOutcome {
     Success,
     SplitNotFound,
@@ -213,6 +218,21 @@ impl FileBackedIndex {
         &self.metadata
     }
 
+    /// Overwrites the index with `index_metadata`, which must carry the same index UID: the staged
+    /// and published splits are marked for deletion and the shards are dropped.
+    pub(crate) fn overwrite(&mut self, index_metadata: IndexMetadata) -> MetastoreResult<()> {
+        let split_ids: Vec<SplitId> = self.splits.keys().cloned().collect();
+        let deletable_split_states = [
+            SplitState::Staged,
+            SplitState::Published,
+            SplitState::MarkedForDeletion,
+        ];
+        self.mark_splits_for_deletion(split_ids, &deletable_split_states, false)?;
+        self.per_source_shards = empty_per_source_shards(&index_metadata);
+        self.metadata = index_metadata;
+        Ok(())
+    }
+
     /// Stages a single split.
     ///
     /// If a split already exists and is in the [SplitState::Staged] state,
@@ -221,10 +241,14 @@ impl FileBackedIndex {
     /// If a split already exists and is *not* in the [SplitState::Staged] state, a
     /// [MetastoreError::NotFound] error is returned providing the split ID to go with
     /// it.
+    ///
+    /// If the split time range is inverted, a [MetastoreError::InvalidArgument] error is returned.
     pub(crate) fn stage_split(
         &mut self,
         split_metadata: SplitMetadata,
     ) -> Result<(), MetastoreError> {
+        validate_split_time_range(&split_metadata)?;
+
         // Check whether the split exists.
         // If the split exists, we check what state it is in. If it's anything other than `Staged`
         // something has gone very wrong and we should abort the operation.
@@ -306,6 +330,42 @@ impl FileBackedIndex {
         Ok(mutation_occurred)
     }
 
+    /// Transitions the split `split_id` from `expected_split_state` to `new_split_state`. Fails
+    /// with a `FailedPrecondition` error if the split is not in the expected state.
+    pub(crate) fn update_split_state(
+        &mut self,
+        split_id: &str,
+        expected_split_state: SplitState,
+        new_split_state: SplitState,
+    ) -> MetastoreResult<bool> {
+        let Some(metadata) = self.splits.get_mut(split_id) else {
+            return Err(MetastoreError::NotFound(EntityKind::Splits {
+                split_ids: vec![split_id.to_string()],
+            }));
+        };
+        if metadata.split_state != expected_split_state {
+            let entity = EntityKind::Splits {
+                split_ids: vec![split_id.to_string()],
+            };
+            let message = format!(
+                "split is `{}` instead of `{expected_split_state}`",
+                metadata.split_state
+            );
+            return Err(MetastoreError::FailedPrecondition { entity, message });
+        }
+        if expected_split_state == new_split_state {
+            return Ok(false);
+        }
+        let now_timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        metadata.split_state = new_split_state;
+        metadata.update_timestamp = now_timestamp;
+
+        if new_split_state == SplitState::Published {
+            metadata.publish_timestamp = Some(now_timestamp);
+        }
+        Ok(true)
+    }
+
     /// Helper to mark a list of splits as published.
     /// This function however does not update the checkpoint.
     fn mark_splits_as_published_helper(
@@ -390,9 +450,21 @@ impl FileBackedIndex {
         let limit = query.limit.unwrap_or(usize::MAX);
         let offset = query.offset.unwrap_or_default();
 
+        // Splits requested by ID are looked up directly.
+        let candidate_splits = if query.split_ids.is_empty() {
+            Either::Left(self.splits.values())
+        } else {
+            Either::Right(
+                query
+                    .split_ids
+                    .iter()
+                    .unique()
+                    .filter_map(|split_id| self.splits.get(split_id)),
+            )
+        };
+
         let splits: Vec<Split> = if query.sort_by_staleness {
-            self.splits
-                .values()
+            candidate_splits
                 .filter(|split| split_query_predicate(split, query))
                 .sorted_unstable_by(|left_split, right_split| {
                     left_split
@@ -410,8 +482,7 @@ impl FileBackedIndex {
                 .cloned()
                 .collect()
         } else {
-            self.splits
-                .values()
+            candidate_splits
                 .filter(|split| split_query_predicate(split, query))
                 .skip(offset)
                 .take(limit)
@@ -542,14 +613,15 @@ impl FileBackedIndex {
         Ok(true)
     }
 
-    /// Lists delete tasks with opstamp > `opstamp_start`.
+    /// Lists delete tasks with opstamp > `opstamp_start`, ordered by ascending opstamp.
     pub(crate) fn list_delete_tasks(&self, opstamp_start: u64) -> MetastoreResult<Vec<DeleteTask>> {
-        let delete_tasks = self
+        let mut delete_tasks: Vec<DeleteTask> = self
             .delete_tasks
             .iter()
             .filter(|delete_task| delete_task.opstamp > opstamp_start)
             .cloned()
             .collect();
+        delete_tasks.sort_by_key(|delete_task| delete_task.opstamp);
         Ok(delete_tasks)
     }
 
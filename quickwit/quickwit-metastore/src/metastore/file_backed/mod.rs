@@ -44,10 +44,10 @@ use quickwit_common::ServiceStream;
 use quickwit_config::IndexTemplate;
 use quickwit_proto::metastore::{
     serde_utils, AcquireShardsRequest, AcquireShardsResponse, AcquireShardsSubrequest,
-    AddSourceRequest, CreateIndexRequest, CreateIndexResponse, CreateIndexTemplateRequest,
-    DeleteIndexRequest, DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest,
-    DeleteShardsResponse, DeleteShardsSubrequest, DeleteSourceRequest, DeleteSplitsRequest,
-    DeleteTask, EmptyResponse, EntityKind, FindIndexTemplateMatchesRequest,
+    AddSourceRequest, CreateIndexMode, CreateIndexRequest, CreateIndexResponse,
+    CreateIndexTemplateRequest, DeleteIndexRequest, DeleteIndexTemplatesRequest, DeleteQuery,
+    DeleteShardsRequest, DeleteShardsResponse, DeleteShardsSubrequest, DeleteSourceRequest,
+    DeleteSplitsRequest, DeleteTask, EmptyResponse, EntityKind, FindIndexTemplateMatchesRequest,
     FindIndexTemplateMatchesResponse, GetIndexTemplateRequest, GetIndexTemplateResponse,
     IndexMetadataRequest, IndexMetadataResponse, IndexTemplateMatch, LastDeleteOpstampRequest,
     LastDeleteOpstampResponse, ListDeleteTasksRequest, ListDeleteTasksResponse,
@@ -56,8 +56,8 @@ use quickwit_proto::metastore::{
     ListSplitsResponse, ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError,
     MetastoreResult, MetastoreService, MetastoreServiceStream, OpenShardsRequest,
     OpenShardsResponse, OpenShardsSubrequest, PublishSplitsRequest, ResetSourceCheckpointRequest,
-    StageSplitsRequest, ToggleSourceRequest, UpdateSplitsDeleteOpstampRequest,
-    UpdateSplitsDeleteOpstampResponse,
+    StageSplitsRequest, ToggleSourceRequest, UpdateSplitStateRequest,
+    UpdateSplitsDeleteOpstampRequest, UpdateSplitsDeleteOpstampResponse,
 };
 use quickwit_proto::types::{IndexId, IndexUid};
 use quickwit_storage::Storage;
@@ -72,9 +72,10 @@ use self::manifest::{load_or_create_manifest, save_manifest, MANIFEST_FILE_NAME}
 use self::state::MetastoreState;
 use self::store_operations::{delete_index, index_exists, load_index, put_index};
 use super::{
-    AddSourceRequestExt, CreateIndexRequestExt, IndexMetadataResponseExt,
+    overwrite_index_metadata, AddSourceRequestExt, CreateIndexRequestExt, IndexMetadataResponseExt,
     ListIndexesMetadataResponseExt, ListSplitsRequestExt, ListSplitsResponseExt,
-    PublishSplitsRequestExt, StageSplitsRequestExt, STREAM_SPLITS_CHUNK_SIZE,
+    PublishSplitsRequestExt, StageSplitsRequestExt, UpdateSplitStateRequestExt,
+    STREAM_SPLITS_CHUNK_SIZE,
 };
 use crate::checkpoint::IndexCheckpointDelta;
 use crate::{IndexMetadata, ListSplitsQuery, MetastoreServiceExt, Split, SplitState};
@@ -248,6 +249,39 @@ impl FileBackedMetastore {
         }
     }
 
+    /// Handles the creation of an index that already exists according to `create_index_mode`:
+    /// returns the existing index for `IfNotExists` or overwrites it for `Overwrite`.
+    async fn create_existing_index(
+        &self,
+        index_metadata: IndexMetadata,
+        create_index_mode: CreateIndexMode,
+    ) -> MetastoreResult<CreateIndexResponse> {
+        let existing_index_metadata = self
+            .read_any(index_metadata.index_id(), |existing_index| {
+                Ok(existing_index.metadata().clone())
+            })
+            .await?;
+        let index_metadata = if create_index_mode == CreateIndexMode::Overwrite {
+            let index_metadata =
+                overwrite_index_metadata(&existing_index_metadata, index_metadata)?;
+            let index_uid = index_metadata.index_uid.clone();
+            self.mutate(index_uid, |existing_index| {
+                existing_index.overwrite(index_metadata.clone())?;
+                Ok(MutationOccurred::Yes(()))
+            })
+            .await?;
+            index_metadata
+        } else {
+            existing_index_metadata
+        };
+        let index_metadata_json = serde_utils::to_json_str(&index_metadata)?;
+        let response = CreateIndexResponse {
+            index_uid: index_metadata.index_uid.into(),
+            index_metadata_json,
+        };
+        Ok(response)
+    }
+
     async fn read<T, F>(&self, index_uid: IndexUid, view: F) -> MetastoreResult<T>
     where F: FnOnce(&FileBackedIndex) -> MetastoreResult<T> {
         let index_id = index_uid.index_id();
@@ -396,22 +430,27 @@ impl MetastoreService for FileBackedMetastore {
         let index_uid = index_metadata.index_uid.clone();
         let index_id = index_uid.index_id().to_string();
 
-        let index_metadata_json = serde_utils::to_json_str(&index_metadata)?;
-        let index = FileBackedIndex::from(index_metadata);
-
         let mut state_wlock_guard = self.state.write().await;
 
         // Checking if index already exists is a bit tedious:
-        // - first we check the index state: if it's `Active`, return `IndexAlreadyExists` error,
-        //   and if it's `Creating` or `Deleting`, it's ok to override them as these are
-        //   transitioning states.
+        // - first we check the index state: if it's `Active`, handle the existing index according
+        //   to the create index mode, and if it's `Creating` or `Deleting`, it's ok to override
+        //   them as these are transitioning states.
         // - if the index is not in the index states map, we still need to check the storage as we
         //   don't want to override an existing metadata file.
         if let Some(index_status) = state_wlock_guard.indexes.get(&index_id) {
             if let LazyIndexStatus::Active(_) = index_status {
-                return Err(MetastoreError::AlreadyExists(EntityKind::Index {
-                    index_id,
-                }));
+                let create_index_mode = request.mode();
+
+                if create_index_mode == CreateIndexMode::ErrorIfExists {
+                    return Err(MetastoreError::AlreadyExists(EntityKind::Index {
+                        index_id,
+                    }));
+                }
+                drop(state_wlock_guard);
+                return self
+                    .create_existing_index(index_metadata, create_index_mode)
+                    .await;
             }
         } else if index_exists(&*self.storage, &index_id).await? {
             return Err(MetastoreError::Internal {
@@ -422,6 +461,9 @@ impl MetastoreService for FileBackedMetastore {
                 ),
             });
         }
+        let index_metadata_json = serde_utils::to_json_str(&index_metadata)?;
+        let index = FileBackedIndex::from(index_metadata);
+
         // Set state to `Creating` and rollback on metastore error.
         state_wlock_guard
             .indexes
@@ -611,6 +653,22 @@ impl MetastoreService for FileBackedMetastore {
         Ok(EmptyResponse {})
     }
 
+    async fn update_split_state(
+        &mut self,
+        request: UpdateSplitStateRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let (expected_split_state, new_split_state) = request.deserialize_split_states()?;
+        let index_uid: IndexUid = request.index_uid.into();
+
+        self.mutate(index_uid, |index| {
+            index
+                .update_split_state(&request.split_id, expected_split_state, new_split_state)
+                .map(MutationOccurred::from)
+        })
+        .await?;
+        Ok(EmptyResponse {})
+    }
+
     async fn add_source(&mut self, request: AddSourceRequest) -> MetastoreResult<EmptyResponse> {
         let source_config = request.deserialize_source_config()?;
         let index_uid = request.index_uid.into();
@@ -17,6 +17,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+mod changes;
 pub mod file_backed;
 pub(crate) mod index_metadata;
 #[cfg(feature = "postgres")]
@@ -24,9 +25,11 @@ pub mod postgres;
 
 pub mod control_plane_metastore;
 
+use std::collections::HashSet;
 use std::ops::{Bound, RangeInclusive};
 
 use async_trait::async_trait;
+pub use changes::{poll_changes, subscribe_changes, MetastoreEvent, MetastoreEventStream};
 use futures::TryStreamExt;
 pub use index_metadata::IndexMetadata;
 use itertools::Itertools;
@@ -35,10 +38,11 @@ use quickwit_common::tower::PrometheusMetricsLayer;
 use quickwit_config::{IndexConfig, SourceConfig};
 use quickwit_doc_mapper::tag_pruning::TagFilterAst;
 use quickwit_proto::metastore::{
-    serde_utils, AddSourceRequest, CreateIndexRequest, CreateIndexResponse, DeleteTask,
-    IndexMetadataRequest, IndexMetadataResponse, ListIndexesMetadataResponse, ListSplitsRequest,
-    ListSplitsResponse, MetastoreError, MetastoreResult, MetastoreService, MetastoreServiceClient,
-    MetastoreServiceStream, PublishSplitsRequest, StageSplitsRequest,
+    serde_utils, AddSourceRequest, CreateIndexMode, CreateIndexRequest, CreateIndexResponse,
+    DeleteTask, EntityKind, IndexMetadataRequest, IndexMetadataResponse,
+    ListIndexesMetadataResponse, ListSplitsRequest, ListSplitsResponse, MetastoreError,
+    MetastoreResult, MetastoreService, MetastoreServiceClient, MetastoreServiceStream,
+    PublishSplitsRequest, StageSplitsRequest, UpdateSplitStateRequest,
 };
 use quickwit_proto::types::{IndexUid, SplitId};
 use time::OffsetDateTime;
@@ -60,6 +64,44 @@ pub(crate) fn instrument_metastore(
         .build(metastore_impl)
 }
 
+/// Returns an error if the time range of a split is inverted, i.e. its start is greater than its
+/// end. Such a split would silently be pruned from every time range query.
+pub(crate) fn validate_split_time_range(split_metadata: &SplitMetadata) -> MetastoreResult<()> {
+    if let Some(time_range) = &split_metadata.time_range {
+        if time_range.start() > time_range.end() {
+            let message = format!(
+                "split `{}` has an inverted time range `{}..={}`",
+                split_metadata.split_id,
+                time_range.start(),
+                time_range.end()
+            );
+            return Err(MetastoreError::InvalidArgument { message });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the metadata of an index overwritten with `new_index_metadata`: the index config, the
+/// sources, and the checkpoint are replaced, but the index keeps its UID, so that the garbage
+/// collector can still delete the splits of the overwritten index. Since these splits are deleted
+/// from the index storage, the index URI cannot change.
+pub(crate) fn overwrite_index_metadata(
+    existing_index_metadata: &IndexMetadata,
+    mut new_index_metadata: IndexMetadata,
+) -> MetastoreResult<IndexMetadata> {
+    if new_index_metadata.index_uri() != existing_index_metadata.index_uri() {
+        let message = format!(
+            "index `{}` cannot be overwritten with a different index URI: `{}` != `{}`",
+            existing_index_metadata.index_id(),
+            new_index_metadata.index_uri(),
+            existing_index_metadata.index_uri()
+        );
+        return Err(MetastoreError::InvalidArgument { message });
+    }
+    new_index_metadata.index_uid = existing_index_metadata.index_uid.clone();
+    Ok(new_index_metadata)
+}
+
 /// An extended trait for [`MetastoreService`].
 #[async_trait]
 pub trait MetastoreServiceExt: MetastoreService {
@@ -72,6 +114,41 @@ pub trait MetastoreServiceExt: MetastoreService {
             Err(error) => Err(error),
         }
     }
+
+    /// Returns the metadata of the splits `split_ids` of the index `index_uid`, looked up by ID.
+    /// Returns a `NotFound` error listing the missing splits if some of them do not exist.
+    async fn get_splits(
+        &mut self,
+        index_uid: IndexUid,
+        split_ids: &[SplitId],
+    ) -> MetastoreResult<Vec<SplitMetadata>> {
+        if split_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let query = ListSplitsQuery::for_index(index_uid).with_split_ids(split_ids.iter().cloned());
+        let request = ListSplitsRequest::try_from_list_splits_query(query)?;
+        let splits_metadata = self
+            .list_splits(request)
+            .await?
+            .collect_splits_metadata()
+            .await?;
+
+        let found_split_ids: HashSet<&str> = splits_metadata
+            .iter()
+            .map(|split_metadata| split_metadata.split_id())
+            .collect();
+        let missing_split_ids: Vec<SplitId> = split_ids
+            .iter()
+            .filter(|split_id| !found_split_ids.contains(split_id.as_str()))
+            .cloned()
+            .collect();
+        if !missing_split_ids.is_empty() {
+            return Err(MetastoreError::NotFound(EntityKind::Splits {
+                split_ids: missing_split_ids,
+            }));
+        }
+        Ok(splits_metadata)
+    }
 }
 
 impl MetastoreServiceExt for MetastoreServiceClient {}
@@ -140,6 +217,7 @@ impl CreateIndexRequestExt for CreateIndexRequest {
         let request = Self {
             index_config_json,
             source_configs_json,
+            mode: CreateIndexMode::ErrorIfExists as i32,
         };
         Ok(request)
     }
@@ -399,6 +477,65 @@ impl PublishSplitsRequestExt for PublishSplitsRequest {
     }
 }
 
+/// Helper trait to build an [`UpdateSplitStateRequest`] and deserialize its payload.
+pub trait UpdateSplitStateRequestExt {
+    /// Creates a new [`UpdateSplitStateRequest`] transitioning the split `split_id` from
+    /// `expected_split_state` to `new_split_state`.
+    fn new(
+        index_uid: impl Into<IndexUid>,
+        split_id: impl Into<SplitId>,
+        expected_split_state: SplitState,
+        new_split_state: SplitState,
+    ) -> UpdateSplitStateRequest;
+
+    /// Deserializes the `expected_split_state` and `new_split_state` fields of an
+    /// [`UpdateSplitStateRequest`] and checks that the transition between them is allowed.
+    fn deserialize_split_states(&self) -> MetastoreResult<(SplitState, SplitState)>;
+}
+
+impl UpdateSplitStateRequestExt for UpdateSplitStateRequest {
+    fn new(
+        index_uid: impl Into<IndexUid>,
+        split_id: impl Into<SplitId>,
+        expected_split_state: SplitState,
+        new_split_state: SplitState,
+    ) -> UpdateSplitStateRequest {
+        Self {
+            index_uid: index_uid.into().into(),
+            split_id: split_id.into(),
+            expected_split_state: expected_split_state.as_str().to_string(),
+            new_split_state: new_split_state.as_str().to_string(),
+        }
+    }
+
+    fn deserialize_split_states(&self) -> MetastoreResult<(SplitState, SplitState)> {
+        let parse_split_state = |split_state_str: &str| {
+            split_state_str
+                .parse::<SplitState>()
+                .map_err(|message| MetastoreError::InvalidArgument { message })
+        };
+        let expected_split_state = parse_split_state(&self.expected_split_state)?;
+        let new_split_state = parse_split_state(&self.new_split_state)?;
+
+        // Splits only ever move forward: staged -> published -> marked for deletion.
+        let is_valid_transition = matches!(
+            (expected_split_state, new_split_state),
+            (SplitState::Staged, SplitState::Published)
+                | (SplitState::Staged, SplitState::MarkedForDeletion)
+                | (SplitState::Published, SplitState::MarkedForDeletion)
+        ) || expected_split_state == new_split_state;
+
+        if !is_valid_transition {
+            let message = format!(
+                "invalid split state transition from `{expected_split_state}` to \
+                 `{new_split_state}`"
+            );
+            return Err(MetastoreError::InvalidArgument { message });
+        }
+        Ok((expected_split_state, new_split_state))
+    }
+}
+
 impl ListSplitsResponseExt for ListSplitsResponse {
     fn empty() -> Self {
         Self {
@@ -425,6 +562,10 @@ pub struct ListSplitsQuery {
     /// A non-empty list of index UIDs to get splits from.
     pub index_uids: Vec<IndexUid>,
 
+    /// A specific set of split IDs to look up. All splits are considered when empty.
+    #[serde(default)]
+    pub split_ids: Vec<SplitId>,
+
     /// The maximum number of splits to retrieve.
     pub limit: Option<usize>,
 
@@ -463,6 +604,7 @@ impl ListSplitsQuery {
     pub fn for_index(index_uid: IndexUid) -> Self {
         Self {
             index_uids: vec![index_uid],
+            split_ids: Vec::new(),
             limit: None,
             offset: None,
             split_states: Vec::new(),
@@ -487,6 +629,7 @@ impl ListSplitsQuery {
         }
         Ok(Self {
             index_uids,
+            split_ids: Vec::new(),
             limit: None,
             offset: None,
             split_states: Vec::new(),
@@ -512,6 +655,12 @@ impl ListSplitsQuery {
         self
     }
 
+    /// Select splits which have one of the given split IDs.
+    pub fn with_split_ids(mut self, split_ids: impl IntoIterator<Item = SplitId>) -> Self {
+        self.split_ids.extend(split_ids);
+        self
+    }
+
     /// Select splits which have the given split state.
     pub fn with_split_state(mut self, state: SplitState) -> Self {
         self.split_states.push(state);
@@ -31,19 +31,19 @@ use quickwit_config::{
 use quickwit_proto::ingest::{Shard, ShardState};
 use quickwit_proto::metastore::{
     serde_utils, AcquireShardsRequest, AcquireShardsResponse, AcquireShardsSubresponse,
-    AddSourceRequest, CreateIndexRequest, CreateIndexResponse, CreateIndexTemplateRequest,
-    DeleteIndexRequest, DeleteIndexTemplatesRequest, DeleteQuery, DeleteShardsRequest,
-    DeleteShardsResponse, DeleteSourceRequest, DeleteSplitsRequest, DeleteTask, EmptyResponse,
-    EntityKind, FindIndexTemplateMatchesRequest, FindIndexTemplateMatchesResponse,
-    GetIndexTemplateRequest, GetIndexTemplateResponse, IndexMetadataRequest, IndexMetadataResponse,
-    IndexTemplateMatch, LastDeleteOpstampRequest, LastDeleteOpstampResponse,
-    ListDeleteTasksRequest, ListDeleteTasksResponse, ListIndexTemplatesRequest,
-    ListIndexTemplatesResponse, ListIndexesMetadataRequest, ListIndexesMetadataResponse,
-    ListShardsRequest, ListShardsResponse, ListShardsSubresponse, ListSplitsRequest,
-    ListSplitsResponse, ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError,
-    MetastoreResult, MetastoreService, MetastoreServiceStream, OpenShardsRequest,
+    AddSourceRequest, CreateIndexMode, CreateIndexRequest, CreateIndexResponse,
+    CreateIndexTemplateRequest, DeleteIndexRequest, DeleteIndexTemplatesRequest, DeleteQuery,
+    DeleteShardsRequest, DeleteShardsResponse, DeleteSourceRequest, DeleteSplitsRequest,
+    DeleteTask, EmptyResponse, EntityKind, FindIndexTemplateMatchesRequest,
+    FindIndexTemplateMatchesResponse, GetIndexTemplateRequest, GetIndexTemplateResponse,
+    IndexMetadataRequest, IndexMetadataResponse, IndexTemplateMatch, LastDeleteOpstampRequest,
+    LastDeleteOpstampResponse, ListDeleteTasksRequest, ListDeleteTasksResponse,
+    ListIndexTemplatesRequest, ListIndexTemplatesResponse, ListIndexesMetadataRequest,
+    ListIndexesMetadataResponse, ListShardsRequest, ListShardsResponse, ListShardsSubresponse,
+    ListSplitsRequest, ListSplitsResponse, ListStaleSplitsRequest, MarkSplitsForDeletionRequest,
+    MetastoreError, MetastoreResult, MetastoreService, MetastoreServiceStream, OpenShardsRequest,
     OpenShardsResponse, OpenShardsSubrequest, OpenShardsSubresponse, PublishSplitsRequest,
-    ResetSourceCheckpointRequest, StageSplitsRequest, ToggleSourceRequest,
+    ResetSourceCheckpointRequest, StageSplitsRequest, ToggleSourceRequest, UpdateSplitStateRequest,
     UpdateSplitsDeleteOpstampRequest, UpdateSplitsDeleteOpstampResponse,
 };
 use quickwit_proto::types::{IndexId, IndexUid, Position, PublishToken, SourceId};
@@ -61,11 +61,14 @@ use crate::checkpoint::{
     IndexCheckpointDelta, PartitionId, SourceCheckpoint, SourceCheckpointDelta,
 };
 use crate::metastore::postgres::utils::split_maturity_timestamp;
-use crate::metastore::{PublishSplitsRequestExt, STREAM_SPLITS_CHUNK_SIZE};
+use crate::metastore::{
+    overwrite_index_metadata, validate_split_time_range, PublishSplitsRequestExt,
+    STREAM_SPLITS_CHUNK_SIZE,
+};
 use crate::{
     AddSourceRequestExt, CreateIndexRequestExt, IndexMetadata, IndexMetadataResponseExt,
     ListIndexesMetadataResponseExt, ListSplitsRequestExt, ListSplitsResponseExt,
-    MetastoreServiceExt, Split, SplitState, StageSplitsRequestExt,
+    MetastoreServiceExt, Split, SplitState, StageSplitsRequestExt, UpdateSplitStateRequestExt,
 };
 
 /// PostgreSQL metastore implementation.
@@ -260,6 +263,49 @@ async fn try_apply_delta_v2(
     Ok(())
 }
 
+/// Overwrites the metadata of an existing index, which keeps its index UID, marks its staged and
+/// published splits for deletion, and deletes its shards.
+async fn overwrite_index(
+    tx: &mut Transaction<'_, Postgres>,
+    index_metadata: &IndexMetadata,
+) -> MetastoreResult<()> {
+    let index_uid = index_metadata.index_uid.as_str();
+
+    sqlx::query(
+        r#"
+        UPDATE splits
+        SET
+            split_state = 'MarkedForDeletion',
+            update_timestamp = (CURRENT_TIMESTAMP AT TIME ZONE 'UTC')
+        WHERE
+            index_uid = $1
+            AND split_state IN ('Staged', 'Published')
+        "#,
+    )
+    .bind(index_uid)
+    .execute(tx.as_mut())
+    .await?;
+
+    sqlx::query("DELETE FROM shards WHERE index_uid = $1")
+        .bind(index_uid)
+        .execute(tx.as_mut())
+        .await?;
+
+    let index_metadata_json = serde_utils::to_json_str(index_metadata)?;
+    sqlx::query(
+        r#"
+        UPDATE indexes
+        SET index_metadata_json = $1
+        WHERE index_uid = $2
+        "#,
+    )
+    .bind(index_metadata_json)
+    .bind(index_uid)
+    .execute(tx.as_mut())
+    .await?;
+    Ok(())
+}
+
 /// This macro is used to systematically wrap the metastore
 /// into transaction, commit them on Result::Ok and rollback on Error.
 ///
@@ -376,17 +422,45 @@ impl MetastoreService for PostgresqlMetastore {
         for source_config in source_configs {
             index_metadata.add_source(source_config)?;
         }
-        let index_metadata_json = serde_utils::to_json_str(&index_metadata)?;
+        let create_index_mode = request.mode();
 
-        sqlx::query(
-            "INSERT INTO indexes (index_uid, index_id, index_metadata_json) VALUES ($1, $2, $3)",
-        )
-        .bind(index_metadata.index_uid.to_string())
-        .bind(index_metadata.index_uid.index_id())
-        .bind(&index_metadata_json)
-        .execute(&self.connection_pool)
-        .await
-        .map_err(|sqlx_error| convert_sqlx_err(index_metadata.index_id(), sqlx_error))?;
+        let index_metadata = run_with_tx!(self.connection_pool, tx, {
+            let existing_index_opt = if create_index_mode == CreateIndexMode::ErrorIfExists {
+                None
+            } else {
+                index_opt(tx.as_mut(), index_metadata.index_id()).await?
+            };
+            match existing_index_opt {
+                Some(existing_index) if create_index_mode == CreateIndexMode::IfNotExists => {
+                    existing_index.index_metadata()
+                }
+                Some(existing_index) => {
+                    let index_metadata = overwrite_index_metadata(
+                        &existing_index.index_metadata()?,
+                        index_metadata,
+                    )?;
+                    overwrite_index(tx, &index_metadata).await?;
+                    Ok(index_metadata)
+                }
+                None => {
+                    let index_metadata_json = serde_utils::to_json_str(&index_metadata)?;
+                    sqlx::query(
+                        "INSERT INTO indexes (index_uid, index_id, index_metadata_json) VALUES \
+                         ($1, $2, $3)",
+                    )
+                    .bind(index_metadata.index_uid.to_string())
+                    .bind(index_metadata.index_uid.index_id())
+                    .bind(&index_metadata_json)
+                    .execute(tx.as_mut())
+                    .await
+                    .map_err(|sqlx_error| {
+                        convert_sqlx_err(index_metadata.index_id(), sqlx_error)
+                    })?;
+                    Ok(index_metadata)
+                }
+            }
+        })?;
+        let index_metadata_json = serde_utils::to_json_str(&index_metadata)?;
 
         let response = CreateIndexResponse {
             index_uid: index_metadata.index_uid.into(),
@@ -434,6 +508,8 @@ impl MetastoreService for PostgresqlMetastore {
         let mut maturity_timestamps = Vec::with_capacity(split_metadata_list.len());
 
         for split_metadata in split_metadata_list {
+            validate_split_time_range(&split_metadata)?;
+
             let split_metadata_json = serde_json::to_string(&split_metadata).map_err(|error| {
                 MetastoreError::JsonSerializeError {
                     struct_name: "SplitMetadata".to_string(),
@@ -905,6 +981,80 @@ impl MetastoreService for PostgresqlMetastore {
         Ok(EmptyResponse {})
     }
 
+    #[instrument(skip(self))]
+    async fn update_split_state(
+        &mut self,
+        request: UpdateSplitStateRequest,
+    ) -> MetastoreResult<EmptyResponse> {
+        let (expected_split_state, new_split_state) = request.deserialize_split_states()?;
+        let index_uid: IndexUid = request.index_uid.into();
+        let split_id = request.split_id;
+        const UPDATE_SPLIT_STATE_QUERY: &str = r#"
+            -- Select the split to update, regardless of its state.
+            WITH input_split AS (
+                SELECT split_state
+                FROM splits
+                WHERE
+                    index_uid = $1
+                    AND split_id = $2
+                FOR UPDATE
+            ),
+            -- Update the split if and only if it is in the expected state.
+            updated_split AS (
+                UPDATE splits
+                SET
+                    split_state = $4,
+                    update_timestamp = (CURRENT_TIMESTAMP AT TIME ZONE 'UTC'),
+                    publish_timestamp = CASE
+                        WHEN $4 = 'Published' THEN (CURRENT_TIMESTAMP AT TIME ZONE 'UTC')
+                        ELSE publish_timestamp
+                    END
+                WHERE
+                    index_uid = $1
+                    AND split_id = $2
+                    AND split_state = $3
+                    AND split_state != $4
+            )
+            -- Report the state of the split prior to the update.
+            SELECT split_state FROM input_split
+        "#;
+        let split_state_opt: Option<(String,)> = sqlx::query_as(UPDATE_SPLIT_STATE_QUERY)
+            .bind(index_uid.as_str())
+            .bind(&split_id)
+            .bind(expected_split_state.as_str())
+            .bind(new_split_state.as_str())
+            .fetch_optional(&self.connection_pool)
+            .await
+            .map_err(|sqlx_error| convert_sqlx_err(index_uid.index_id(), sqlx_error))?;
+
+        let Some((split_state,)) = split_state_opt else {
+            if index_opt_for_uid(&self.connection_pool, index_uid.clone())
+                .await?
+                .is_none()
+            {
+                return Err(MetastoreError::NotFound(EntityKind::Index {
+                    index_id: index_uid.index_id().to_string(),
+                }));
+            }
+            return Err(MetastoreError::NotFound(EntityKind::Splits {
+                split_ids: vec![split_id],
+            }));
+        };
+        if split_state != expected_split_state.as_str() {
+            let message = format!("split is `{split_state}` instead of `{expected_split_state}`");
+            let entity = EntityKind::Splits {
+                split_ids: vec![split_id],
+            };
+            return Err(MetastoreError::FailedPrecondition { entity, message });
+        }
+        info!(
+            index_id=%index_uid.index_id(),
+            split_id=%split_id,
+            "Updated split state from `{expected_split_state}` to `{new_split_state}`."
+        );
+        Ok(EmptyResponse {})
+    }
+
     #[instrument(skip(self))]
     async fn index_metadata(
         &mut self,
@@ -1117,7 +1267,7 @@ impl MetastoreService for PostgresqlMetastore {
         Ok(UpdateSplitsDeleteOpstampResponse {})
     }
 
-    /// Lists the delete tasks with opstamp > `opstamp_start`.
+    /// Lists the delete tasks with opstamp > `opstamp_start`, ordered by ascending opstamp.
     #[instrument(skip(self))]
     async fn list_delete_tasks(
         &mut self,
@@ -1130,6 +1280,7 @@ impl MetastoreService for PostgresqlMetastore {
                 WHERE
                     index_uid = $1
                     AND opstamp > $2
+                ORDER BY opstamp ASC
                 "#,
         )
         .bind(index_uid.as_str())
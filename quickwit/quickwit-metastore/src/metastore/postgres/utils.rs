@@ -101,6 +101,10 @@ pub(super) fn append_query_filters(sql: &mut SelectStatement, query: &ListSplits
         });
     sql.cond_where(or_condition);
 
+    if !query.split_ids.is_empty() {
+        sql.cond_where(Expr::col(Splits::SplitId).is_in(query.split_ids.iter().cloned()));
+    };
+
     if !query.split_states.is_empty() {
         sql.cond_where(
             Expr::col(Splits::SplitState)
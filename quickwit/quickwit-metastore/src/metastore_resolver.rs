@@ -189,6 +189,27 @@ mod tests {
         metastore_resolver.resolve(&metastore_uri).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_metastore_resolver_should_not_raise_errors_on_ram() {
+        let metastore_resolver = MetastoreResolver::unconfigured();
+        let metastore_uri = Uri::for_test("ram:///metastore");
+        metastore_resolver.resolve(&metastore_uri).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_metastore_resolver_should_raise_errors_on_unsupported_protocol() {
+        let metastore_resolver = MetastoreResolver::unconfigured();
+        let metastore_uri = Uri::for_test("grpc://localhost:7281");
+        let error = metastore_resolver
+            .resolve(&metastore_uri)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            MetastoreResolverError::UnsupportedBackend(_)
+        ));
+    }
+
     #[cfg(feature = "postgres")]
     #[tokio::test]
     async fn test_postgres_and_postgresql_protocol_accepted() {
@@ -32,6 +32,12 @@ use time::OffsetDateTime;
 
 use crate::split_metadata_version::VersionedSplitMetadata;
 
+/// Version of the split format written by this build of Quickwit. It must be bumped whenever the
+/// split format changes in a way that older versions of Quickwit cannot read.
+///
+/// Splits created before the format was versioned have version 0.
+pub const SPLIT_FORMAT_VERSION: u32 = 1;
+
 /// Carries split metadata.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct Split {
@@ -132,6 +138,13 @@ pub struct SplitMetadata {
     /// Number of merge operations that was involved to create
     /// this split.
     pub num_merge_ops: usize,
+
+    /// Version of the format of the split file. See [`SPLIT_FORMAT_VERSION`].
+    pub format_version: u32,
+
+    /// Whether the split was built from backfilled documents. Backfill splits are published
+    /// without updating the checkpoint of their source.
+    pub backfill: bool,
 }
 
 impl fmt::Debug for SplitMetadata {
@@ -176,6 +189,8 @@ impl fmt::Debug for SplitMetadata {
         debug_struct.field("footer_offsets", &self.footer_offsets);
         debug_struct.field("delete_opstamp", &self.delete_opstamp);
         debug_struct.field("num_merge_ops", &self.num_merge_ops);
+        debug_struct.field("format_version", &self.format_version);
+        debug_struct.field("backfill", &self.backfill);
         debug_struct.finish()
     }
 }
@@ -196,6 +211,7 @@ impl SplitMetadata {
             source_id,
             node_id,
             create_timestamp: utc_now_timestamp(),
+            format_version: SPLIT_FORMAT_VERSION,
             ..Default::default()
         }
     }
@@ -205,6 +221,11 @@ impl SplitMetadata {
         &self.split_id
     }
 
+    /// Returns true if splits of this version can be read by this build of Quickwit.
+    pub fn is_format_supported(&self) -> bool {
+        self.format_version <= SPLIT_FORMAT_VERSION
+    }
+
     /// Returns true if the split is mature at the unix `timestamp`.
     pub fn is_mature(&self, datetime: OffsetDateTime) -> bool {
         match self.maturity {
@@ -281,6 +302,8 @@ impl quickwit_config::TestableForRegression for SplitMetadata {
             tags: ["234".to_string(), "aaa".to_string()].into_iter().collect(),
             footer_offsets: 1000..2000,
             num_merge_ops: 3,
+            format_version: SPLIT_FORMAT_VERSION,
+            backfill: false,
         }
     }
 
@@ -290,7 +313,7 @@ impl quickwit_config::TestableForRegression for SplitMetadata {
 }
 
 /// A split state.
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, utoipa::ToSchema)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, Hash, PartialEq, utoipa::ToSchema)]
 pub enum SplitState {
     /// The split is almost ready. Some of its files may have been uploaded in the storage.
     Staged,
@@ -423,17 +446,81 @@ mod tests {
             footer_offsets: 0..1024,
             delete_opstamp: 0,
             num_merge_ops: 0,
+            format_version: 1,
+            backfill: false,
         };
 
-        let expected_output = "SplitMetadata { split_id: \"split-1\", index_uid: \
-                               IndexUid(\"00000000-0000-0000-0000-000000000000:\
-                               00000000000000000000000000\"), partition_id: 0, source_id: \
-                               \"source-1\", node_id: \"node-1\", num_docs: 100, \
-                               uncompressed_docs_size_in_bytes: 1024, time_range: Some(0..=100), \
-                               create_timestamp: 1629867600, maturity: Mature, tags: \
-                               \"{\\\"🐱\\\", \\\"😻\\\", \\\"😼\\\", \\\"😿\\\", and 1 more}\", \
-                               footer_offsets: 0..1024, delete_opstamp: 0, num_merge_ops: 0 }";
+        let expected_output =
+            "SplitMetadata { split_id: \"split-1\", index_uid: \
+             IndexUid(\"00000000-0000-0000-0000-000000000000:00000000000000000000000000\"), \
+             partition_id: 0, source_id: \"source-1\", node_id: \"node-1\", num_docs: 100, \
+             uncompressed_docs_size_in_bytes: 1024, time_range: Some(0..=100), create_timestamp: \
+             1629867600, maturity: Mature, tags: \"{\\\"🐱\\\", \\\"😻\\\", \\\"😼\\\", \
+             \\\"😿\\\", and 1 more}\", footer_offsets: 0..1024, delete_opstamp: 0, \
+             num_merge_ops: 0, format_version: 1, backfill: false }";
 
         assert_eq!(format!("{:?}", split_metadata), expected_output);
     }
+
+    #[test]
+    fn test_split_metadata_serialization_round_trip() {
+        let split_metadata = SplitMetadata {
+            split_id: "split-1".to_string(),
+            index_uid: IndexUid::from_parts("my-index", ulid::Ulid::nil()),
+            partition_id: 7,
+            source_id: "source-1".to_string(),
+            node_id: "node-1".to_string(),
+            num_docs: 100,
+            uncompressed_docs_size_in_bytes: 1024,
+            time_range: Some(0..=100),
+            create_timestamp: 1629867600,
+            maturity: SplitMaturity::Immature {
+                maturation_period: Duration::from_secs(3600),
+            },
+            tags: BTreeSet::from_iter(["tag:foo".to_string(), "tag:bar".to_string()]),
+            footer_offsets: 0..1024,
+            delete_opstamp: 10,
+            num_merge_ops: 3,
+            format_version: 1,
+            backfill: true,
+        };
+        let serialized = serde_json::to_value(&split_metadata).unwrap();
+        assert_eq!(serialized["version"], "0.7");
+
+        let deserialized: SplitMetadata = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized, split_metadata);
+    }
+
+    #[test]
+    fn test_split_metadata_deserialization_defaults() {
+        // Splits persisted before optional fields were added deserialize with their defaults.
+        let split_metadata_json = r#"{
+            "version": "0.4",
+            "split_id": "split-1",
+            "index_id": "my-index",
+            "num_docs": 100,
+            "size_in_bytes": 1024,
+            "time_range": null,
+            "footer_offsets": {"start": 0, "end": 1024}
+        }"#;
+        let timestamp_before = utc_now_timestamp();
+        let split_metadata: SplitMetadata = serde_json::from_str(split_metadata_json).unwrap();
+        let timestamp_after = utc_now_timestamp();
+        assert_eq!(split_metadata.split_id, "split-1");
+        assert_eq!(split_metadata.index_uid.index_id(), "my-index");
+        assert_eq!(split_metadata.partition_id, 0);
+        assert_eq!(split_metadata.source_id, "unknown");
+        assert_eq!(split_metadata.node_id, "unknown");
+        assert_eq!(split_metadata.num_docs, 100);
+        assert_eq!(split_metadata.uncompressed_docs_size_in_bytes, 1024);
+        assert!(split_metadata.time_range.is_none());
+        assert!((timestamp_before..=timestamp_after).contains(&split_metadata.create_timestamp));
+        assert_eq!(split_metadata.maturity, SplitMaturity::Mature);
+        assert!(split_metadata.tags.is_empty());
+        assert_eq!(split_metadata.footer_offsets, 0..1024);
+        assert_eq!(split_metadata.delete_opstamp, 0);
+        assert_eq!(split_metadata.num_merge_ops, 0);
+        assert_eq!(split_metadata.format_version, 0);
+        assert!(!split_metadata.backfill);
+    }
 }
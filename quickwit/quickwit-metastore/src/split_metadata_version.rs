@@ -91,6 +91,12 @@ pub(crate) struct SplitMetadataV0_7 {
 
     #[serde(default)]
     num_merge_ops: usize,
+
+    #[serde(default)]
+    pub format_version: u32,
+
+    #[serde(default)]
+    pub backfill: bool,
 }
 
 impl From<SplitMetadataV0_7> for SplitMetadata {
@@ -126,6 +132,8 @@ impl From<SplitMetadataV0_7> for SplitMetadata {
             tags: v6.tags,
             footer_offsets: v6.footer_offsets,
             num_merge_ops: v6.num_merge_ops,
+            format_version: v6.format_version,
+            backfill: v6.backfill,
         }
     }
 }
@@ -147,6 +155,8 @@ impl From<SplitMetadata> for SplitMetadataV0_7 {
             tags: split.tags,
             footer_offsets: split.footer_offsets,
             num_merge_ops: split.num_merge_ops,
+            format_version: split.format_version,
+            backfill: split.backfill,
         }
     }
 }
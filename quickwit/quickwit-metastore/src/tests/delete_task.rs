@@ -171,6 +171,20 @@ pub async fn test_metastore_last_delete_opstamp<
         .last_delete_opstamp;
     assert_eq!(last_opstamp_index_1, delete_task_2.opstamp);
     assert_eq!(last_opstamp_index_2, delete_task_3.opstamp);
+
+    // The last delete opstamp follows the most recent delete task of the index.
+    let delete_task_4 = metastore
+        .create_delete_task(delete_query_index_1.clone())
+        .await
+        .unwrap();
+    let last_opstamp_index_1 = metastore
+        .last_delete_opstamp(LastDeleteOpstampRequest {
+            index_uid: index_uid_1.to_string(),
+        })
+        .await
+        .unwrap()
+        .last_delete_opstamp;
+    assert_eq!(last_opstamp_index_1, delete_task_4.opstamp);
     cleanup_index(&mut metastore, index_uid_1).await;
     cleanup_index(&mut metastore, index_uid_2).await;
 }
@@ -260,13 +274,28 @@ pub async fn test_metastore_list_delete_tasks<
         .create_delete_task(delete_query_index_2.clone())
         .await
         .unwrap();
+    let delete_task_3 = metastore
+        .create_delete_task(delete_query_index_1.clone())
+        .await
+        .unwrap();
 
     let all_index_id_1_delete_tasks = metastore
         .list_delete_tasks(ListDeleteTasksRequest::new(index_uid_1.clone(), 0))
         .await
         .unwrap()
         .delete_tasks;
-    assert_eq!(all_index_id_1_delete_tasks.len(), 2);
+    let all_index_id_1_opstamps: Vec<u64> = all_index_id_1_delete_tasks
+        .iter()
+        .map(|delete_task| delete_task.opstamp)
+        .collect();
+    assert_eq!(
+        all_index_id_1_opstamps,
+        [
+            delete_task_1.opstamp,
+            delete_task_2.opstamp,
+            delete_task_3.opstamp
+        ]
+    );
 
     let recent_index_id_1_delete_tasks = metastore
         .list_delete_tasks(ListDeleteTasksRequest::new(
@@ -276,11 +305,24 @@ pub async fn test_metastore_list_delete_tasks<
         .await
         .unwrap()
         .delete_tasks;
-    assert_eq!(recent_index_id_1_delete_tasks.len(), 1);
+    let recent_index_id_1_opstamps: Vec<u64> = recent_index_id_1_delete_tasks
+        .iter()
+        .map(|delete_task| delete_task.opstamp)
+        .collect();
     assert_eq!(
-        recent_index_id_1_delete_tasks[0].opstamp,
-        delete_task_2.opstamp
+        recent_index_id_1_opstamps,
+        [delete_task_2.opstamp, delete_task_3.opstamp]
     );
+
+    let no_index_id_1_delete_tasks = metastore
+        .list_delete_tasks(ListDeleteTasksRequest::new(
+            index_uid_1.clone(),
+            delete_task_3.opstamp,
+        ))
+        .await
+        .unwrap()
+        .delete_tasks;
+    assert!(no_index_id_1_delete_tasks.is_empty());
     cleanup_index(&mut metastore, index_uid_1).await;
     cleanup_index(&mut metastore, index_uid_2).await;
 }
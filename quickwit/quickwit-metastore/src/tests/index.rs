@@ -28,16 +28,19 @@
 use quickwit_common::rand::append_random_suffix;
 use quickwit_config::{IndexConfig, SourceConfig, CLI_SOURCE_ID, INGEST_V2_SOURCE_ID};
 use quickwit_proto::metastore::{
-    CreateIndexRequest, DeleteIndexRequest, EntityKind, IndexMetadataRequest,
-    ListIndexesMetadataRequest, MetastoreError, MetastoreService, StageSplitsRequest,
+    CreateIndexMode, CreateIndexRequest, DeleteIndexRequest, EntityKind, IndexMetadataRequest,
+    ListIndexesMetadataRequest, ListSplitsRequest, MetastoreError, MetastoreService,
+    PublishSplitsRequest, StageSplitsRequest,
 };
 use quickwit_proto::types::IndexUid;
 
 use super::DefaultForTest;
+use crate::metastore::MetastoreServiceStreamSplitsExt;
 use crate::tests::cleanup_index;
 use crate::{
-    CreateIndexRequestExt, IndexMetadataResponseExt, ListIndexesMetadataResponseExt,
-    MetastoreServiceExt, SplitMetadata, StageSplitsRequestExt,
+    CreateIndexRequestExt, CreateIndexResponseExt, IndexMetadataResponseExt,
+    ListIndexesMetadataResponseExt, ListSplitsRequestExt, MetastoreServiceExt, SplitMetadata,
+    SplitState, StageSplitsRequestExt,
 };
 
 pub async fn test_metastore_create_index<
@@ -95,6 +98,7 @@ pub async fn test_metastore_create_index_with_sources<
     let create_index_request = CreateIndexRequest {
         index_config_json,
         source_configs_json,
+        mode: CreateIndexMode::ErrorIfExists as i32,
     };
     let index_uid: IndexUid = metastore
         .create_index(create_index_request.clone())
@@ -122,6 +126,143 @@ pub async fn test_metastore_create_index_with_sources<
     cleanup_index(&mut metastore, index_uid).await;
 }
 
+pub async fn test_metastore_create_index_if_not_exists<
+    MetastoreToTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-create-index-if-not-exists");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let mut create_index_request =
+        CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    create_index_request.set_mode(CreateIndexMode::IfNotExists);
+
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request.clone())
+        .await
+        .unwrap()
+        .index_uid
+        .into();
+
+    assert!(metastore.index_exists(&index_id).await.unwrap());
+
+    // The existing index is returned untouched.
+    create_index_request.source_configs_json =
+        vec![serde_json::to_string(&SourceConfig::cli()).unwrap()];
+
+    let create_index_response = metastore.create_index(create_index_request).await.unwrap();
+    assert_eq!(create_index_response.index_uid, index_uid.as_str());
+
+    let index_metadata = create_index_response.deserialize_index_metadata().unwrap();
+    assert!(index_metadata.sources.is_empty());
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
+pub async fn test_metastore_create_index_overwrite<
+    MetastoreToTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-create-index-overwrite");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let mut create_index_request =
+        CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    create_index_request.source_configs_json =
+        vec![serde_json::to_string(&SourceConfig::cli()).unwrap()];
+
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request.clone())
+        .await
+        .unwrap()
+        .index_uid
+        .into();
+
+    let split_id_1 = format!("{index_id}--split-1");
+    let split_metadata_1 = SplitMetadata {
+        split_id: split_id_1.clone(),
+        index_uid: index_uid.clone(),
+        ..Default::default()
+    };
+    let split_id_2 = format!("{index_id}--split-2");
+    let split_metadata_2 = SplitMetadata {
+        split_id: split_id_2.clone(),
+        index_uid: index_uid.clone(),
+        ..Default::default()
+    };
+    let stage_splits_request = StageSplitsRequest::try_from_splits_metadata(
+        index_uid.clone(),
+        [split_metadata_1, split_metadata_2],
+    )
+    .unwrap();
+    metastore.stage_splits(stage_splits_request).await.unwrap();
+
+    let publish_splits_request = PublishSplitsRequest {
+        index_uid: index_uid.to_string(),
+        staged_split_ids: vec![split_id_1.clone()],
+        ..Default::default()
+    };
+    metastore
+        .publish_splits(publish_splits_request)
+        .await
+        .unwrap();
+
+    // Overwriting the index replaces its sources, keeps its UID, and marks its splits for
+    // deletion.
+    create_index_request.set_mode(CreateIndexMode::Overwrite);
+    create_index_request.source_configs_json =
+        vec![serde_json::to_string(&SourceConfig::ingest_v2()).unwrap()];
+
+    let create_index_response = metastore
+        .create_index(create_index_request.clone())
+        .await
+        .unwrap();
+    assert_eq!(create_index_response.index_uid, index_uid.as_str());
+
+    let index_metadata = metastore
+        .index_metadata(IndexMetadataRequest::for_index_id(index_id.to_string()))
+        .await
+        .unwrap()
+        .deserialize_index_metadata()
+        .unwrap();
+    assert_eq!(index_metadata.index_uid, index_uid);
+    assert_eq!(index_metadata.sources.len(), 1);
+    assert!(index_metadata.sources.contains_key(INGEST_V2_SOURCE_ID));
+
+    let splits = metastore
+        .list_splits(ListSplitsRequest::try_from_index_uid(index_uid.clone()).unwrap())
+        .await
+        .unwrap()
+        .collect_splits()
+        .await
+        .unwrap();
+    assert_eq!(splits.len(), 2);
+
+    for split in &splits {
+        assert_eq!(split.split_state, SplitState::MarkedForDeletion);
+    }
+
+    // The index URI of an overwritten index cannot change.
+    let other_index_uri = format!("ram:///indexes/{index_id}-other");
+    let other_index_config = IndexConfig::for_test(&index_id, &other_index_uri);
+
+    let mut create_index_request =
+        CreateIndexRequest::try_from_index_config(&other_index_config).unwrap();
+    create_index_request.set_mode(CreateIndexMode::Overwrite);
+
+    let error = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
 pub async fn test_metastore_create_index_enforces_index_id_maximum_length<
     MetastoreToTest: MetastoreService + MetastoreServiceExt + DefaultForTest,
 >() {
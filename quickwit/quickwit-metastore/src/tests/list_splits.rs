@@ -25,8 +25,8 @@ use quickwit_common::rand::append_random_suffix;
 use quickwit_config::IndexConfig;
 use quickwit_doc_mapper::tag_pruning::{no_tag, tag, TagFilterAst};
 use quickwit_proto::metastore::{
-    CreateIndexRequest, ListSplitsRequest, ListStaleSplitsRequest, MarkSplitsForDeletionRequest,
-    PublishSplitsRequest, StageSplitsRequest,
+    CreateIndexRequest, EntityKind, ListSplitsRequest, ListStaleSplitsRequest,
+    MarkSplitsForDeletionRequest, MetastoreError, PublishSplitsRequest, StageSplitsRequest,
 };
 use quickwit_proto::types::{IndexUid, SplitId};
 use time::OffsetDateTime;
@@ -166,6 +166,66 @@ pub async fn test_metastore_list_all_splits<
     cleanup_index(&mut metastore, index_uid.clone()).await;
 }
 
+pub async fn test_metastore_get_splits<MetastoreToTest: MetastoreServiceExt + DefaultForTest>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-get-splits");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid
+        .into();
+
+    let split_id_1 = format!("{index_id}--split-1");
+    let split_id_2 = format!("{index_id}--split-2");
+    let split_id_3 = format!("{index_id}--split-3");
+    let splits_metadata = [&split_id_1, &split_id_2, &split_id_3]
+        .into_iter()
+        .map(|split_id| SplitMetadata {
+            split_id: split_id.clone(),
+            index_uid: index_uid.clone(),
+            ..Default::default()
+        })
+        .collect_vec();
+    let stage_splits_request =
+        StageSplitsRequest::try_from_splits_metadata(index_uid.clone(), splits_metadata).unwrap();
+    metastore.stage_splits(stage_splits_request).await.unwrap();
+
+    let splits_metadata = metastore.get_splits(index_uid.clone(), &[]).await.unwrap();
+    assert!(splits_metadata.is_empty());
+
+    let splits_metadata = metastore
+        .get_splits(index_uid.clone(), &[split_id_1.clone(), split_id_3.clone()])
+        .await
+        .unwrap();
+    let split_ids = splits_metadata
+        .iter()
+        .map(|split_metadata| split_metadata.split_id())
+        .sorted()
+        .collect_vec();
+    assert_eq!(split_ids, [split_id_1.as_str(), split_id_3.as_str()]);
+
+    let missing_split_id = format!("{index_id}--split-missing");
+    let error = metastore
+        .get_splits(
+            index_uid.clone(),
+            &[split_id_2.clone(), missing_split_id.clone()],
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MetastoreError::NotFound(EntityKind::Splits { split_ids }) if split_ids == [missing_split_id]
+    ));
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
 pub async fn test_metastore_stream_splits<MetastoreToTest: MetastoreServiceExt + DefaultForTest>() {
     let mut metastore = MetastoreToTest::default_for_test().await;
 
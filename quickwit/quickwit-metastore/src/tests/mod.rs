@@ -192,6 +192,18 @@ macro_rules! metastore_test_suite {
                 $crate::tests::index::test_metastore_create_index_with_sources::<$metastore_type>().await;
             }
 
+            #[tokio::test]
+            async fn test_metastore_create_index_if_not_exists() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::index::test_metastore_create_index_if_not_exists::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            async fn test_metastore_create_index_overwrite() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::index::test_metastore_create_index_overwrite::<$metastore_type>().await;
+            }
+
             #[tokio::test]
             async fn test_metastore_create_index_enforces_index_id_maximum_length() {
                 let _ = tracing_subscriber::fmt::try_init();
@@ -238,6 +250,7 @@ macro_rules! metastore_test_suite {
             //  - stream_splits
             //  - mark_splits_for_deletion
             //  - delete_splits
+            //  - update_split_state
 
             #[tokio::test]
             async fn test_metastore_publish_splits() {
@@ -280,6 +293,21 @@ macro_rules! metastore_test_suite {
                 $crate::tests::split::test_metastore_delete_splits::<$metastore_type>().await;
             }
 
+            #[tokio::test]
+            async fn test_metastore_update_split_state() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::split::test_metastore_update_split_state::<$metastore_type>().await;
+            }
+
+            #[tokio::test]
+            async fn test_metastore_update_split_state_unexpected_state() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::split::test_metastore_update_split_state_unexpected_state::<
+                    $metastore_type,
+                >()
+                .await;
+            }
+
             #[tokio::test]
             async fn test_metastore_stream_splits() {
                 let _ = tracing_subscriber::fmt::try_init();
@@ -298,6 +326,12 @@ macro_rules! metastore_test_suite {
                 $crate::tests::list_splits::test_metastore_list_splits::<$metastore_type>().await;
             }
 
+            #[tokio::test]
+            async fn test_metastore_get_splits() {
+                let _ = tracing_subscriber::fmt::try_init();
+                $crate::tests::list_splits::test_metastore_get_splits::<$metastore_type>().await;
+            }
+
             #[tokio::test]
             async fn test_metastore_split_update_timestamp() {
                 let _ = tracing_subscriber::fmt::try_init();
@@ -25,7 +25,7 @@ use quickwit_config::IndexConfig;
 use quickwit_proto::metastore::{
     CreateIndexRequest, DeleteSplitsRequest, EntityKind, IndexMetadataRequest, ListSplitsRequest,
     ListStaleSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError, PublishSplitsRequest,
-    StageSplitsRequest, UpdateSplitsDeleteOpstampRequest,
+    StageSplitsRequest, UpdateSplitStateRequest, UpdateSplitsDeleteOpstampRequest,
 };
 use quickwit_proto::types::{IndexUid, Position};
 use time::OffsetDateTime;
@@ -39,6 +39,7 @@ use crate::tests::{cleanup_index, collect_split_ids};
 use crate::{
     CreateIndexRequestExt, IndexMetadataResponseExt, ListSplitsQuery, ListSplitsRequestExt,
     ListSplitsResponseExt, MetastoreServiceExt, SplitMetadata, SplitState, StageSplitsRequestExt,
+    UpdateSplitStateRequestExt,
 };
 
 pub async fn test_metastore_publish_splits_empty_splits_array_is_allowed<
@@ -1413,6 +1414,150 @@ pub async fn test_metastore_delete_splits<MetastoreToTest: MetastoreServiceExt +
     cleanup_index(&mut metastore, index_uid).await;
 }
 
+pub async fn test_metastore_update_split_state<
+    MetastoreToTest: MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-update-split-state");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid
+        .into();
+
+    // Check error if the split does not exist.
+    let update_split_state_request = UpdateSplitStateRequest::new(
+        index_uid.clone(),
+        "split-not-found",
+        SplitState::Staged,
+        SplitState::Published,
+    );
+    let error = metastore
+        .update_split_state(update_split_state_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MetastoreError::NotFound(EntityKind::Splits { .. })
+    ));
+
+    let split_id = format!("{index_id}--split");
+    let split_metadata = SplitMetadata {
+        split_id: split_id.clone(),
+        index_uid: index_uid.clone(),
+        ..Default::default()
+    };
+    let stage_splits_request =
+        StageSplitsRequest::try_from_split_metadata(index_uid.clone(), &split_metadata).unwrap();
+    metastore.stage_splits(stage_splits_request).await.unwrap();
+
+    let update_split_state_request = UpdateSplitStateRequest::new(
+        index_uid.clone(),
+        &split_id,
+        SplitState::Staged,
+        SplitState::Published,
+    );
+    metastore
+        .update_split_state(update_split_state_request)
+        .await
+        .unwrap();
+
+    let split = metastore
+        .list_splits(ListSplitsRequest::try_from_index_uid(index_uid.clone()).unwrap())
+        .await
+        .unwrap()
+        .collect_splits()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|split| split.split_id() == split_id)
+        .unwrap();
+    assert_eq!(split.split_state, SplitState::Published);
+    assert!(split.publish_timestamp.is_some());
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
+pub async fn test_metastore_update_split_state_unexpected_state<
+    MetastoreToTest: MetastoreServiceExt + DefaultForTest,
+>() {
+    let mut metastore = MetastoreToTest::default_for_test().await;
+
+    let index_id = append_random_suffix("test-update-split-state-unexpected-state");
+    let index_uri = format!("ram:///indexes/{index_id}");
+    let index_config = IndexConfig::for_test(&index_id, &index_uri);
+
+    let create_index_request = CreateIndexRequest::try_from_index_config(&index_config).unwrap();
+    let index_uid: IndexUid = metastore
+        .create_index(create_index_request)
+        .await
+        .unwrap()
+        .index_uid
+        .into();
+
+    let split_id = format!("{index_id}--split");
+    let split_metadata = SplitMetadata {
+        split_id: split_id.clone(),
+        index_uid: index_uid.clone(),
+        ..Default::default()
+    };
+    let stage_splits_request =
+        StageSplitsRequest::try_from_split_metadata(index_uid.clone(), &split_metadata).unwrap();
+    metastore.stage_splits(stage_splits_request).await.unwrap();
+
+    // The split is staged, not published.
+    let update_split_state_request = UpdateSplitStateRequest::new(
+        index_uid.clone(),
+        &split_id,
+        SplitState::Published,
+        SplitState::MarkedForDeletion,
+    );
+    let error = metastore
+        .update_split_state(update_split_state_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MetastoreError::FailedPrecondition {
+            entity: EntityKind::Splits { .. },
+            ..
+        }
+    ));
+
+    // Splits cannot move backward.
+    let update_split_state_request = UpdateSplitStateRequest::new(
+        index_uid.clone(),
+        &split_id,
+        SplitState::Published,
+        SplitState::Staged,
+    );
+    let error = metastore
+        .update_split_state(update_split_state_request)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    let split = metastore
+        .list_splits(ListSplitsRequest::try_from_index_uid(index_uid.clone()).unwrap())
+        .await
+        .unwrap()
+        .collect_splits()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|split| split.split_id() == split_id)
+        .unwrap();
+    assert_eq!(split.split_state, SplitState::Staged);
+
+    cleanup_index(&mut metastore, index_uid).await;
+}
+
 pub async fn test_metastore_split_update_timestamp<
     MetastoreToTest: MetastoreServiceExt + DefaultForTest,
 >() {
@@ -1639,6 +1784,62 @@ pub async fn test_metastore_stage_splits<MetastoreToTest: MetastoreServiceExt +
         }
     ),);
 
+    // Stage a split with an inverted time range
+    let split_id_3 = format!("{index_id}--split-3");
+    let split_metadata_3 = SplitMetadata {
+        split_id: split_id_3.clone(),
+        index_uid: index_uid.clone(),
+        create_timestamp: current_timestamp,
+        time_range: Some(100..=99),
+        ..Default::default()
+    };
+    let stage_splits_request =
+        StageSplitsRequest::try_from_splits_metadata(index_uid.clone(), [split_metadata_3])
+            .unwrap();
+    let error = metastore
+        .stage_splits(stage_splits_request)
+        .await
+        .expect_err("Metastore should not allow splits with an inverted time range.");
+    assert!(matches!(error, MetastoreError::InvalidArgument { .. }));
+
+    let query = ListSplitsQuery::for_index(index_uid.clone()).with_split_state(SplitState::Staged);
+    let splits = metastore
+        .list_splits(ListSplitsRequest::try_from_list_splits_query(&query).unwrap())
+        .await
+        .unwrap()
+        .collect_splits()
+        .await
+        .unwrap();
+    assert!(splits.is_empty());
+
+    // Stage a split whose time range is a single timestamp
+    let split_id_4 = format!("{index_id}--split-4");
+    let split_metadata_4 = SplitMetadata {
+        split_id: split_id_4.clone(),
+        index_uid: index_uid.clone(),
+        create_timestamp: current_timestamp,
+        time_range: Some(100..=100),
+        ..Default::default()
+    };
+    let stage_splits_request =
+        StageSplitsRequest::try_from_splits_metadata(index_uid.clone(), [split_metadata_4])
+            .unwrap();
+    metastore.stage_splits(stage_splits_request).await.unwrap();
+
+    let query = ListSplitsQuery::for_index(index_uid.clone())
+        .with_split_state(SplitState::Staged)
+        .with_time_range_start_gte(100)
+        .with_time_range_end_lt(101);
+    let splits = metastore
+        .list_splits(ListSplitsRequest::try_from_list_splits_query(&query).unwrap())
+        .await
+        .unwrap()
+        .collect_splits()
+        .await
+        .unwrap();
+    let split_ids = collect_split_ids(&splits);
+    assert_eq!(split_ids, &[&split_id_4]);
+
     cleanup_index(&mut metastore, index_uid).await;
 }
 
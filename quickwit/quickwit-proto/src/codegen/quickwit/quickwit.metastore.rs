@@ -10,6 +10,8 @@ pub struct CreateIndexRequest {
     pub index_config_json: ::prost::alloc::string::String,
     #[prost(string, repeated, tag = "3")]
     pub source_configs_json: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(enumeration = "CreateIndexMode", tag = "4")]
+    pub mode: i32,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -127,6 +129,21 @@ pub struct DeleteSplitsRequest {
     pub split_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateSplitStateRequest {
+    #[prost(string, tag = "1")]
+    pub index_uid: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub split_id: ::prost::alloc::string::String,
+    /// State the split must be in for the update to take place.
+    #[prost(string, tag = "3")]
+    pub expected_split_state: ::prost::alloc::string::String,
+    /// State of the split after the update.
+    #[prost(string, tag = "4")]
+    pub new_split_state: ::prost::alloc::string::String,
+}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AddSourceRequest {
@@ -520,6 +537,43 @@ impl SourceType {
         }
     }
 }
+/// Behavior of `CreateIndex` when an index with the same ID already exists.
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CreateIndexMode {
+    /// Fails with an `AlreadyExists` error.
+    ErrorIfExists = 0,
+    /// Leaves the existing index untouched and returns its metadata.
+    IfNotExists = 1,
+    /// Atomically replaces the config and the sources of the existing index, resets its checkpoint,
+    /// deletes its shards, and marks all its splits for deletion. The index keeps its UID so that the
+    /// garbage collector deletes the files of its splits.
+    Overwrite = 2,
+}
+impl CreateIndexMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            CreateIndexMode::ErrorIfExists => "CREATE_INDEX_MODE_ERROR_IF_EXISTS",
+            CreateIndexMode::IfNotExists => "CREATE_INDEX_MODE_IF_NOT_EXISTS",
+            CreateIndexMode::Overwrite => "CREATE_INDEX_MODE_OVERWRITE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "CREATE_INDEX_MODE_ERROR_IF_EXISTS" => Some(Self::ErrorIfExists),
+            "CREATE_INDEX_MODE_IF_NOT_EXISTS" => Some(Self::IfNotExists),
+            "CREATE_INDEX_MODE_OVERWRITE" => Some(Self::Overwrite),
+            _ => None,
+        }
+    }
+}
 /// BEGIN quickwit-codegen
 #[allow(unused_imports)]
 use std::str::FromStr;
@@ -572,6 +626,11 @@ impl PrometheusLabels<1> for DeleteSplitsRequest {
         OwnedPrometheusLabels::new([std::borrow::Cow::Borrowed("delete_splits")])
     }
 }
+impl PrometheusLabels<1> for UpdateSplitStateRequest {
+    fn labels(&self) -> OwnedPrometheusLabels<1usize> {
+        OwnedPrometheusLabels::new([std::borrow::Cow::Borrowed("update_split_state")])
+    }
+}
 impl PrometheusLabels<1> for AddSourceRequest {
     fn labels(&self) -> OwnedPrometheusLabels<1usize> {
         OwnedPrometheusLabels::new([std::borrow::Cow::Borrowed("add_source")])
@@ -679,7 +738,8 @@ pub trait MetastoreService: std::fmt::Debug + dyn_clone::DynClone + Send + Sync
     /// Creates an index.
     ///
     /// This API creates a new index in the metastore.
-    /// An error will occur if an index that already exists in the storage is specified.
+    /// An error will occur if an index that already exists in the storage is specified, unless the
+    /// `mode` of the request says otherwise.
     async fn create_index(
         &mut self,
         request: CreateIndexRequest,
@@ -724,6 +784,12 @@ pub trait MetastoreService: std::fmt::Debug + dyn_clone::DynClone + Send + Sync
         &mut self,
         request: DeleteSplitsRequest,
     ) -> crate::metastore::MetastoreResult<EmptyResponse>;
+    /// Updates the state of a split, provided it is in the expected state. Fails with a
+    /// `FailedPrecondition` error otherwise.
+    async fn update_split_state(
+        &mut self,
+        request: UpdateSplitStateRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse>;
     /// Adds source.
     async fn add_source(
         &mut self,
@@ -759,7 +825,8 @@ pub trait MetastoreService: std::fmt::Debug + dyn_clone::DynClone + Send + Sync
         &mut self,
         request: UpdateSplitsDeleteOpstampRequest,
     ) -> crate::metastore::MetastoreResult<UpdateSplitsDeleteOpstampResponse>;
-    /// Lists delete tasks with `delete_task.opstamp` > `opstamp_start` for a given `index_id`.
+    /// Lists delete tasks with `delete_task.opstamp` > `opstamp_start` for a given `index_id`,
+    /// ordered by ascending opstamp.
     async fn list_delete_tasks(
         &mut self,
         request: ListDeleteTasksRequest,
@@ -965,6 +1032,12 @@ impl MetastoreService for MetastoreServiceClient {
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.inner.delete_splits(request).await
     }
+    async fn update_split_state(
+        &mut self,
+        request: UpdateSplitStateRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner.update_split_state(request).await
+    }
     async fn add_source(
         &mut self,
         request: AddSourceRequest,
@@ -1145,6 +1218,12 @@ pub mod metastore_service_mock {
         ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
             self.inner.lock().await.delete_splits(request).await
         }
+        async fn update_split_state(
+            &mut self,
+            request: super::UpdateSplitStateRequest,
+        ) -> crate::metastore::MetastoreResult<super::EmptyResponse> {
+            self.inner.lock().await.update_split_state(request).await
+        }
         async fn add_source(
             &mut self,
             request: super::AddSourceRequest,
@@ -1418,6 +1497,22 @@ impl tower::Service<DeleteSplitsRequest> for Box<dyn MetastoreService> {
         Box::pin(fut)
     }
 }
+impl tower::Service<UpdateSplitStateRequest> for Box<dyn MetastoreService> {
+    type Response = EmptyResponse;
+    type Error = crate::metastore::MetastoreError;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, request: UpdateSplitStateRequest) -> Self::Future {
+        let mut svc = self.clone();
+        let fut = async move { svc.update_split_state(request).await };
+        Box::pin(fut)
+    }
+}
 impl tower::Service<AddSourceRequest> for Box<dyn MetastoreService> {
     type Response = EmptyResponse;
     type Error = crate::metastore::MetastoreError;
@@ -1755,6 +1850,11 @@ struct MetastoreServiceTowerServiceStack {
         EmptyResponse,
         crate::metastore::MetastoreError,
     >,
+    update_split_state_svc: quickwit_common::tower::BoxService<
+        UpdateSplitStateRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
     add_source_svc: quickwit_common::tower::BoxService<
         AddSourceRequest,
         EmptyResponse,
@@ -1859,6 +1959,7 @@ impl Clone for MetastoreServiceTowerServiceStack {
             publish_splits_svc: self.publish_splits_svc.clone(),
             mark_splits_for_deletion_svc: self.mark_splits_for_deletion_svc.clone(),
             delete_splits_svc: self.delete_splits_svc.clone(),
+            update_split_state_svc: self.update_split_state_svc.clone(),
             add_source_svc: self.add_source_svc.clone(),
             toggle_source_svc: self.toggle_source_svc.clone(),
             delete_source_svc: self.delete_source_svc.clone(),
@@ -1940,6 +2041,12 @@ impl MetastoreService for MetastoreServiceTowerServiceStack {
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.delete_splits_svc.ready().await?.call(request).await
     }
+    async fn update_split_state(
+        &mut self,
+        request: UpdateSplitStateRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.update_split_state_svc.ready().await?.call(request).await
+    }
     async fn add_source(
         &mut self,
         request: AddSourceRequest,
@@ -2145,6 +2252,16 @@ type DeleteSplitsLayer = quickwit_common::tower::BoxLayer<
     EmptyResponse,
     crate::metastore::MetastoreError,
 >;
+type UpdateSplitStateLayer = quickwit_common::tower::BoxLayer<
+    quickwit_common::tower::BoxService<
+        UpdateSplitStateRequest,
+        EmptyResponse,
+        crate::metastore::MetastoreError,
+    >,
+    UpdateSplitStateRequest,
+    EmptyResponse,
+    crate::metastore::MetastoreError,
+>;
 type AddSourceLayer = quickwit_common::tower::BoxLayer<
     quickwit_common::tower::BoxService<
         AddSourceRequest,
@@ -2336,6 +2453,7 @@ pub struct MetastoreServiceTowerLayerStack {
     publish_splits_layers: Vec<PublishSplitsLayer>,
     mark_splits_for_deletion_layers: Vec<MarkSplitsForDeletionLayer>,
     delete_splits_layers: Vec<DeleteSplitsLayer>,
+    update_split_state_layers: Vec<UpdateSplitStateLayer>,
     add_source_layers: Vec<AddSourceLayer>,
     toggle_source_layers: Vec<ToggleSourceLayer>,
     delete_source_layers: Vec<DeleteSourceLayer>,
@@ -2587,6 +2705,31 @@ impl MetastoreServiceTowerLayerStack {
                 crate::metastore::MetastoreError,
             >,
         >>::Service as tower::Service<DeleteSplitsRequest>>::Future: Send + 'static,
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    UpdateSplitStateRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Clone + Send + Sync + 'static,
+        <L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                UpdateSplitStateRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service: tower::Service<
+                UpdateSplitStateRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <<L as tower::Layer<
+            quickwit_common::tower::BoxService<
+                UpdateSplitStateRequest,
+                EmptyResponse,
+                crate::metastore::MetastoreError,
+            >,
+        >>::Service as tower::Service<UpdateSplitStateRequest>>::Future: Send + 'static,
         L: tower::Layer<
                 quickwit_common::tower::BoxService<
                     AddSourceRequest,
@@ -3068,6 +3211,8 @@ impl MetastoreServiceTowerLayerStack {
             .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self.delete_splits_layers
             .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
+        self.update_split_state_layers
+            .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self.add_source_layers
             .push(quickwit_common::tower::BoxLayer::new(layer.clone()));
         self.toggle_source_layers
@@ -3283,6 +3428,25 @@ impl MetastoreServiceTowerLayerStack {
         self.delete_splits_layers.push(quickwit_common::tower::BoxLayer::new(layer));
         self
     }
+    pub fn stack_update_split_state_layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<
+                quickwit_common::tower::BoxService<
+                    UpdateSplitStateRequest,
+                    EmptyResponse,
+                    crate::metastore::MetastoreError,
+                >,
+            > + Send + Sync + 'static,
+        L::Service: tower::Service<
+                UpdateSplitStateRequest,
+                Response = EmptyResponse,
+                Error = crate::metastore::MetastoreError,
+            > + Clone + Send + Sync + 'static,
+        <L::Service as tower::Service<UpdateSplitStateRequest>>::Future: Send + 'static,
+    {
+        self.update_split_state_layers.push(quickwit_common::tower::BoxLayer::new(layer));
+        self
+    }
     pub fn stack_add_source_layer<L>(mut self, layer: L) -> Self
     where
         L: tower::Layer<
@@ -3764,6 +3928,14 @@ impl MetastoreServiceTowerLayerStack {
                 quickwit_common::tower::BoxService::new(boxed_instance.clone()),
                 |svc, layer| layer.layer(svc),
             );
+        let update_split_state_svc = self
+            .update_split_state_layers
+            .into_iter()
+            .rev()
+            .fold(
+                quickwit_common::tower::BoxService::new(boxed_instance.clone()),
+                |svc, layer| layer.layer(svc),
+            );
         let add_source_svc = self
             .add_source_layers
             .into_iter()
@@ -3919,6 +4091,7 @@ impl MetastoreServiceTowerLayerStack {
             publish_splits_svc,
             mark_splits_for_deletion_svc,
             delete_splits_svc,
+            update_split_state_svc,
             add_source_svc,
             toggle_source_svc,
             delete_source_svc,
@@ -4073,6 +4246,12 @@ where
             Error = crate::metastore::MetastoreError,
             Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
         >
+        + tower::Service<
+            UpdateSplitStateRequest,
+            Response = EmptyResponse,
+            Error = crate::metastore::MetastoreError,
+            Future = BoxFuture<EmptyResponse, crate::metastore::MetastoreError>,
+        >
         + tower::Service<
             AddSourceRequest,
             Response = EmptyResponse,
@@ -4251,6 +4430,12 @@ where
     ) -> crate::metastore::MetastoreResult<EmptyResponse> {
         self.call(request).await
     }
+    async fn update_split_state(
+        &mut self,
+        request: UpdateSplitStateRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.call(request).await
+    }
     async fn add_source(
         &mut self,
         request: AddSourceRequest,
@@ -4500,6 +4685,16 @@ where
             .map(|response| response.into_inner())
             .map_err(|error| error.into())
     }
+    async fn update_split_state(
+        &mut self,
+        request: UpdateSplitStateRequest,
+    ) -> crate::metastore::MetastoreResult<EmptyResponse> {
+        self.inner
+            .update_split_state(request)
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|error| error.into())
+    }
     async fn add_source(
         &mut self,
         request: AddSourceRequest,
@@ -4813,6 +5008,17 @@ for MetastoreServiceGrpcServerAdapter {
             .map(tonic::Response::new)
             .map_err(|error| error.into())
     }
+    async fn update_split_state(
+        &self,
+        request: tonic::Request<UpdateSplitStateRequest>,
+    ) -> Result<tonic::Response<EmptyResponse>, tonic::Status> {
+        self.inner
+            .clone()
+            .update_split_state(request.into_inner())
+            .await
+            .map(tonic::Response::new)
+            .map_err(|error| error.into())
+    }
     async fn add_source(
         &self,
         request: tonic::Request<AddSourceRequest>,
@@ -5146,7 +5352,8 @@ pub mod metastore_service_grpc_client {
         /// Creates an index.
         ///
         /// This API creates a new index in the metastore.
-        /// An error will occur if an index that already exists in the storage is specified.
+        /// An error will occur if an index that already exists in the storage is specified, unless the
+        /// `mode` of the request says otherwise.
         pub async fn create_index(
             &mut self,
             request: impl tonic::IntoRequest<super::CreateIndexRequest>,
@@ -5398,6 +5605,35 @@ pub mod metastore_service_grpc_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        /// Updates the state of a split, provided it is in the expected state. Fails with a
+        /// `FailedPrecondition` error otherwise.
+        pub async fn update_split_state(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateSplitStateRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/quickwit.metastore.MetastoreService/UpdateSplitState",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "quickwit.metastore.MetastoreService",
+                        "UpdateSplitState",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
         /// Adds source.
         pub async fn add_source(
             &mut self,
@@ -5597,7 +5833,8 @@ pub mod metastore_service_grpc_client {
                 );
             self.inner.unary(req, path, codec).await
         }
-        /// Lists delete tasks with `delete_task.opstamp` > `opstamp_start` for a given `index_id`.
+        /// Lists delete tasks with `delete_task.opstamp` > `opstamp_start` for a given `index_id`,
+        /// ordered by ascending opstamp.
         pub async fn list_delete_tasks(
             &mut self,
             request: impl tonic::IntoRequest<super::ListDeleteTasksRequest>,
@@ -5945,7 +6182,8 @@ pub mod metastore_service_grpc_server {
         /// Creates an index.
         ///
         /// This API creates a new index in the metastore.
-        /// An error will occur if an index that already exists in the storage is specified.
+        /// An error will occur if an index that already exists in the storage is specified, unless the
+        /// `mode` of the request says otherwise.
         async fn create_index(
             &self,
             request: tonic::Request<super::CreateIndexRequest>,
@@ -6005,6 +6243,12 @@ pub mod metastore_service_grpc_server {
             &self,
             request: tonic::Request<super::DeleteSplitsRequest>,
         ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
+        /// Updates the state of a split, provided it is in the expected state. Fails with a
+        /// `FailedPrecondition` error otherwise.
+        async fn update_split_state(
+            &self,
+            request: tonic::Request<super::UpdateSplitStateRequest>,
+        ) -> std::result::Result<tonic::Response<super::EmptyResponse>, tonic::Status>;
         /// Adds source.
         async fn add_source(
             &self,
@@ -6046,7 +6290,8 @@ pub mod metastore_service_grpc_server {
             tonic::Response<super::UpdateSplitsDeleteOpstampResponse>,
             tonic::Status,
         >;
-        /// Lists delete tasks with `delete_task.opstamp` > `opstamp_start` for a given `index_id`.
+        /// Lists delete tasks with `delete_task.opstamp` > `opstamp_start` for a given `index_id`,
+        /// ordered by ascending opstamp.
         async fn list_delete_tasks(
             &self,
             request: tonic::Request<super::ListDeleteTasksRequest>,
@@ -6675,6 +6920,52 @@ pub mod metastore_service_grpc_server {
                     };
                     Box::pin(fut)
                 }
+                "/quickwit.metastore.MetastoreService/UpdateSplitState" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateSplitStateSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
+                    impl<
+                        T: MetastoreServiceGrpc,
+                    > tonic::server::UnaryService<super::UpdateSplitStateRequest>
+                    for UpdateSplitStateSvc<T> {
+                        type Response = super::EmptyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateSplitStateRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).update_split_state(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateSplitStateSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/quickwit.metastore.MetastoreService/AddSource" => {
                     #[allow(non_camel_case_types)]
                     struct AddSourceSvc<T: MetastoreServiceGrpc>(pub Arc<T>);
@@ -55,6 +55,9 @@ pub struct ReportSplit {
 pub struct ReportSplitsRequest {
     #[prost(message, repeated, tag = "1")]
     pub report_splits: ::prost::alloc::vec::Vec<ReportSplit>,
+    /// IDs of the splits that were deleted and must be evicted from the split cache.
+    #[prost(string, repeated, tag = "2")]
+    pub deleted_split_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::derive_partial_eq_without_eq)]
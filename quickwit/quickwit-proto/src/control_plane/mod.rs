@@ -83,6 +83,9 @@ impl From<AskError<ControlPlaneError>> for ControlPlaneError {
             AskError::MessageNotDelivered => {
                 ControlPlaneError::Unavailable("request not delivered".to_string())
             }
+            AskError::QueueFull => {
+                ControlPlaneError::Unavailable("request queue is full".to_string())
+            }
             AskError::ProcessMessageError => ControlPlaneError::Internal(
                 "an error occurred while processing the request".to_string(),
             ),
@@ -32,6 +32,7 @@ pub enum ServiceErrorCode {
     NotFound,
     // Used for APIs that are available in Elasticsearch but not available yet in Quickwit.
     NotSupportedYet,
+    PayloadTooLarge,
     RateLimited,
     Timeout,
     Unavailable,
@@ -47,6 +48,7 @@ impl ServiceErrorCode {
             ServiceErrorCode::MethodNotAllowed => tonic::Code::InvalidArgument,
             ServiceErrorCode::NotFound => tonic::Code::NotFound,
             ServiceErrorCode::NotSupportedYet => tonic::Code::Unimplemented,
+            ServiceErrorCode::PayloadTooLarge => tonic::Code::ResourceExhausted,
             ServiceErrorCode::RateLimited => tonic::Code::ResourceExhausted,
             ServiceErrorCode::Timeout => tonic::Code::DeadlineExceeded,
             ServiceErrorCode::Unavailable => tonic::Code::Unavailable,
@@ -61,6 +63,7 @@ impl ServiceErrorCode {
             ServiceErrorCode::MethodNotAllowed => http::StatusCode::METHOD_NOT_ALLOWED,
             ServiceErrorCode::NotFound => http::StatusCode::NOT_FOUND,
             ServiceErrorCode::NotSupportedYet => http::StatusCode::NOT_IMPLEMENTED,
+            ServiceErrorCode::PayloadTooLarge => http::StatusCode::PAYLOAD_TOO_LARGE,
             ServiceErrorCode::RateLimited => http::StatusCode::TOO_MANY_REQUESTS,
             ServiceErrorCode::Unavailable => http::StatusCode::SERVICE_UNAVAILABLE,
             ServiceErrorCode::UnsupportedMediaType => http::StatusCode::UNSUPPORTED_MEDIA_TYPE,
@@ -142,6 +142,7 @@ impl From<AskError<IndexingError>> for IndexingError {
         match error {
             AskError::ErrorReply(error) => error,
             AskError::MessageNotDelivered => IndexingError::Unavailable,
+            AskError::QueueFull => IndexingError::Unavailable,
             AskError::ProcessMessageError => IndexingError::Internal(
                 "an error occurred while processing the request".to_string(),
             ),
@@ -224,6 +224,7 @@ impl<E: fmt::Debug + ServiceError> ServiceError for quickwit_actors::AskError<E>
     fn error_code(&self) -> ServiceErrorCode {
         match self {
             quickwit_actors::AskError::MessageNotDelivered => ServiceErrorCode::Internal,
+            quickwit_actors::AskError::QueueFull => ServiceErrorCode::Unavailable,
             quickwit_actors::AskError::ProcessMessageError => ServiceErrorCode::Internal,
             quickwit_actors::AskError::ErrorReply(err) => err.error_code(),
         }
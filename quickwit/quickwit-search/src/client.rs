@@ -288,7 +288,8 @@ impl SearchServiceClient {
     }
 
     /// Indexers call report_splits to inform searchers node about the presence of a split, which
-    /// would then be considered as a candidate for the searcher split cache.
+    /// would then be considered as a candidate for the searcher split cache. The janitor calls it
+    /// to inform them of deleted splits, which are evicted from the cache.
     pub async fn report_splits(&mut self, report_splits_request: ReportSplitsRequest) {
         match &mut self.client_impl {
             SearchServiceClientImpl::Local(service) => {
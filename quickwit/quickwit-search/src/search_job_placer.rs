@@ -85,9 +85,19 @@ impl EventSubscriber<ReportSplitsRequest> for SearchJobPlacer {
                 .or_default()
                 .push(report_split);
         }
+        // Deleted splits may have been cached by any searcher, for instance before the set of
+        // searchers changed, so their IDs are sent to all of them.
+        if !evt.deleted_split_ids.is_empty() {
+            for node_addr in nodes.keys() {
+                splits_per_node.entry(*node_addr).or_default();
+            }
+        }
         for (node_addr, report_splits) in splits_per_node {
             if let Some(search_client) = nodes.get_mut(&node_addr) {
-                let report_splits_req = ReportSplitsRequest { report_splits };
+                let report_splits_req = ReportSplitsRequest {
+                    report_splits,
+                    deleted_split_ids: evt.deleted_split_ids.clone(),
+                };
                 let _ = search_client.report_splits(report_splits_req).await;
             }
         }
@@ -236,6 +246,8 @@ impl Eq for CandidateNodes {}
 
 #[cfg(test)]
 mod tests {
+    use quickwit_proto::search::ReportSplitsResponse;
+
     use super::*;
     use crate::{searcher_pool_for_test, MockSearchService, SearchJob};
 
@@ -322,4 +334,31 @@ mod tests {
             assert_eq!(assigned_jobs, expected_assigned_jobs);
         }
     }
+
+    #[tokio::test]
+    async fn test_search_job_placer_broadcasts_deleted_splits() {
+        let mock_search_service = || {
+            let mut mock_search_service = MockSearchService::new();
+            mock_search_service
+                .expect_report_splits()
+                .withf(|report_splits_request| {
+                    report_splits_request.report_splits.is_empty()
+                        && report_splits_request.deleted_split_ids == ["split-1", "split-2"]
+                })
+                .times(1)
+                .returning(|_| ReportSplitsResponse {});
+            mock_search_service
+        };
+        let searcher_pool = searcher_pool_for_test([
+            ("127.0.0.1:1001", mock_search_service()),
+            ("127.0.0.1:1002", mock_search_service()),
+        ]);
+        let mut search_job_placer = SearchJobPlacer::new(searcher_pool);
+        search_job_placer
+            .handle_event(ReportSplitsRequest {
+                report_splits: Vec::new(),
+                deleted_split_ids: vec!["split-1".to_string(), "split-2".to_string()],
+            })
+            .await;
+    }
 }
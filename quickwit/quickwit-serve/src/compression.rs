@@ -0,0 +1,230 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use hyper::header::ACCEPT_ENCODING;
+use tracing::warn;
+use warp::{Filter, Rejection};
+
+use crate::decompression::CompressionAlgorithm;
+
+/// Parses the server-side preference order of the response encodings, for instance
+/// `[zstd, gzip, br]`, so that operators can favor encodings that are cheaper to compute. It is
+/// used to break ties between the encodings a client accepts with the same q-value. Unknown and
+/// duplicate encodings are ignored.
+pub(crate) fn parse_compression_preference(preference: &[String]) -> Vec<CompressionAlgorithm> {
+    let mut algorithms = Vec::new();
+    for coding in preference {
+        let coding = coding.trim();
+        match CompressionAlgorithm::from_content_encoding(coding) {
+            Some(algorithm) if !algorithms.contains(&algorithm) => algorithms.push(algorithm),
+            Some(_) => {}
+            None => warn!(
+                content_encoding = coding,
+                "ignoring unknown encoding in response compression preference"
+            ),
+        }
+    }
+    algorithms
+}
+
+/// Extracts the `accept-encoding` header of a request and negotiates the encoding of the
+/// response body with the server preference order `preference`. See [`negotiate_encoding`].
+pub(crate) fn accept_encoding(
+    preference: Vec<CompressionAlgorithm>,
+) -> impl Filter<Extract = (Option<CompressionAlgorithm>,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(ACCEPT_ENCODING.as_str()).map(
+        move |accept_encoding_opt: Option<String>| {
+            negotiate_encoding(accept_encoding_opt.as_deref(), &preference)
+        },
+    )
+}
+
+/// Picks the algorithm used to compress a response body given the `accept-encoding` header of
+/// the request. Returns `None` when the body should be sent as is (`identity`).
+///
+/// The enabled algorithm with the highest q-value wins. When q-values tie, the algorithm ranked
+/// first in the server preference order `preference` is chosen, then the algorithm listed first
+/// by the client. Algorithms missing from `preference` rank after the ones it lists. The wildcard
+/// `*` stands for `gzip`, which is always enabled.
+pub(crate) fn negotiate_encoding(
+    accept_encoding_opt: Option<&str>,
+    preference: &[CompressionAlgorithm],
+) -> Option<CompressionAlgorithm> {
+    let accept_encoding = accept_encoding_opt?;
+    let preference_rank = |algorithm: CompressionAlgorithm| {
+        preference
+            .iter()
+            .position(|preferred_algorithm| *preferred_algorithm == algorithm)
+            .unwrap_or(preference.len())
+    };
+    let mut best_algorithm_opt: Option<(CompressionAlgorithm, f32)> = None;
+    let mut identity_qvalue = 0.0;
+
+    for (coding, qvalue) in accept_encoding.split(',').filter_map(parse_coding) {
+        if qvalue <= 0.0 {
+            continue;
+        }
+        let algorithm = match coding {
+            "identity" => {
+                identity_qvalue = qvalue;
+                continue;
+            }
+            "*" => CompressionAlgorithm::Gzip,
+            _ => match CompressionAlgorithm::from_content_encoding(coding) {
+                Some(algorithm) if algorithm.is_enabled() => algorithm,
+                _ => continue,
+            },
+        };
+        let is_better = best_algorithm_opt.map_or(true, |(best_algorithm, best_qvalue)| {
+            qvalue > best_qvalue
+                || (qvalue == best_qvalue
+                    && preference_rank(algorithm) < preference_rank(best_algorithm))
+        });
+        if is_better {
+            best_algorithm_opt = Some((algorithm, qvalue));
+        }
+    }
+    best_algorithm_opt
+        .filter(|(_, qvalue)| *qvalue >= identity_qvalue)
+        .map(|(algorithm, _)| algorithm)
+}
+
+/// Parses a `coding[;q=qvalue]` item of an `accept-encoding` header. A missing or malformed
+/// q-value defaults to 1.
+fn parse_coding(item: &str) -> Option<(&str, f32)> {
+    let mut parts = item.split(';');
+    let coding = parts.next()?.trim();
+
+    if coding.is_empty() {
+        return None;
+    }
+    let qvalue = parts
+        .filter_map(|param| param.trim().strip_prefix("q="))
+        .find_map(|qvalue| qvalue.trim().parse::<f32>().ok())
+        .unwrap_or(1.0);
+    Some((coding, qvalue))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_encoding() {
+        assert_eq!(negotiate_encoding(None, &[]), None);
+        assert_eq!(negotiate_encoding(Some(""), &[]), None);
+        assert_eq!(negotiate_encoding(Some("identity"), &[]), None);
+        assert_eq!(negotiate_encoding(Some("compress"), &[]), None);
+        assert_eq!(
+            negotiate_encoding(Some("gzip"), &[]),
+            Some(CompressionAlgorithm::Gzip)
+        );
+        assert_eq!(
+            negotiate_encoding(Some("X-GZIP"), &[]),
+            Some(CompressionAlgorithm::Gzip)
+        );
+        assert_eq!(
+            negotiate_encoding(Some("deflate, gzip"), &[]),
+            Some(CompressionAlgorithm::Deflate)
+        );
+        assert_eq!(
+            negotiate_encoding(Some("deflate;q=0.5, gzip;q=0.8"), &[]),
+            Some(CompressionAlgorithm::Gzip)
+        );
+        assert_eq!(negotiate_encoding(Some("gzip;q=0"), &[]), None);
+        assert_eq!(negotiate_encoding(Some("gzip;q=0.5, identity"), &[]), None);
+        assert_eq!(
+            negotiate_encoding(Some("*"), &[]),
+            Some(CompressionAlgorithm::Gzip)
+        );
+    }
+
+    #[test]
+    fn test_negotiate_encoding_with_preference() {
+        let preference = [CompressionAlgorithm::Deflate, CompressionAlgorithm::Gzip];
+        assert_eq!(
+            negotiate_encoding(Some("gzip, deflate"), &preference),
+            Some(CompressionAlgorithm::Deflate)
+        );
+        assert_eq!(
+            negotiate_encoding(Some("gzip;q=0.8, deflate;q=0.8"), &preference),
+            Some(CompressionAlgorithm::Deflate)
+        );
+        // The preference only breaks ties between q-values.
+        assert_eq!(
+            negotiate_encoding(Some("gzip, deflate;q=0.5"), &preference),
+            Some(CompressionAlgorithm::Gzip)
+        );
+        // Algorithms missing from the preference rank last, in the order of the client.
+        assert_eq!(
+            negotiate_encoding(Some("gzip, deflate"), &[]),
+            Some(CompressionAlgorithm::Gzip)
+        );
+        assert_eq!(
+            negotiate_encoding(Some("gzip, deflate"), &[CompressionAlgorithm::Gzip]),
+            Some(CompressionAlgorithm::Gzip)
+        );
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_negotiate_encoding_with_preference_zstd() {
+        let preference = [
+            CompressionAlgorithm::Zstd,
+            CompressionAlgorithm::Gzip,
+            CompressionAlgorithm::Brotli,
+        ];
+        assert_eq!(
+            negotiate_encoding(Some("gzip;q=0.9, zstd;q=0.9"), &preference),
+            Some(CompressionAlgorithm::Zstd)
+        );
+    }
+
+    #[test]
+    fn test_parse_compression_preference() {
+        assert!(parse_compression_preference(&[]).is_empty());
+        let preference = ["zstd", " gzip", "compress", "br", "gzip"].map(String::from);
+        assert_eq!(
+            parse_compression_preference(&preference),
+            [
+                CompressionAlgorithm::Zstd,
+                CompressionAlgorithm::Gzip,
+                CompressionAlgorithm::Brotli
+            ]
+        );
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_negotiate_encoding_zstd() {
+        assert_eq!(
+            negotiate_encoding(Some("gzip;q=0.5, zstd"), &[]),
+            Some(CompressionAlgorithm::Zstd)
+        );
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[test]
+    fn test_negotiate_encoding_ignores_disabled_algorithms() {
+        assert_eq!(
+            negotiate_encoding(Some("zstd, gzip;q=0.5"), &[]),
+            Some(CompressionAlgorithm::Gzip)
+        );
+    }
+}
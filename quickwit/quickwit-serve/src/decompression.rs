@@ -0,0 +1,2130 @@
+// Copyright (C) 2024 Quickwit, Inc.
+//
+// Quickwit is offered under the AGPL v3.0 and as commercial software.
+// For commercial licensing, contact us at hello@quickwit.io.
+//
+// AGPL:
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+#[cfg(feature = "zstd")]
+use std::io::BufReader;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::{Arc, RwLock};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use flate2::read::{MultiGzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use flate2::Compression;
+use futures::{Stream, StreamExt};
+use hyper::header::{HeaderMap, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE};
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use quickwit_config::IngestApiConfig;
+use thiserror::Error;
+use tracing::{debug, error, info, warn};
+use warp::reject::Reject;
+use warp::{Filter, Rejection};
+
+use crate::rest::InvalidArgument;
+
+/// Compression algorithms known to Quickwit for request and response bodies. Some of them are
+/// behind cargo features and may not be enabled in a given build, see
+/// [`CompressionAlgorithm::is_enabled`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum CompressionAlgorithm {
+    Brotli,
+    Deflate,
+    /// Sequence of zstd blocks, each prefixed with its length as a 4-byte little-endian integer.
+    FramedZstd,
+    Gzip,
+    Zstd,
+}
+
+impl CompressionAlgorithm {
+    pub const ALL: [CompressionAlgorithm; 5] = [
+        CompressionAlgorithm::Brotli,
+        CompressionAlgorithm::Deflate,
+        CompressionAlgorithm::FramedZstd,
+        CompressionAlgorithm::Gzip,
+        CompressionAlgorithm::Zstd,
+    ];
+
+    /// Parses a `content-encoding` token. Returns `None` if the algorithm is unknown.
+    pub fn from_content_encoding(content_encoding: &str) -> Option<Self> {
+        match content_encoding.trim().to_ascii_lowercase().as_str() {
+            "br" => Some(CompressionAlgorithm::Brotli),
+            "deflate" => Some(CompressionAlgorithm::Deflate),
+            "gzip" | "x-gzip" => Some(CompressionAlgorithm::Gzip),
+            "qw-framed-zstd" => Some(CompressionAlgorithm::FramedZstd),
+            "zstd" => Some(CompressionAlgorithm::Zstd),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompressionAlgorithm::Brotli => "br",
+            CompressionAlgorithm::Deflate => "deflate",
+            CompressionAlgorithm::FramedZstd => "qw-framed-zstd",
+            CompressionAlgorithm::Gzip => "gzip",
+            CompressionAlgorithm::Zstd => "zstd",
+        }
+    }
+
+    /// Returns whether the algorithm is compiled into this build.
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            CompressionAlgorithm::Brotli => cfg!(feature = "brotli"),
+            CompressionAlgorithm::Deflate | CompressionAlgorithm::Gzip => true,
+            CompressionAlgorithm::FramedZstd | CompressionAlgorithm::Zstd => cfg!(feature = "zstd"),
+        }
+    }
+
+    /// Returns a reader over the decompressed content of the compressed bytes read from `reader`.
+    /// `brotli_dictionary_opt` is the custom dictionary the body was compressed with, if any. It is
+    /// ignored by other algorithms.
+    #[cfg_attr(not(feature = "brotli"), allow(unused_variables))]
+    fn decoder<'a>(
+        &self,
+        reader: impl Read + 'a,
+        brotli_dictionary_opt: Option<&[u8]>,
+    ) -> std::io::Result<Box<dyn Read + 'a>> {
+        match self {
+            #[cfg(feature = "brotli")]
+            CompressionAlgorithm::Brotli => match brotli_dictionary_opt {
+                Some(brotli_dictionary) => {
+                    Ok(Box::new(brotli::Decompressor::new_with_custom_dict(
+                        reader,
+                        4096,
+                        brotli_dictionary.to_vec().into(),
+                    )))
+                }
+                None => Ok(Box::new(brotli::Decompressor::new(reader, 4096))),
+            },
+            CompressionAlgorithm::Deflate => Ok(Box::new(ZlibDecoder::new(reader))),
+            #[cfg(feature = "zstd")]
+            CompressionAlgorithm::FramedZstd => Ok(Box::new(FramedZstdDecoder::new(reader))),
+            CompressionAlgorithm::Gzip => Ok(Box::new(MultiGzDecoder::new(reader))),
+            #[cfg(feature = "zstd")]
+            CompressionAlgorithm::Zstd => Ok(Box::new(zstd::stream::read::Decoder::new(reader)?)),
+            #[allow(unreachable_patterns)]
+            disabled_algorithm => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!("`{disabled_algorithm}` is not enabled in this build"),
+            )),
+        }
+    }
+
+    pub fn compress(&self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "brotli")]
+            CompressionAlgorithm::Brotli => {
+                let mut compressed_body = Vec::new();
+                {
+                    let mut encoder =
+                        brotli::CompressorWriter::new(&mut compressed_body, 4096, 5, 22);
+                    encoder.write_all(body)?;
+                }
+                Ok(compressed_body)
+            }
+            CompressionAlgorithm::Deflate => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+            #[cfg(feature = "zstd")]
+            CompressionAlgorithm::FramedZstd => {
+                let block = zstd::encode_all(body, 0)?;
+                let block_len = u32::try_from(block.len()).map_err(|_| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        "zstd block is too large to be framed",
+                    )
+                })?;
+                let mut framed_body = Vec::with_capacity(FRAME_LENGTH_PREFIX_SIZE + block.len());
+                framed_body.extend_from_slice(&block_len.to_le_bytes());
+                framed_body.extend_from_slice(&block);
+                Ok(framed_body)
+            }
+            CompressionAlgorithm::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+            #[cfg(feature = "zstd")]
+            CompressionAlgorithm::Zstd => zstd::encode_all(body, 0),
+            #[allow(unreachable_patterns)]
+            disabled_algorithm => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!("`{disabled_algorithm}` is not enabled in this build"),
+            )),
+        }
+    }
+}
+
+/// Size of the length prefix of the frames of a `qw-framed-zstd` body.
+#[cfg(feature = "zstd")]
+const FRAME_LENGTH_PREFIX_SIZE: usize = 4;
+
+/// Reader over the concatenated content of the frames of a `qw-framed-zstd` body.
+///
+/// Frames are read from `reader` and decoded lazily, one after the other, so a limit enforced on
+/// the output of the decoder applies to all the frames at once.
+#[cfg(feature = "zstd")]
+struct FramedZstdDecoder<R> {
+    // The reader positioned at the start of the next frame. It is handed over to the frame decoder
+    // while a frame is being decoded.
+    reader_opt: Option<R>,
+    frame_decoder_opt: Option<zstd::stream::read::Decoder<'static, BufReader<FrameBlockReader<R>>>>,
+}
+
+#[cfg(feature = "zstd")]
+impl<R: Read> FramedZstdDecoder<R> {
+    fn new(reader: R) -> Self {
+        FramedZstdDecoder {
+            reader_opt: Some(reader),
+            frame_decoder_opt: None,
+        }
+    }
+
+    /// Reads the length prefix of the next frame. Returns `None` if there are no frames left.
+    /// Errors if the length prefix is truncated.
+    fn next_block_len(reader: &mut R) -> std::io::Result<Option<u64>> {
+        let mut length_prefix = [0u8; FRAME_LENGTH_PREFIX_SIZE];
+        let mut num_bytes_read = 0;
+
+        while num_bytes_read < FRAME_LENGTH_PREFIX_SIZE {
+            match reader.read(&mut length_prefix[num_bytes_read..]) {
+                Ok(0) => break,
+                Ok(num_bytes) => num_bytes_read += num_bytes,
+                Err(io_error) if io_error.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(io_error) => return Err(io_error),
+            }
+        }
+        match num_bytes_read {
+            0 => Ok(None),
+            FRAME_LENGTH_PREFIX_SIZE => Ok(Some(u32::from_le_bytes(length_prefix) as u64)),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "truncated frame length prefix",
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl<R: Read> Read for FramedZstdDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            if let Some(frame_decoder) = &mut self.frame_decoder_opt {
+                let num_bytes = frame_decoder.read(buf)?;
+
+                if num_bytes > 0 {
+                    return Ok(num_bytes);
+                }
+                // The frame decoder reads its block to the end, so the reader is now positioned at
+                // the start of the next frame.
+                let frame_decoder = self
+                    .frame_decoder_opt
+                    .take()
+                    .expect("the frame decoder should be set");
+                self.reader_opt = Some(frame_decoder.finish().into_inner().reader);
+            }
+            let Some(reader) = &mut self.reader_opt else {
+                return Ok(0);
+            };
+            let Some(block_len) = Self::next_block_len(reader)? else {
+                return Ok(0);
+            };
+            let reader = self
+                .reader_opt
+                .take()
+                .expect("the reader should be set between frames");
+            let block_reader = FrameBlockReader {
+                reader,
+                block_len,
+                num_bytes_read: 0,
+            };
+            let frame_decoder = zstd::stream::read::Decoder::new(block_reader)?;
+            self.frame_decoder_opt = Some(frame_decoder);
+        }
+    }
+}
+
+/// Reader over the zstd block of a `qw-framed-zstd` frame. Errors if `reader` ends before the
+/// block does.
+#[cfg(feature = "zstd")]
+struct FrameBlockReader<R> {
+    reader: R,
+    block_len: u64,
+    num_bytes_read: u64,
+}
+
+#[cfg(feature = "zstd")]
+impl<R: Read> Read for FrameBlockReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let num_bytes_remaining = self.block_len - self.num_bytes_read;
+
+        if num_bytes_remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+        let max_num_bytes = (buf.len() as u64).min(num_bytes_remaining) as usize;
+        let num_bytes = self.reader.read(&mut buf[..max_num_bytes])?;
+
+        if num_bytes == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!(
+                    "truncated frame: expected {} bytes, got {}",
+                    self.block_len, self.num_bytes_read
+                ),
+            ));
+        }
+        self.num_bytes_read += num_bytes as u64;
+        Ok(num_bytes)
+    }
+}
+
+impl fmt::Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+fn enabled_content_encodings() -> String {
+    CompressionAlgorithm::ALL
+        .iter()
+        .filter(|algorithm| algorithm.is_enabled())
+        .map(|algorithm| format!("`{algorithm}`"))
+        .join(", ")
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum UnsupportedCompressionAlgorithm {
+    #[error(
+        "request's content-encoding `{0}` is unknown: supported encodings are {}",
+        enabled_content_encodings()
+    )]
+    Unknown(String),
+    #[error(
+        "request's content-encoding `{0}` is not enabled in this build of Quickwit: supported \
+         encodings are {}",
+        enabled_content_encodings()
+    )]
+    Disabled(CompressionAlgorithm),
+}
+
+impl Reject for UnsupportedCompressionAlgorithm {}
+
+#[derive(Debug, Error)]
+#[error("failed to decompress {algorithm} request body: {message}")]
+pub(crate) struct MalformedCompressedBody {
+    pub algorithm: CompressionAlgorithm,
+    pub message: String,
+}
+
+impl Reject for MalformedCompressedBody {}
+
+#[derive(Debug, Error)]
+#[error("request body is larger than the limit of {limit_num_bytes} bytes")]
+pub(crate) struct PayloadTooLarge {
+    pub limit_num_bytes: u64,
+}
+
+impl Reject for PayloadTooLarge {}
+
+#[derive(Debug, Error)]
+#[error("decompressed request body is larger than the limit of {limit_num_bytes} bytes")]
+pub(crate) struct DecompressedBodyTooLarge {
+    pub limit_num_bytes: u64,
+}
+
+impl Reject for DecompressedBodyTooLarge {}
+
+#[derive(Debug, Error)]
+#[error("request body is not valid UTF-8: invalid byte sequence at offset {offset}")]
+pub(crate) struct InvalidUtf8 {
+    /// Offset of the first invalid byte sequence in the decompressed body.
+    pub offset: usize,
+}
+
+impl Reject for InvalidUtf8 {}
+
+#[derive(Debug, Error)]
+#[error("request references unknown brotli dictionary `{dictionary_id}`")]
+pub(crate) struct UnknownBrotliDictionary {
+    pub dictionary_id: String,
+}
+
+impl Reject for UnknownBrotliDictionary {}
+
+#[derive(Debug, Error)]
+#[error(
+    "request body looks {algorithm}-compressed but has no `content-encoding` header, set \
+     `content-encoding: {algorithm}` if it is"
+)]
+pub(crate) struct UndeclaredCompressedBody {
+    pub algorithm: CompressionAlgorithm,
+}
+
+impl Reject for UndeclaredCompressedBody {}
+
+#[derive(Debug, Error)]
+pub(crate) enum DecompressionError {
+    #[error(transparent)]
+    UnsupportedCompressionAlgorithm(#[from] UnsupportedCompressionAlgorithm),
+    #[error(transparent)]
+    MalformedCompressedBody(#[from] MalformedCompressedBody),
+    #[error(transparent)]
+    UnknownBrotliDictionary(#[from] UnknownBrotliDictionary),
+    #[error(transparent)]
+    UndeclaredCompressedBody(#[from] UndeclaredCompressedBody),
+    #[error(transparent)]
+    DecompressedBodyTooLarge(#[from] DecompressedBodyTooLarge),
+}
+
+/// Error returned by [`decompress_chunks`].
+#[derive(Debug, Error)]
+pub(crate) enum DecompressChunksError<E> {
+    #[error(transparent)]
+    Decompression(#[from] DecompressionError),
+    /// The chunk callback returned an error.
+    #[error("decompression aborted: {0}")]
+    Aborted(E),
+}
+
+impl From<DecompressionError> for Rejection {
+    fn from(error: DecompressionError) -> Self {
+        match error {
+            DecompressionError::UnsupportedCompressionAlgorithm(error) => {
+                warp::reject::custom(error)
+            }
+            DecompressionError::MalformedCompressedBody(error) => warp::reject::custom(error),
+            DecompressionError::UnknownBrotliDictionary(error) => warp::reject::custom(error),
+            DecompressionError::UndeclaredCompressedBody(error) => warp::reject::custom(error),
+            DecompressionError::DecompressedBodyTooLarge(error) => warp::reject::custom(error),
+        }
+    }
+}
+
+/// Extracts the body of a request and decompresses it according to its `content-encoding`
+/// header. Bodies without a `content-encoding` header, or with an empty or `identity` one, are
+/// returned as is. Brotli bodies compressed with a custom dictionary reference it with the
+/// [`BROTLI_DICTIONARY_ID_HEADER`] header. When UTF-8 validation is enabled, the decompressed
+/// body is then converted to UTF-8 according to the charset of its `content-type` header, see
+/// [`to_utf8`]. The decompression options usually come from the node config, see
+/// [`DecompressOptions::from_ingest_api_config`].
+///
+/// A route extracts its body with either [`decompress`] or [`raw_body`], never both: the body of
+/// a request can only be consumed once.
+pub(crate) fn decompress(
+    options: DecompressOptions,
+) -> impl Filter<Extract = (Bytes,), Error = Rejection> + Clone {
+    decompress_with_meta(options).map(|body: Bytes, _meta: DecompressionMeta| body)
+}
+
+/// Same as [`decompress`], but the decompressed body is extracted along with a
+/// [`DecompressionMeta`] describing how it was decompressed, for handlers that log or report it.
+pub(crate) fn decompress_with_meta(
+    options: DecompressOptions,
+) -> impl Filter<Extract = (Bytes, DecompressionMeta), Error = Rejection> + Clone {
+    decompress_raw_body_with_meta(raw_body(), options)
+}
+
+/// Same as [`decompress`], but the raw body is extracted with [`raw_body_with_limit`]: requests
+/// whose raw body exceeds `limit_num_bytes` are rejected before being decompressed.
+pub(crate) fn decompress_with_limit(
+    limit_num_bytes: u64,
+    options: DecompressOptions,
+) -> impl Filter<Extract = (Bytes,), Error = Rejection> + Clone {
+    decompress_raw_body(raw_body_with_limit(limit_num_bytes), options)
+}
+
+fn decompress_raw_body(
+    raw_body: impl Filter<Extract = (Bytes,), Error = Rejection> + Clone,
+    options: DecompressOptions,
+) -> impl Filter<Extract = (Bytes,), Error = Rejection> + Clone {
+    decompress_raw_body_with_meta(raw_body, options)
+        .map(|body: Bytes, _meta: DecompressionMeta| body)
+}
+
+fn decompress_raw_body_with_meta(
+    raw_body: impl Filter<Extract = (Bytes,), Error = Rejection> + Clone,
+    options: DecompressOptions,
+) -> impl Filter<Extract = (Bytes, DecompressionMeta), Error = Rejection> + Clone {
+    warp::header::headers_cloned()
+        .map(|headers: HeaderMap| content_encoding_from_headers(&headers))
+        .and(warp::header::optional::<String>(
+            BROTLI_DICTIONARY_ID_HEADER,
+        ))
+        .and(warp::header::optional::<String>(CONTENT_TYPE.as_str()))
+        .and(raw_body)
+        .and_then(
+            move |content_encoding_opt: Option<String>,
+                  brotli_dictionary_id_opt: Option<String>,
+                  content_type_opt: Option<String>,
+                  body: Bytes| async move {
+                let (body, meta) = decompress_body_with_meta(
+                    content_encoding_opt,
+                    brotli_dictionary_id_opt,
+                    body,
+                    options,
+                )
+                .await
+                .map_err(Rejection::from)?;
+                // The parts of multipart bodies are validated one by one, according to their own
+                // charset.
+                let is_multipart = content_type_opt.as_deref().map_or(false, |content_type| {
+                    content_type
+                        .trim_start()
+                        .to_ascii_lowercase()
+                        .starts_with("multipart/")
+                });
+                if !options.validate_utf8 || is_multipart {
+                    return Ok((body, meta));
+                }
+                let charset_opt = content_type_opt
+                    .as_deref()
+                    .and_then(Charset::from_content_type);
+                let body = to_utf8(body, charset_opt).map_err(warp::reject::custom)?;
+                Ok::<_, Rejection>((body, meta))
+            },
+        )
+        .untuple_one()
+}
+
+/// Returns the `content-encoding` of a request or multipart part. The header may be split across
+/// several lines, so all of them are joined, in order, into a single comma-separated list.
+pub(crate) fn content_encoding_from_headers(headers: &HeaderMap) -> Option<String> {
+    let content_encodings: Vec<String> = headers
+        .get_all(CONTENT_ENCODING)
+        .iter()
+        .map(|header_value| String::from_utf8_lossy(header_value.as_bytes()).into_owned())
+        .collect();
+    if content_encodings.is_empty() {
+        return None;
+    }
+    Some(content_encodings.join(", "))
+}
+
+/// Extracts the body of a request as is, ignoring its `content-encoding` header.
+///
+/// This is meant for routes that must forward the payload untouched, for instance to another
+/// node. Such routes must not go through [`decompress`], otherwise the payload could end up
+/// decompressed twice.
+pub(crate) fn raw_body() -> impl Filter<Extract = (Bytes,), Error = Rejection> + Clone {
+    warp::body::bytes()
+}
+
+/// Extracts the body of a request as is, like [`raw_body`], and rejects it with
+/// [`PayloadTooLarge`] if it exceeds `limit_num_bytes`.
+///
+/// Unlike `warp::body::content_length_limit`, this does not require a `content-length` header:
+/// chunked bodies are accepted and their size is checked as their chunks arrive, so that oversized
+/// bodies are rejected without being buffered in full. Bodies announcing a `content-length` above
+/// the limit are rejected upfront.
+pub(crate) fn raw_body_with_limit(
+    limit_num_bytes: u64,
+) -> impl Filter<Extract = (Bytes,), Error = Rejection> + Clone {
+    warp::header::optional::<u64>(CONTENT_LENGTH.as_str())
+        .and_then(move |content_length_opt: Option<u64>| async move {
+            match content_length_opt {
+                Some(content_length) if content_length > limit_num_bytes => {
+                    Err(warp::reject::custom(PayloadTooLarge { limit_num_bytes }))
+                }
+                _ => Ok(limit_num_bytes),
+            }
+        })
+        .and(warp::body::stream())
+        .and_then(read_body_with_limit)
+}
+
+/// Reads `body_stream` to the end, counting bytes as they arrive, and stops with a
+/// [`PayloadTooLarge`] rejection as soon as more than `limit_num_bytes` have been received.
+async fn read_body_with_limit<S, B, E>(
+    limit_num_bytes: u64,
+    body_stream: S,
+) -> Result<Bytes, Rejection>
+where
+    S: Stream<Item = Result<B, E>>,
+    B: Buf,
+    E: fmt::Display,
+{
+    let mut body = BytesMut::new();
+    futures::pin_mut!(body_stream);
+
+    while let Some(chunk_res) = body_stream.next().await {
+        let chunk = chunk_res.map_err(|error| {
+            warp::reject::custom(InvalidArgument(format!(
+                "failed to read request body: {error}"
+            )))
+        })?;
+        if (body.len() + chunk.remaining()) as u64 > limit_num_bytes {
+            return Err(warp::reject::custom(PayloadTooLarge { limit_num_bytes }));
+        }
+        body.put(chunk);
+    }
+    Ok(body.freeze())
+}
+
+/// Options of the decompression of request bodies, see [`decompress_body`].
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct DecompressOptions {
+    /// Treat unknown content-encodings as `identity` instead of rejecting them.
+    pub lenient_unknown_encoding: bool,
+    /// Reject bodies without a `content-encoding` header that look compressed, see
+    /// [`detect_undeclared_compression`].
+    pub detect_undeclared_compression: bool,
+    /// Reject bodies that decompress to more bytes than this limit. With stacked
+    /// content-encodings, the limit applies to the output of each decoder.
+    pub max_decompressed_num_bytes_opt: Option<u64>,
+    /// Validate decompressed bodies as UTF-8, see [`to_utf8`].
+    pub validate_utf8: bool,
+}
+
+impl DecompressOptions {
+    /// Returns the options set in the `ingest_api` section of the node config.
+    pub fn from_ingest_api_config(ingest_api_config: &IngestApiConfig) -> Self {
+        DecompressOptions {
+            lenient_unknown_encoding: ingest_api_config.lenient_unknown_content_encoding,
+            detect_undeclared_compression: ingest_api_config.detect_undeclared_compression,
+            max_decompressed_num_bytes_opt: ingest_api_config
+                .decompressed_content_length_limit
+                .map(|limit| limit.as_u64()),
+            validate_utf8: ingest_api_config.validate_utf8_request_bodies,
+        }
+    }
+}
+
+/// Returns the compression algorithm whose magic bytes `body` starts with, if any. Only formats
+/// with a magic number are detected: gzip, zlib-wrapped deflate, and zstd. Brotli streams have no
+/// magic number.
+///
+/// This is a cheap check of the first bytes of the body, not an attempt at decompressing it.
+fn detect_undeclared_compression(body: &[u8]) -> Option<CompressionAlgorithm> {
+    match body {
+        [0x1f, 0x8b, ..] => Some(CompressionAlgorithm::Gzip),
+        // The zlib header with the compression levels emitted by common encoders. The second byte
+        // is a checksum of the first one, which makes false positives on text unlikely.
+        [0x78, 0x01 | 0x5e | 0x9c | 0xda, ..] => Some(CompressionAlgorithm::Deflate),
+        [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(CompressionAlgorithm::Zstd),
+        _ => None,
+    }
+}
+
+/// Header referencing the custom dictionary a brotli request body was compressed with, by ID.
+pub(crate) const BROTLI_DICTIONARY_ID_HEADER: &str = "x-qw-brotli-dictionary-id";
+
+/// Custom brotli dictionaries that request bodies can be compressed with, keyed by ID.
+#[derive(Debug, Default)]
+pub(crate) struct BrotliDictionaries {
+    dictionaries: RwLock<HashMap<String, Arc<[u8]>>>,
+}
+
+impl BrotliDictionaries {
+    /// Loads the dictionaries stored in the directory `dir_path`, one per file. The ID of a
+    /// dictionary is the name of its file.
+    fn load_from_dir(dir_path: &Path) -> std::io::Result<Self> {
+        let mut dictionaries = HashMap::new();
+
+        for dir_entry_res in std::fs::read_dir(dir_path)? {
+            let dir_entry = dir_entry_res?;
+
+            if !dir_entry.file_type()?.is_file() {
+                continue;
+            }
+            let Ok(dictionary_id) = dir_entry.file_name().into_string() else {
+                continue;
+            };
+            let dictionary: Arc<[u8]> = std::fs::read(dir_entry.path())?.into();
+            dictionaries.insert(dictionary_id, dictionary);
+        }
+        Ok(BrotliDictionaries {
+            dictionaries: RwLock::new(dictionaries),
+        })
+    }
+
+    /// Registers a dictionary, replacing the dictionary with the same ID, if any.
+    pub fn register(&self, dictionary_id: String, dictionary: Arc<[u8]>) {
+        self.dictionaries
+            .write()
+            .expect("the brotli dictionaries lock should not be poisoned")
+            .insert(dictionary_id, dictionary);
+    }
+
+    pub fn get(&self, dictionary_id: &str) -> Option<Arc<[u8]>> {
+        self.dictionaries
+            .read()
+            .expect("the brotli dictionaries lock should not be poisoned")
+            .get(dictionary_id)
+            .cloned()
+    }
+}
+
+/// Custom brotli dictionaries available to request bodies. No dictionary is available until
+/// [`load_brotli_dictionaries`] is called, when the REST routes are built.
+pub(crate) static BROTLI_DICTIONARIES: Lazy<BrotliDictionaries> =
+    Lazy::new(BrotliDictionaries::default);
+
+/// Registers the custom brotli dictionaries stored in the directory
+/// [`IngestApiConfig::brotli_dictionaries_dir`], if set, see
+/// [`BrotliDictionaries::load_from_dir`]. Failing to load them is logged, not fatal: requests
+/// referencing the dictionaries are then rejected as [`UnknownBrotliDictionary`].
+pub(crate) fn load_brotli_dictionaries(ingest_api_config: &IngestApiConfig) {
+    let Some(dir_path) = &ingest_api_config.brotli_dictionaries_dir else {
+        return;
+    };
+    match BrotliDictionaries::load_from_dir(dir_path) {
+        Ok(brotli_dictionaries) => {
+            let dictionaries = brotli_dictionaries
+                .dictionaries
+                .into_inner()
+                .expect("the brotli dictionaries lock should not be poisoned");
+            info!(
+                dir_path=%dir_path.display(),
+                num_dictionaries=dictionaries.len(),
+                "loaded brotli dictionaries"
+            );
+
+            for (dictionary_id, dictionary) in dictionaries {
+                BROTLI_DICTIONARIES.register(dictionary_id, dictionary);
+            }
+        }
+        Err(io_error) => {
+            error!(
+                dir_path=%dir_path.display(),
+                error=?io_error,
+                "failed to load brotli dictionaries"
+            );
+        }
+    }
+}
+
+/// Character sets of request bodies known to Quickwit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Charset {
+    Utf8,
+    Latin1,
+}
+
+impl Charset {
+    /// Parses the `charset` parameter of a `content-type` header. Returns `None` if the parameter
+    /// is absent or if the charset is not supported.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let charset = content_type.split(';').skip(1).find_map(|parameter| {
+            let (name, value) = parameter.split_once('=')?;
+            let is_charset = name.trim().eq_ignore_ascii_case("charset");
+            is_charset.then(|| value.trim().trim_matches('"'))
+        })?;
+        match charset.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" | "us-ascii" => Some(Charset::Utf8),
+            "iso-8859-1" | "latin1" | "latin-1" => Some(Charset::Latin1),
+            _ => None,
+        }
+    }
+}
+
+/// Converts a decompressed body to UTF-8. Latin-1 bodies are transcoded, while other bodies are
+/// validated and returned as is, so that invalid bytes are reported before they reach the JSON
+/// parser.
+pub(crate) fn to_utf8(body: Bytes, charset_opt: Option<Charset>) -> Result<Bytes, InvalidUtf8> {
+    if body.is_ascii() {
+        return Ok(body);
+    }
+    if charset_opt == Some(Charset::Latin1) {
+        // Latin-1 characters are the first 256 Unicode code points.
+        let transcoded_body: String = body.iter().map(|&byte| byte as char).collect();
+        return Ok(Bytes::from(transcoded_body));
+    }
+    if let Err(utf8_error) = std::str::from_utf8(&body) {
+        return Err(InvalidUtf8 {
+            offset: utf8_error.valid_up_to(),
+        });
+    }
+    Ok(body)
+}
+
+/// Size of the chunks of decompressed bytes produced by [`decompress_body`].
+const DECOMPRESSION_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Stage of the decoding pipeline of [`decompress_chunks`], undoing one content-encoding.
+///
+/// The first error of the pipeline is recorded in `first_error`, so that it is reported against the
+/// stage where it occurred, however the decoders of the following stages wrap it. The stage fails
+/// once it has produced more than `max_num_bytes_opt` bytes.
+struct DecoderStage<'a> {
+    decoder: Box<dyn Read + 'a>,
+    algorithm: CompressionAlgorithm,
+    max_num_bytes_opt: Option<u64>,
+    num_decoded_bytes: u64,
+    first_error: Rc<RefCell<Option<DecompressionError>>>,
+}
+
+impl DecoderStage<'_> {
+    fn record_error(&self, error: impl Into<DecompressionError>) {
+        let mut first_error = self.first_error.borrow_mut();
+
+        if first_error.is_none() {
+            *first_error = Some(error.into());
+        }
+    }
+}
+
+impl Read for DecoderStage<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let num_bytes = match self.decoder.read(buf) {
+            Ok(num_bytes) => num_bytes,
+            Err(io_error) if io_error.kind() == std::io::ErrorKind::Interrupted => {
+                return Err(io_error);
+            }
+            Err(io_error) => {
+                self.record_error(MalformedCompressedBody {
+                    algorithm: self.algorithm,
+                    message: io_error.to_string(),
+                });
+                return Err(io_error);
+            }
+        };
+        self.num_decoded_bytes += num_bytes as u64;
+
+        if let Some(max_num_bytes) = self.max_num_bytes_opt {
+            if self.num_decoded_bytes > max_num_bytes {
+                self.record_error(DecompressedBodyTooLarge {
+                    limit_num_bytes: max_num_bytes,
+                });
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!("`{}` decoder output exceeds the limit", self.algorithm),
+                ));
+            }
+        }
+        Ok(num_bytes)
+    }
+}
+
+/// Decompresses `body` with `algorithms`, listed in the order they were applied, and hands the
+/// decompressed bytes over to `on_chunk` as soon as they are produced, in chunks of at most
+/// `chunk_size` bytes. Without algorithms, `body` is passed as is. Brotli bodies compressed with a
+/// custom dictionary are decompressed with `brotli_dictionary_opt`.
+///
+/// The algorithms are undone in reverse order by a pipeline of decoders, each one reading the
+/// output of the previous one, so no intermediate output is ever held in memory. Decompression
+/// fails with [`DecompressedBodyTooLarge`] as soon as the output of any decoder exceeds
+/// `max_decompressed_num_bytes_opt`, which protects against bodies whose inner encodings are
+/// decompression bombs too.
+///
+/// This lets callers start processing a body, for instance splitting NDJSON lines and enforcing
+/// a limit on the number of docs, before it is fully decompressed. Decompression stops as soon as
+/// `on_chunk` returns an error.
+///
+/// Decompression is CPU-bound: async callers should run this on the blocking thread pool.
+pub(crate) fn decompress_chunks<E>(
+    algorithms: &[CompressionAlgorithm],
+    brotli_dictionary_opt: Option<&[u8]>,
+    body: &[u8],
+    max_decompressed_num_bytes_opt: Option<u64>,
+    chunk_size: usize,
+    mut on_chunk: impl FnMut(&[u8]) -> Result<(), E>,
+) -> Result<(), DecompressChunksError<E>> {
+    let chunk_size = chunk_size.max(1);
+
+    if algorithms.is_empty() {
+        for chunk in body.chunks(chunk_size) {
+            on_chunk(chunk).map_err(DecompressChunksError::Aborted)?;
+        }
+        return Ok(());
+    }
+    if let Some(&algorithm) = algorithms.iter().find(|algorithm| !algorithm.is_enabled()) {
+        return Err(
+            DecompressionError::from(UnsupportedCompressionAlgorithm::Disabled(algorithm)).into(),
+        );
+    }
+    let first_error: Rc<RefCell<Option<DecompressionError>>> = Rc::default();
+    let mut reader: Box<dyn Read + '_> = Box::new(body);
+
+    for (stage_ordinal, &algorithm) in algorithms.iter().rev().enumerate() {
+        let decoder = algorithm
+            .decoder(reader, brotli_dictionary_opt)
+            .map_err(|io_error| {
+                DecompressionError::from(MalformedCompressedBody {
+                    algorithm,
+                    message: io_error.to_string(),
+                })
+            })?;
+        // The output of the last decoder is bounded by the final read below.
+        let is_last_stage = stage_ordinal + 1 == algorithms.len();
+        let max_num_bytes_opt = if is_last_stage {
+            None
+        } else {
+            max_decompressed_num_bytes_opt
+        };
+        reader = Box::new(DecoderStage {
+            decoder,
+            algorithm,
+            max_num_bytes_opt,
+            num_decoded_bytes: 0,
+            first_error: first_error.clone(),
+        });
+    }
+    let max_num_bytes = max_decompressed_num_bytes_opt.unwrap_or(u64::MAX);
+    let mut reader = reader.take(max_num_bytes.saturating_add(1));
+    let mut num_decompressed_bytes: u64 = 0;
+    let mut buffer = vec![0; chunk_size];
+
+    loop {
+        let num_bytes = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(num_bytes) => num_bytes,
+            Err(io_error) if io_error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(io_error) => {
+                let error = first_error.borrow_mut().take().unwrap_or_else(|| {
+                    MalformedCompressedBody {
+                        algorithm: algorithms[0],
+                        message: io_error.to_string(),
+                    }
+                    .into()
+                });
+                return Err(error.into());
+            }
+        };
+        num_decompressed_bytes += num_bytes as u64;
+
+        if num_decompressed_bytes > max_num_bytes {
+            let error = DecompressedBodyTooLarge {
+                limit_num_bytes: max_num_bytes,
+            };
+            return Err(DecompressionError::from(error).into());
+        }
+        on_chunk(&buffer[..num_bytes]).map_err(DecompressChunksError::Aborted)?;
+    }
+}
+
+/// Returns whether `content_encoding` means "no transformation".
+fn is_identity_content_encoding(content_encoding: &str) -> bool {
+    let content_encoding = content_encoding.trim();
+    content_encoding.is_empty() || content_encoding.eq_ignore_ascii_case("identity")
+}
+
+/// Parses a `content-encoding` header into the list of algorithms applied to the body, in the
+/// order they were applied. Empty and `identity` codings are skipped. The wildcard `*` is only
+/// meaningful in `accept-encoding`, so it is handled like any unknown coding: rejected, or
+/// skipped with a warning when `lenient_unknown_encoding` is set.
+fn parse_content_encodings(
+    content_encoding: &str,
+    lenient_unknown_encoding: bool,
+) -> Result<Vec<CompressionAlgorithm>, UnsupportedCompressionAlgorithm> {
+    let mut algorithms = Vec::new();
+
+    for coding in content_encoding.split(',') {
+        if is_identity_content_encoding(coding) {
+            continue;
+        }
+        let coding = coding.trim();
+        let Some(algorithm) = CompressionAlgorithm::from_content_encoding(coding) else {
+            if lenient_unknown_encoding {
+                warn!(
+                    content_encoding = coding,
+                    "treating unknown content-encoding as identity"
+                );
+                continue;
+            }
+            return Err(UnsupportedCompressionAlgorithm::Unknown(coding.to_string()));
+        };
+        if !algorithm.is_enabled() {
+            return Err(UnsupportedCompressionAlgorithm::Disabled(algorithm));
+        }
+        algorithms.push(algorithm);
+    }
+    Ok(algorithms)
+}
+
+/// Describes how a request body was decompressed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct DecompressionMeta {
+    /// The algorithms the body was compressed with, in the order they were applied. Empty if the
+    /// body was not compressed, i.e. its content-encoding is `identity`.
+    pub algorithms: Vec<CompressionAlgorithm>,
+    pub compressed_num_bytes: usize,
+    /// The size of the body after decompression, before any charset conversion.
+    pub decompressed_num_bytes: usize,
+}
+
+impl DecompressionMeta {
+    fn identity(num_bytes: usize) -> Self {
+        DecompressionMeta {
+            algorithms: Vec::new(),
+            compressed_num_bytes: num_bytes,
+            decompressed_num_bytes: num_bytes,
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.algorithms.is_empty()
+    }
+
+    /// Returns the content-encoding of the body with unknown and `identity` codings removed, or
+    /// `identity` if the body was not compressed.
+    pub fn content_encoding(&self) -> String {
+        if self.is_identity() {
+            return "identity".to_string();
+        }
+        self.algorithms
+            .iter()
+            .map(|algorithm| algorithm.as_str())
+            .join(", ")
+    }
+}
+
+/// Decompresses `body` according to `content_encoding_opt`. An absent, empty, or `identity`
+/// content-encoding means the body was not transformed, so it is returned as is. Empty bodies
+/// are returned as is too, whatever their content-encoding: clients such as health checks send
+/// them with the content-encoding of their regular requests, and no decoder accepts empty input.
+/// When several codings are listed, for instance `gzip, br`, they are undone in reverse order.
+/// Brotli bodies referencing a custom dictionary with `brotli_dictionary_id_opt` are decompressed
+/// with it, and rejected if it is unknown. The dictionary ID is ignored by other algorithms.
+/// Unknown codings are rejected, unless [`DecompressOptions::lenient_unknown_encoding`] is set, in
+/// which case they are treated as `identity`. Bodies without a content-encoding that look
+/// compressed are rejected when [`DecompressOptions::detect_undeclared_compression`] is set. Bodies
+/// decompressing to more than [`DecompressOptions::max_decompressed_num_bytes_opt`] are rejected,
+/// see [`decompress_chunks`]. The size of the body is logged at the debug level, see
+/// [`log_request_body_sizes`].
+///
+/// Decompression is CPU-bound, so it runs on the blocking thread pool.
+pub(crate) async fn decompress_body(
+    content_encoding_opt: Option<String>,
+    brotli_dictionary_id_opt: Option<String>,
+    body: Bytes,
+    options: DecompressOptions,
+) -> Result<Bytes, DecompressionError> {
+    let (decompressed_body, _meta) = decompress_body_with_meta(
+        content_encoding_opt,
+        brotli_dictionary_id_opt,
+        body,
+        options,
+    )
+    .await?;
+    Ok(decompressed_body)
+}
+
+/// Same as [`decompress_body`], but also returns a [`DecompressionMeta`] describing how the body
+/// was decompressed.
+pub(crate) async fn decompress_body_with_meta(
+    content_encoding_opt: Option<String>,
+    brotli_dictionary_id_opt: Option<String>,
+    body: Bytes,
+    options: DecompressOptions,
+) -> Result<(Bytes, DecompressionMeta), DecompressionError> {
+    let compressed_num_bytes = body.len();
+
+    if body.is_empty() {
+        let meta = DecompressionMeta::identity(compressed_num_bytes);
+        log_request_body_sizes(&meta);
+        return Ok((body, meta));
+    }
+    let algorithms = match content_encoding_opt {
+        Some(content_encoding) => {
+            parse_content_encodings(&content_encoding, options.lenient_unknown_encoding)?
+        }
+        None => {
+            if options.detect_undeclared_compression {
+                if let Some(algorithm) = detect_undeclared_compression(&body) {
+                    return Err(UndeclaredCompressedBody { algorithm }.into());
+                }
+            }
+            Vec::new()
+        }
+    };
+    if algorithms.is_empty() {
+        let meta = DecompressionMeta::identity(compressed_num_bytes);
+        log_request_body_sizes(&meta);
+        return Ok((body, meta));
+    }
+    let brotli_dictionary_opt = match brotli_dictionary_id_opt {
+        Some(dictionary_id) if algorithms.contains(&CompressionAlgorithm::Brotli) => {
+            let Some(brotli_dictionary) = BROTLI_DICTIONARIES.get(&dictionary_id) else {
+                return Err(UnknownBrotliDictionary { dictionary_id }.into());
+            };
+            Some(brotli_dictionary)
+        }
+        _ => None,
+    };
+    let (decompressed_body, algorithms) = tokio::task::spawn_blocking(move || {
+        let mut decompressed_body = Vec::new();
+
+        decompress_chunks(
+            &algorithms,
+            brotli_dictionary_opt.as_deref(),
+            &body,
+            options.max_decompressed_num_bytes_opt,
+            DECOMPRESSION_CHUNK_SIZE,
+            |chunk| -> Result<(), Infallible> {
+                decompressed_body.extend_from_slice(chunk);
+                Ok(())
+            },
+        )
+        .map_err(|error| match error {
+            DecompressChunksError::Decompression(error) => error,
+            DecompressChunksError::Aborted(infallible) => match infallible {},
+        })?;
+        Ok::<_, DecompressionError>((Bytes::from(decompressed_body), algorithms))
+    })
+    .await
+    .expect("decompression task should not panic")?;
+
+    let meta = DecompressionMeta {
+        algorithms,
+        compressed_num_bytes,
+        decompressed_num_bytes: decompressed_body.len(),
+    };
+    log_request_body_sizes(&meta);
+    Ok((decompressed_body, meta))
+}
+
+/// Logs the size of a request body before and after decompression, so that clients sending
+/// poorly compressed payloads can be identified. The compression ratio is the decompressed size
+/// divided by the compressed size. Bodies that were not compressed are logged with their size
+/// only.
+fn log_request_body_sizes(meta: &DecompressionMeta) {
+    let content_encoding = meta.content_encoding();
+    let compressed_num_bytes = meta.compressed_num_bytes;
+    let decompressed_num_bytes_opt = (!meta.is_identity()).then_some(meta.decompressed_num_bytes);
+    let compression_ratio_opt = decompressed_num_bytes_opt
+        .filter(|_| compressed_num_bytes > 0)
+        .map(|decompressed_num_bytes| decompressed_num_bytes as f64 / compressed_num_bytes as f64);
+    debug!(
+        content_encoding = content_encoding.as_str(),
+        compressed_num_bytes,
+        decompressed_num_bytes = decompressed_num_bytes_opt,
+        compression_ratio = compression_ratio_opt,
+        "received request body"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use bytesize::ByteSize;
+
+    use super::*;
+
+    fn gzip(payload: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(payload).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_decompress_identity() {
+        let body = warp::test::request()
+            .body("hello")
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap();
+        assert_eq!(body, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_decompress_identity_content_encoding() {
+        for content_encoding in ["identity", "IDENTITY", " identity "] {
+            let body = warp::test::request()
+                .header("content-encoding", content_encoding)
+                .body("hello")
+                .filter(&decompress(DecompressOptions::default()))
+                .await
+                .unwrap();
+            assert_eq!(body, "hello");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decompress_empty_content_encoding() {
+        let body = warp::test::request()
+            .header("content-encoding", "")
+            .body("hello")
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap();
+        assert_eq!(body, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_decompress_gzip() {
+        let body = warp::test::request()
+            .header("content-encoding", "gzip")
+            .body(gzip(b"hello"))
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap();
+        assert_eq!(body, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_decompress_with_meta() {
+        let payload = b"hello hello hello hello hello hello hello hello";
+        let compressed_payload = gzip(payload);
+        let (body, meta) = warp::test::request()
+            .header("content-encoding", "gzip")
+            .body(compressed_payload.clone())
+            .filter(&decompress_with_meta(DecompressOptions::default()))
+            .await
+            .unwrap();
+        assert_eq!(body, &payload[..]);
+        assert_eq!(meta.algorithms, [CompressionAlgorithm::Gzip]);
+        assert_eq!(meta.content_encoding(), "gzip");
+        assert_eq!(meta.compressed_num_bytes, compressed_payload.len());
+        assert_eq!(meta.decompressed_num_bytes, payload.len());
+
+        let (body, meta) = warp::test::request()
+            .body("hello")
+            .filter(&decompress_with_meta(DecompressOptions::default()))
+            .await
+            .unwrap();
+        assert_eq!(body, "hello");
+        assert!(meta.is_identity());
+        assert_eq!(meta.content_encoding(), "identity");
+        assert_eq!(meta.compressed_num_bytes, 5);
+        assert_eq!(meta.decompressed_num_bytes, 5);
+    }
+
+    #[tokio::test]
+    async fn test_decompress_deflate() {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello").unwrap();
+        let body = warp::test::request()
+            .header("content-encoding", "deflate")
+            .body(encoder.finish().unwrap())
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap();
+        assert_eq!(body, "hello");
+    }
+
+    #[cfg(feature = "brotli")]
+    #[tokio::test]
+    async fn test_decompress_brotli() {
+        let mut compressed_body = Vec::new();
+        {
+            let mut encoder = brotli::CompressorWriter::new(&mut compressed_body, 4096, 5, 22);
+            encoder.write_all(b"hello").unwrap();
+        }
+        let body = warp::test::request()
+            .header("content-encoding", "br")
+            .body(compressed_body)
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap();
+        assert_eq!(body, "hello");
+    }
+
+    /// Compresses `payload` with brotli, using `dictionary` as the custom dictionary.
+    #[cfg(feature = "brotli")]
+    fn brotli_with_dictionary(payload: &[u8], dictionary: &[u8]) -> Vec<u8> {
+        let mut compressed_payload = Vec::new();
+        brotli::enc::BrotliCompressCustomIoCustomDict(
+            &mut brotli::IoReaderWrapper(&mut &payload[..]),
+            &mut brotli::IoWriterWrapper(&mut compressed_payload),
+            &mut [0; 4096],
+            &mut [0; 4096],
+            &brotli::enc::BrotliEncoderParams::default(),
+            brotli::enc::StandardAlloc::default(),
+            &mut |_, _, _, _| (),
+            dictionary,
+            std::io::Error::from(std::io::ErrorKind::UnexpectedEof),
+        )
+        .unwrap();
+        compressed_payload
+    }
+
+    #[cfg(feature = "brotli")]
+    #[tokio::test]
+    async fn test_decompress_brotli_with_dictionary() {
+        let dictionary: &[u8] = br#"{"service": "checkout", "level": "info", "message": ""#;
+        BROTLI_DICTIONARIES.register("test-dictionary".to_string(), dictionary.into());
+
+        let payload: &[u8] = br#"{"service": "checkout", "level": "info", "message": "hello"}"#;
+        let body = warp::test::request()
+            .header("content-encoding", "br")
+            .header(BROTLI_DICTIONARY_ID_HEADER, "test-dictionary")
+            .body(brotli_with_dictionary(payload, dictionary))
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap();
+        assert_eq!(body, payload);
+    }
+
+    #[cfg(feature = "brotli")]
+    #[tokio::test]
+    async fn test_decompress_brotli_unknown_dictionary() {
+        let rejection = warp::test::request()
+            .header("content-encoding", "br")
+            .header(BROTLI_DICTIONARY_ID_HEADER, "missing-dictionary")
+            .body(brotli_with_dictionary(b"hello", b"hello"))
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap_err();
+        let error = rejection.find::<UnknownBrotliDictionary>().unwrap();
+        assert_eq!(error.dictionary_id, "missing-dictionary");
+    }
+
+    #[test]
+    fn test_brotli_dictionaries_load_from_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("logs-v1"), b"dictionary").unwrap();
+        std::fs::create_dir(temp_dir.path().join("subdir")).unwrap();
+
+        let brotli_dictionaries = BrotliDictionaries::load_from_dir(temp_dir.path()).unwrap();
+        assert_eq!(
+            brotli_dictionaries.get("logs-v1").as_deref(),
+            Some(&b"dictionary"[..])
+        );
+        assert!(brotli_dictionaries.get("subdir").is_none());
+        assert!(brotli_dictionaries.get("logs-v2").is_none());
+    }
+
+    #[test]
+    fn test_load_brotli_dictionaries_from_ingest_api_config() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("config-dictionary"), b"dictionary").unwrap();
+
+        let ingest_api_config = IngestApiConfig {
+            brotli_dictionaries_dir: Some(temp_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        load_brotli_dictionaries(&ingest_api_config);
+        assert_eq!(
+            BROTLI_DICTIONARIES.get("config-dictionary").as_deref(),
+            Some(&b"dictionary"[..])
+        );
+    }
+
+    #[cfg(feature = "zstd")]
+    #[tokio::test]
+    async fn test_decompress_zstd() {
+        let body = warp::test::request()
+            .header("content-encoding", "zstd")
+            .body(zstd::encode_all(&b"hello"[..], 0).unwrap())
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap();
+        assert_eq!(body, "hello");
+    }
+
+    #[cfg(feature = "zstd")]
+    fn frame(payload: &[u8]) -> Vec<u8> {
+        CompressionAlgorithm::FramedZstd.compress(payload).unwrap()
+    }
+
+    #[cfg(feature = "zstd")]
+    #[tokio::test]
+    async fn test_decompress_framed_zstd() {
+        let framed_body = [frame(b"hello"), frame(b""), frame(b", world")].concat();
+        let body = warp::test::request()
+            .header("content-encoding", "qw-framed-zstd")
+            .body(framed_body)
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap();
+        assert_eq!(body, "hello, world");
+    }
+
+    #[cfg(feature = "zstd")]
+    #[tokio::test]
+    async fn test_decompress_framed_zstd_truncated_frame() {
+        let mut framed_body = [frame(b"hello"), frame(b", world")].concat();
+        framed_body.pop();
+
+        let rejection = warp::test::request()
+            .header("content-encoding", "qw-framed-zstd")
+            .body(framed_body)
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap_err();
+        let error = rejection.find::<MalformedCompressedBody>().unwrap();
+        assert_eq!(error.algorithm, CompressionAlgorithm::FramedZstd);
+        assert!(error.message.contains("truncated frame"));
+
+        let truncated_length_prefix = [frame(b"hello"), vec![1, 0]].concat();
+        let rejection = warp::test::request()
+            .header("content-encoding", "qw-framed-zstd")
+            .body(truncated_length_prefix)
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap_err();
+        let error = rejection.find::<MalformedCompressedBody>().unwrap();
+        assert!(error.message.contains("truncated frame length prefix"));
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_decompress_chunks_framed_zstd_spans_frames() {
+        // A limit enforced by the chunk callback applies to the output of all the frames.
+        let framed_body = [frame(b"hello"), frame(b", world")].concat();
+        let mut num_decompressed_bytes = 0;
+
+        let error = decompress_chunks(
+            &[CompressionAlgorithm::FramedZstd],
+            None,
+            &framed_body,
+            None,
+            4,
+            |chunk| {
+                num_decompressed_bytes += chunk.len();
+
+                if num_decompressed_bytes > 8 {
+                    return Err("body too large");
+                }
+                Ok(())
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            DecompressChunksError::Aborted("body too large")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_decompress_unknown_algorithm() {
+        let rejection = warp::test::request()
+            .header("content-encoding", "compress")
+            .body("hello")
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap_err();
+        let error = rejection.find::<UnsupportedCompressionAlgorithm>().unwrap();
+        let UnsupportedCompressionAlgorithm::Unknown(content_encoding) = error else {
+            panic!("expected unknown compression algorithm, got {error:?}");
+        };
+        assert_eq!(content_encoding, "compress");
+        assert!(error.to_string().contains("is unknown"));
+    }
+
+    #[tokio::test]
+    async fn test_decompress_wildcard_content_encoding() {
+        let rejection = warp::test::request()
+            .header("content-encoding", "*")
+            .body("hello")
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap_err();
+        let error = rejection.find::<UnsupportedCompressionAlgorithm>().unwrap();
+        let UnsupportedCompressionAlgorithm::Unknown(content_encoding) = error else {
+            panic!("expected unknown compression algorithm, got {error:?}");
+        };
+        assert_eq!(content_encoding, "*");
+    }
+
+    #[tokio::test]
+    async fn test_decompress_empty_body() {
+        for algorithm in CompressionAlgorithm::ALL {
+            let body = warp::test::request()
+                .header("content-encoding", algorithm.as_str())
+                .body("")
+                .filter(&decompress(DecompressOptions::default()))
+                .await
+                .unwrap();
+            assert!(body.is_empty(), "{algorithm} body should be empty");
+        }
+        for content_encoding in ["identity", "gzip, br", "compress"] {
+            let body = warp::test::request()
+                .header("content-encoding", content_encoding)
+                .body("")
+                .filter(&decompress(DecompressOptions::default()))
+                .await
+                .unwrap();
+            assert!(body.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decompress_validates_utf8() {
+        let validate_options = DecompressOptions {
+            validate_utf8: true,
+            ..Default::default()
+        };
+        let rejection = warp::test::request()
+            .body(&b"hello \xff"[..])
+            .filter(&decompress(validate_options))
+            .await
+            .unwrap_err();
+        assert!(rejection.find::<InvalidUtf8>().is_some());
+
+        let body = warp::test::request()
+            .header("content-type", "application/x-ndjson; charset=iso-8859-1")
+            .body(&b"caf\xe9"[..])
+            .filter(&decompress(validate_options))
+            .await
+            .unwrap();
+        assert_eq!(body, "café");
+
+        // The parts of multipart bodies are validated individually.
+        let body = warp::test::request()
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(&b"hello \xff"[..])
+            .filter(&decompress(validate_options))
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"hello \xff");
+
+        // The validation is opt-in.
+        let body = warp::test::request()
+            .body(&b"hello \xff"[..])
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"hello \xff");
+    }
+
+    #[tokio::test]
+    async fn test_decompress_detects_undeclared_compression() {
+        let detect_options = DecompressOptions {
+            detect_undeclared_compression: true,
+            ..Default::default()
+        };
+        let rejection = warp::test::request()
+            .body(gzip(b"hello"))
+            .filter(&decompress_raw_body(raw_body(), detect_options))
+            .await
+            .unwrap_err();
+        let error = rejection.find::<UndeclaredCompressedBody>().unwrap();
+        assert_eq!(error.algorithm, CompressionAlgorithm::Gzip);
+        assert!(error.to_string().contains("set `content-encoding: gzip`"));
+
+        // The check is opt-in.
+        let body = warp::test::request()
+            .body(gzip(b"hello"))
+            .filter(&decompress_raw_body(
+                raw_body(),
+                DecompressOptions::default(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(body, gzip(b"hello"));
+
+        // Plain bodies and bodies with a declared content-encoding go through.
+        let body = warp::test::request()
+            .body(r#"{"message": "hello"}"#)
+            .filter(&decompress_raw_body(raw_body(), detect_options))
+            .await
+            .unwrap();
+        assert_eq!(body, r#"{"message": "hello"}"#);
+
+        let body = warp::test::request()
+            .header("content-encoding", "gzip")
+            .body(gzip(b"hello"))
+            .filter(&decompress_raw_body(raw_body(), detect_options))
+            .await
+            .unwrap();
+        assert_eq!(body, "hello");
+    }
+
+    #[test]
+    fn test_detect_undeclared_compression() {
+        assert_eq!(
+            detect_undeclared_compression(&gzip(b"hello")),
+            Some(CompressionAlgorithm::Gzip)
+        );
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello").unwrap();
+        assert_eq!(
+            detect_undeclared_compression(&encoder.finish().unwrap()),
+            Some(CompressionAlgorithm::Deflate)
+        );
+        assert_eq!(
+            detect_undeclared_compression(&[0x28, 0xb5, 0x2f, 0xfd, 0x00]),
+            Some(CompressionAlgorithm::Zstd)
+        );
+        assert_eq!(detect_undeclared_compression(b""), None);
+        assert_eq!(detect_undeclared_compression(b"x"), None);
+        assert_eq!(detect_undeclared_compression(b"xml"), None);
+        assert_eq!(detect_undeclared_compression(b"{\"id\": 1}"), None);
+    }
+
+    #[test]
+    fn test_decompress_options_from_ingest_api_config() {
+        let options = DecompressOptions::from_ingest_api_config(&IngestApiConfig::default());
+        assert!(!options.lenient_unknown_encoding);
+        assert!(options.max_decompressed_num_bytes_opt.is_none());
+        assert!(!options.detect_undeclared_compression);
+        assert!(!options.validate_utf8);
+
+        let ingest_api_config = IngestApiConfig {
+            lenient_unknown_content_encoding: true,
+            decompressed_content_length_limit: Some(ByteSize::mb(1)),
+            detect_undeclared_compression: true,
+            validate_utf8_request_bodies: true,
+            ..Default::default()
+        };
+        let options = DecompressOptions::from_ingest_api_config(&ingest_api_config);
+        assert!(options.lenient_unknown_encoding);
+        assert_eq!(options.max_decompressed_num_bytes_opt, Some(1_000_000));
+        assert!(options.detect_undeclared_compression);
+        assert!(options.validate_utf8);
+    }
+
+    #[tokio::test]
+    async fn test_decompress_lenient_unknown_encoding() {
+        let lenient_options = DecompressOptions {
+            lenient_unknown_encoding: true,
+            ..Default::default()
+        };
+        let strict_rejection = warp::test::request()
+            .header("content-encoding", "x-proxy-tag")
+            .body("hello")
+            .filter(&decompress_raw_body(
+                raw_body(),
+                DecompressOptions::default(),
+            ))
+            .await
+            .unwrap_err();
+        let error = strict_rejection
+            .find::<UnsupportedCompressionAlgorithm>()
+            .unwrap();
+        let UnsupportedCompressionAlgorithm::Unknown(content_encoding) = error else {
+            panic!("expected unknown compression algorithm, got {error:?}");
+        };
+        assert_eq!(content_encoding, "x-proxy-tag");
+
+        let body = warp::test::request()
+            .header("content-encoding", "x-proxy-tag")
+            .body("hello")
+            .filter(&decompress_raw_body(raw_body(), lenient_options))
+            .await
+            .unwrap();
+        assert_eq!(body, "hello");
+
+        // Known codings are still undone around the unknown ones.
+        let body = warp::test::request()
+            .header("content-encoding", "gzip, x-proxy-tag")
+            .body(gzip(b"hello"))
+            .filter(&decompress_raw_body(raw_body(), lenient_options))
+            .await
+            .unwrap();
+        assert_eq!(body, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_decompress_stacked_content_encodings() {
+        let payload = b"hello";
+        let compressed_payload = CompressionAlgorithm::Deflate
+            .compress(&gzip(payload))
+            .unwrap();
+        let body = warp::test::request()
+            .header("content-encoding", "gzip, identity, deflate")
+            .body(compressed_payload)
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap();
+        assert_eq!(body, Bytes::from_static(payload));
+    }
+
+    #[tokio::test]
+    async fn test_decompress_content_encoding_split_across_header_lines() {
+        let mut headers = HeaderMap::new();
+        headers.append(CONTENT_ENCODING, "gzip".parse().unwrap());
+        headers.append(CONTENT_ENCODING, "deflate".parse().unwrap());
+
+        let content_encoding_opt = content_encoding_from_headers(&headers);
+        assert_eq!(content_encoding_opt.as_deref(), Some("gzip, deflate"));
+
+        let payload = b"hello";
+        let compressed_payload = CompressionAlgorithm::Deflate
+            .compress(&gzip(payload))
+            .unwrap();
+        let body = decompress_body(
+            content_encoding_opt,
+            None,
+            Bytes::from(compressed_payload),
+            DecompressOptions::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(body, Bytes::from_static(payload));
+
+        assert!(content_encoding_from_headers(&HeaderMap::new()).is_none());
+    }
+
+    #[cfg(not(feature = "brotli"))]
+    #[tokio::test]
+    async fn test_decompress_disabled_brotli() {
+        let rejection = warp::test::request()
+            .header("content-encoding", "br")
+            .body("hello")
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap_err();
+        let error = rejection.find::<UnsupportedCompressionAlgorithm>().unwrap();
+        assert!(matches!(
+            error,
+            UnsupportedCompressionAlgorithm::Disabled(CompressionAlgorithm::Brotli)
+        ));
+        assert!(error.to_string().contains("is not enabled in this build"));
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[tokio::test]
+    async fn test_decompress_disabled_zstd() {
+        let rejection = warp::test::request()
+            .header("content-encoding", "zstd")
+            .body("hello")
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap_err();
+        let error = rejection.find::<UnsupportedCompressionAlgorithm>().unwrap();
+        assert!(matches!(
+            error,
+            UnsupportedCompressionAlgorithm::Disabled(CompressionAlgorithm::Zstd)
+        ));
+    }
+
+    #[test]
+    fn test_enabled_content_encodings() {
+        let enabled_content_encodings = enabled_content_encodings();
+        assert!(enabled_content_encodings.contains("`gzip`"));
+        assert!(enabled_content_encodings.contains("`deflate`"));
+        assert_eq!(
+            enabled_content_encodings.contains("`br`"),
+            cfg!(feature = "brotli")
+        );
+        assert_eq!(
+            enabled_content_encodings.contains("`zstd`"),
+            cfg!(feature = "zstd")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decompress_malformed_body() {
+        let rejection = warp::test::request()
+            .header("content-encoding", "gzip")
+            .body("hello")
+            .filter(&decompress(DecompressOptions::default()))
+            .await
+            .unwrap_err();
+        let error = rejection.find::<MalformedCompressedBody>().unwrap();
+        assert_eq!(error.algorithm, CompressionAlgorithm::Gzip);
+    }
+
+    #[tokio::test]
+    async fn test_raw_body_with_limit() {
+        let body = warp::test::request()
+            .body("hello")
+            .filter(&raw_body_with_limit(5))
+            .await
+            .unwrap();
+        assert_eq!(body, "hello");
+
+        let rejection = warp::test::request()
+            .body("hello")
+            .filter(&raw_body_with_limit(4))
+            .await
+            .unwrap_err();
+        let error = rejection.find::<PayloadTooLarge>().unwrap();
+        assert_eq!(error.limit_num_bytes, 4);
+    }
+
+    #[tokio::test]
+    async fn test_decompress_with_limit_applies_to_compressed_body() {
+        let body = gzip(b"hello");
+        let body_len = body.len() as u64;
+        let decompressed_body = warp::test::request()
+            .header("content-encoding", "gzip")
+            .body(body.clone())
+            .filter(&decompress_with_limit(
+                body_len,
+                DecompressOptions::default(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(decompressed_body, "hello");
+
+        let rejection = warp::test::request()
+            .header("content-encoding", "gzip")
+            .body(body)
+            .filter(&decompress_with_limit(
+                body_len - 1,
+                DecompressOptions::default(),
+            ))
+            .await
+            .unwrap_err();
+        assert!(rejection.find::<PayloadTooLarge>().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_read_body_with_limit_rejects_chunked_body_as_soon_as_limit_is_exceeded() {
+        let chunks = ["hello", " world", ", more bytes"]
+            .into_iter()
+            .map(|chunk| Ok::<_, Infallible>(Bytes::from(chunk)));
+        // The body never ends: it must be rejected on the chunk that crosses the limit, without
+        // waiting for the rest of it.
+        let body_stream = futures::stream::iter(chunks).chain(futures::stream::pending());
+        let rejection = tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            read_body_with_limit(16, body_stream),
+        )
+        .await
+        .expect("oversized body should be rejected before it is fully received")
+        .unwrap_err();
+        let error = rejection.find::<PayloadTooLarge>().unwrap();
+        assert_eq!(error.limit_num_bytes, 16);
+
+        let chunks = ["hello", " world"]
+            .into_iter()
+            .map(|chunk| Ok::<_, Infallible>(Bytes::from(chunk)));
+        let body = read_body_with_limit(16, futures::stream::iter(chunks))
+            .await
+            .unwrap();
+        assert_eq!(body, "hello world");
+    }
+
+    fn decompress_chunks_for_test(
+        algorithms: &[CompressionAlgorithm],
+        body: &[u8],
+        chunk_size: usize,
+    ) -> Vec<Vec<u8>> {
+        let mut chunks = Vec::new();
+        decompress_chunks(algorithms, None, body, None, chunk_size, |chunk| {
+            chunks.push(chunk.to_vec());
+            Ok::<(), Infallible>(())
+        })
+        .unwrap();
+        chunks
+    }
+
+    #[test]
+    fn test_compress_decompress_roundtrip() {
+        for algorithm in CompressionAlgorithm::ALL {
+            if !algorithm.is_enabled() {
+                continue;
+            }
+            let compressed_body = algorithm.compress(b"hello").unwrap();
+            let chunks = decompress_chunks_for_test(&[algorithm], &compressed_body, 1024);
+            assert_eq!(chunks.concat(), b"hello");
+        }
+    }
+
+    #[test]
+    fn test_decompress_chunks() {
+        let payload = b"{\"id\": 1}\n{\"id\": 2}\n{\"id\": 3}\n";
+
+        let chunks = decompress_chunks_for_test(&[], payload, 4);
+        assert_eq!(chunks.len(), 8);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 4));
+        assert_eq!(chunks.concat(), payload);
+
+        let chunks = decompress_chunks_for_test(&[CompressionAlgorithm::Gzip], &gzip(payload), 4);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 4));
+        assert_eq!(chunks.concat(), payload);
+    }
+
+    #[test]
+    fn test_decompress_chunks_aborts_on_callback_error() {
+        // Counts NDJSON lines as they are decompressed and gives up past the second doc.
+        let payload = b"{\"id\": 1}\n{\"id\": 2}\n{\"id\": 3}\n{\"id\": 4}\n";
+        let mut num_docs = 0;
+        let mut num_decompressed_bytes = 0;
+
+        let error = decompress_chunks(
+            &[CompressionAlgorithm::Gzip],
+            None,
+            &gzip(payload),
+            None,
+            8,
+            |chunk| {
+                num_decompressed_bytes += chunk.len();
+                num_docs += chunk.iter().filter(|byte| **byte == b'\n').count();
+
+                if num_docs > 2 {
+                    return Err("too many docs");
+                }
+                Ok(())
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            DecompressChunksError::Aborted("too many docs")
+        ));
+        assert_eq!(num_docs, 3);
+        assert!(num_decompressed_bytes < payload.len());
+    }
+
+    #[test]
+    fn test_decompress_chunks_malformed_body() {
+        let error = decompress_chunks(
+            &[CompressionAlgorithm::Gzip],
+            None,
+            b"hello",
+            None,
+            8,
+            |_chunk| Ok::<(), Infallible>(()),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            DecompressChunksError::Decompression(DecompressionError::MalformedCompressedBody(_))
+        ));
+    }
+
+    #[test]
+    fn test_decompress_chunks_nested_bomb() {
+        // The inner layer expands way past the limit, while the outer layer is small.
+        let bomb = gzip(&vec![0; 8 * 1024 * 1024]);
+        let nested_bomb = gzip(&bomb);
+        let max_decompressed_num_bytes = 1024 * 1024;
+        let mut num_decompressed_bytes = 0;
+
+        let error = decompress_chunks(
+            &[CompressionAlgorithm::Gzip, CompressionAlgorithm::Gzip],
+            None,
+            &nested_bomb,
+            Some(max_decompressed_num_bytes),
+            DECOMPRESSION_CHUNK_SIZE,
+            |chunk| {
+                num_decompressed_bytes += chunk.len() as u64;
+                Ok::<(), Infallible>(())
+            },
+        )
+        .unwrap_err();
+        let DecompressChunksError::Decompression(DecompressionError::DecompressedBodyTooLarge(
+            error,
+        )) = error
+        else {
+            panic!("expected `DecompressedBodyTooLarge` error, got `{error:?}`");
+        };
+        assert_eq!(error.limit_num_bytes, max_decompressed_num_bytes);
+        assert!(num_decompressed_bytes <= max_decompressed_num_bytes);
+
+        let mut decompressed_body = Vec::new();
+        decompress_chunks(
+            &[CompressionAlgorithm::Gzip, CompressionAlgorithm::Gzip],
+            None,
+            &gzip(&gzip(b"hello")),
+            Some(5),
+            DECOMPRESSION_CHUNK_SIZE,
+            |chunk| {
+                decompressed_body.extend_from_slice(chunk);
+                Ok::<(), Infallible>(())
+            },
+        )
+        .unwrap();
+        assert_eq!(decompressed_body, b"hello");
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_decompress_chunks_limits_intermediate_output() {
+        // A zstd skippable frame carries data that the zstd decoder drops: the output of the gzip
+        // decoder is large, while the final output is tiny.
+        let skippable_frame_len = 2 * 1024 * 1024;
+        let mut zstd_body = Vec::new();
+        zstd_body.extend_from_slice(&0x184D2A50u32.to_le_bytes());
+        zstd_body.extend_from_slice(&(skippable_frame_len as u32).to_le_bytes());
+        zstd_body.resize(zstd_body.len() + skippable_frame_len, 0);
+        zstd_body.extend_from_slice(&zstd::encode_all(&b"hello"[..], 0).unwrap());
+        let body = gzip(&zstd_body);
+
+        let mut decompressed_body = Vec::new();
+        decompress_chunks(
+            &[CompressionAlgorithm::Zstd, CompressionAlgorithm::Gzip],
+            None,
+            &body,
+            None,
+            DECOMPRESSION_CHUNK_SIZE,
+            |chunk| {
+                decompressed_body.extend_from_slice(chunk);
+                Ok::<(), Infallible>(())
+            },
+        )
+        .unwrap();
+        assert_eq!(decompressed_body, b"hello");
+
+        let error = decompress_chunks(
+            &[CompressionAlgorithm::Zstd, CompressionAlgorithm::Gzip],
+            None,
+            &body,
+            Some(1024 * 1024),
+            DECOMPRESSION_CHUNK_SIZE,
+            |_chunk| Ok::<(), Infallible>(()),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            DecompressChunksError::Decompression(DecompressionError::DecompressedBodyTooLarge(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_decompress_rejects_decompressed_body_too_large() {
+        let options = DecompressOptions {
+            max_decompressed_num_bytes_opt: Some(1024),
+            ..Default::default()
+        };
+        let rejection = warp::test::request()
+            .header("content-encoding", "gzip, gzip")
+            .body(gzip(&gzip(&[0; 2048])))
+            .filter(&decompress_raw_body(raw_body(), options))
+            .await
+            .unwrap_err();
+        let error = rejection.find::<DecompressedBodyTooLarge>().unwrap();
+        assert_eq!(error.limit_num_bytes, 1024);
+
+        let body = warp::test::request()
+            .header("content-encoding", "gzip, gzip")
+            .body(gzip(&gzip(&[0; 1024])))
+            .filter(&decompress_raw_body(raw_body(), options))
+            .await
+            .unwrap();
+        assert_eq!(body.len(), 1024);
+    }
+
+    #[tokio::test]
+    async fn test_raw_body_ignores_content_encoding() {
+        let compressed_body = gzip(b"hello");
+        let body = warp::test::request()
+            .header("content-encoding", "gzip")
+            .body(compressed_body.clone())
+            .filter(&raw_body())
+            .await
+            .unwrap();
+        assert_eq!(body, compressed_body);
+    }
+
+    #[test]
+    fn test_charset_from_content_type() {
+        assert_eq!(Charset::from_content_type("application/json"), None);
+        assert_eq!(
+            Charset::from_content_type("application/json; charset=UTF-8"),
+            Some(Charset::Utf8)
+        );
+        assert_eq!(
+            Charset::from_content_type("text/plain;charset=\"ISO-8859-1\""),
+            Some(Charset::Latin1)
+        );
+        assert_eq!(
+            Charset::from_content_type("text/plain; charset=shift_jis"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_to_utf8_rejects_invalid_utf8() {
+        let body = Bytes::from_static(b"{\"name\": \"caf\xe9\"}");
+        let error = to_utf8(body.clone(), None).unwrap_err();
+        assert_eq!(error.offset, 13);
+        assert_eq!(
+            error.to_string(),
+            "request body is not valid UTF-8: invalid byte sequence at offset 13"
+        );
+        let error = to_utf8(body, Some(Charset::Utf8)).unwrap_err();
+        assert_eq!(error.offset, 13);
+
+        let valid_body = Bytes::from("{\"name\": \"café\"}");
+        assert_eq!(to_utf8(valid_body.clone(), None).unwrap(), valid_body);
+    }
+
+    #[test]
+    fn test_to_utf8_transcodes_latin1() {
+        let body = Bytes::from_static(b"{\"name\": \"caf\xe9\"}");
+        let transcoded_body = to_utf8(body, Some(Charset::Latin1)).unwrap();
+        assert_eq!(transcoded_body, Bytes::from("{\"name\": \"café\"}"));
+    }
+
+    /// Log lines written by a `tracing` subscriber, without ANSI escape codes.
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl CapturedLogs {
+        fn lines(&self) -> Vec<String> {
+            let logs = self.0.lock().unwrap();
+            String::from_utf8_lossy(&logs)
+                .lines()
+                .map(|line| line.to_string())
+                .collect()
+        }
+    }
+
+    impl Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decompress_body_logs_body_sizes() {
+        let captured_logs = CapturedLogs::default();
+        let make_writer = {
+            let captured_logs = captured_logs.clone();
+            move || captured_logs.clone()
+        };
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(make_writer)
+            .finish();
+        let _subscriber_guard = tracing::subscriber::set_default(subscriber);
+
+        let payload = b"hello hello hello hello hello hello hello hello";
+        let compressed_payload = gzip(payload);
+        decompress_body(
+            Some("gzip".to_string()),
+            None,
+            Bytes::from(compressed_payload.clone()),
+            DecompressOptions::default(),
+        )
+        .await
+        .unwrap();
+        decompress_body(
+            None,
+            None,
+            Bytes::from_static(payload),
+            DecompressOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        let log_lines = captured_logs.lines();
+        assert_eq!(log_lines.len(), 2);
+
+        let expected_ratio = payload.len() as f64 / compressed_payload.len() as f64;
+        assert!(log_lines[0].contains("received request body"));
+        assert!(log_lines[0].contains("content_encoding=\"gzip\""));
+        assert!(log_lines[0].contains(&format!(
+            "compressed_num_bytes={}",
+            compressed_payload.len()
+        )));
+        assert!(log_lines[0].contains(&format!("decompressed_num_bytes={}", payload.len())));
+        assert!(log_lines[0].contains(&format!("compression_ratio={expected_ratio:?}")));
+
+        assert!(log_lines[1].contains("content_encoding=\"identity\""));
+        assert!(log_lines[1].contains(&format!("compressed_num_bytes={}", payload.len())));
+        assert!(!log_lines[1].contains("decompressed_num_bytes"));
+        assert!(!log_lines[1].contains("compression_ratio"));
+    }
+}
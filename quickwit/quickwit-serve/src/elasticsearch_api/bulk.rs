@@ -31,6 +31,7 @@ use quickwit_proto::types::IndexId;
 use warp::{Filter, Rejection};
 
 use super::bulk_v2::{elastic_bulk_ingest_v2, ElasticBulkResponse};
+use crate::decompression::DecompressOptions;
 use crate::elasticsearch_api::filter::{elastic_bulk_filter, elastic_index_bulk_filter};
 use crate::elasticsearch_api::make_elastic_api_response;
 use crate::elasticsearch_api::model::{BulkAction, ElasticBulkOptions, ElasticsearchError};
@@ -42,8 +43,9 @@ use crate::with_arg;
 pub fn es_compat_bulk_handler(
     ingest_service: IngestServiceClient,
     ingest_router: IngestRouterServiceClient,
+    decompress_options: DecompressOptions,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    elastic_bulk_filter()
+    elastic_bulk_filter(decompress_options)
         .and(with_arg(ingest_service))
         .and(with_arg(ingest_router))
         .then(|body, bulk_options, ingest_service, ingest_router| {
@@ -57,8 +59,9 @@ pub fn es_compat_bulk_handler(
 pub fn es_compat_index_bulk_handler(
     ingest_service: IngestServiceClient,
     ingest_router: IngestRouterServiceClient,
+    decompress_options: DecompressOptions,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    elastic_index_bulk_filter()
+    elastic_index_bulk_filter(decompress_options)
         .and(with_arg(ingest_service))
         .and(with_arg(ingest_router))
         .then(
@@ -356,6 +359,7 @@ mod tests {
             .ask_for_res(SuggestTruncateRequest {
                 index_id: "my-index-1".to_string(),
                 up_to_position_included: 1,
+                published_split_ids: Vec::new(),
             })
             .await
             .unwrap();
@@ -365,6 +369,7 @@ mod tests {
             .ask_for_res(SuggestTruncateRequest {
                 index_id: "my-index-2".to_string(),
                 up_to_position_included: 0,
+                published_split_ids: Vec::new(),
             })
             .await
             .unwrap();
@@ -440,6 +445,7 @@ mod tests {
             .ask_for_res(SuggestTruncateRequest {
                 index_id: "my-index-1".to_string(),
                 up_to_position_included: 1,
+                published_split_ids: Vec::new(),
             })
             .await
             .unwrap();
@@ -449,6 +455,7 @@ mod tests {
             .ask_for_res(SuggestTruncateRequest {
                 index_id: "my-index-2".to_string(),
                 up_to_position_included: 0,
+                published_split_ids: Vec::new(),
             })
             .await
             .unwrap();
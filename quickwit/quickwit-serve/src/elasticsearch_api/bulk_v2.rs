@@ -118,6 +118,7 @@ mod tests {
     use warp::{Filter, Rejection, Reply};
 
     use super::*;
+    use crate::decompression::DecompressOptions;
     use crate::elasticsearch_api::bulk_v2::ElasticBulkResponse;
     use crate::elasticsearch_api::filter::elastic_bulk_filter;
     use crate::elasticsearch_api::make_elastic_api_response;
@@ -128,7 +129,7 @@ mod tests {
     fn es_compat_bulk_handler_v2(
         ingest_router: IngestRouterServiceClient,
     ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
-        elastic_bulk_filter()
+        elastic_bulk_filter(DecompressOptions::default())
             .and(with_arg(ingest_router))
             .then(|body, bulk_options, ingest_router| {
                 elastic_bulk_ingest_v2(None, body, bulk_options, ingest_router)
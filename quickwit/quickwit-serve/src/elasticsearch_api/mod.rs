@@ -45,6 +45,7 @@ use self::rest_handler::{
     es_compat_index_count_handler, es_compat_index_field_capabilities_handler,
     es_compat_index_stats_handler, es_compat_stats_handler,
 };
+use crate::decompression::DecompressOptions;
 use crate::elasticsearch_api::model::ElasticsearchError;
 use crate::rest_api_response::RestApiResponse;
 use crate::{BodyFormat, BuildInfo};
@@ -60,6 +61,8 @@ pub fn elastic_api_handlers(
     ingest_router: IngestRouterServiceClient,
     metastore: MetastoreServiceClient,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    let decompress_options =
+        DecompressOptions::from_ingest_api_config(&node_config.ingest_api_config);
     es_compat_cluster_info_handler(node_config, BuildInfo::get())
         .or(es_compat_search_handler(search_service.clone()))
         .or(es_compat_index_search_handler(search_service.clone()))
@@ -72,8 +75,13 @@ pub fn elastic_api_handlers(
         .or(es_compat_bulk_handler(
             ingest_service.clone(),
             ingest_router.clone(),
+            decompress_options,
+        ))
+        .or(es_compat_index_bulk_handler(
+            ingest_service,
+            ingest_router,
+            decompress_options,
         ))
-        .or(es_compat_index_bulk_handler(ingest_service, ingest_router))
         .or(es_compat_index_stats_handler(metastore.clone()))
         .or(es_compat_stats_handler(metastore.clone()))
         .or(es_compat_index_cat_indices_handler(metastore.clone()))
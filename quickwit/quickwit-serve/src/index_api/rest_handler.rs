@@ -19,6 +19,7 @@
 
 use std::sync::Arc;
 
+use anyhow::{anyhow, bail};
 use bytes::Bytes;
 use quickwit_common::uri::Uri;
 use quickwit_config::{
@@ -32,9 +33,10 @@ use quickwit_metastore::{
     ListSplitsRequestExt, MetastoreServiceStreamSplitsExt, Split, SplitInfo, SplitState,
 };
 use quickwit_proto::metastore::{
-    DeleteSourceRequest, EntityKind, IndexMetadataRequest, ListIndexesMetadataRequest,
-    ListSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError, MetastoreResult,
-    MetastoreService, MetastoreServiceClient, ResetSourceCheckpointRequest, ToggleSourceRequest,
+    CreateIndexMode, DeleteSourceRequest, EntityKind, IndexMetadataRequest,
+    ListIndexesMetadataRequest, ListSplitsRequest, MarkSplitsForDeletionRequest, MetastoreError,
+    MetastoreResult, MetastoreService, MetastoreServiceClient, ResetSourceCheckpointRequest,
+    ToggleSourceRequest,
 };
 use quickwit_proto::types::IndexUid;
 use serde::de::DeserializeOwned;
@@ -51,6 +53,7 @@ use crate::with_arg;
 #[openapi(
     paths(
         create_index,
+        put_index,
         clear_index,
         delete_index,
         get_indexes_metadatas,
@@ -73,7 +76,11 @@ pub fn index_management_handlers(
     // Indexes handlers.
     get_index_metadata_handler(index_service.metastore())
         .or(get_indexes_metadatas_handler(index_service.metastore()))
-        .or(create_index_handler(index_service.clone(), node_config))
+        .or(create_index_handler(
+            index_service.clone(),
+            node_config.clone(),
+        ))
+        .or(put_index_handler(index_service.clone(), node_config))
         .or(clear_index_handler(index_service.clone()))
         .or(delete_index_handler(index_service.clone()))
         // Splits handlers
@@ -421,8 +428,25 @@ async fn get_indexes_metadatas(
 #[derive(Deserialize, utoipa::IntoParams, utoipa::ToSchema)]
 #[into_params(parameter_in = Query)]
 struct CreateIndexQueryParams {
+    /// Deletes the existing index, if any, before creating the new one.
     #[serde(default)]
     overwrite: bool,
+    /// Returns the existing index, if any, instead of failing.
+    #[serde(default)]
+    if_not_exists: bool,
+}
+
+impl CreateIndexQueryParams {
+    fn create_index_mode(&self) -> anyhow::Result<CreateIndexMode> {
+        match (self.overwrite, self.if_not_exists) {
+            (false, false) => Ok(CreateIndexMode::ErrorIfExists),
+            (false, true) => Ok(CreateIndexMode::IfNotExists),
+            (true, false) => Ok(CreateIndexMode::Overwrite),
+            (true, true) => {
+                bail!("parameters `overwrite` and `if_not_exists` are mutually exclusive")
+            }
+        }
+    }
 }
 
 fn create_index_handler(
@@ -469,9 +493,93 @@ async fn create_index(
         &node_config.default_index_root_uri,
     )
     .map_err(IndexServiceError::InvalidConfig)?;
-    info!(index_id = %index_config.index_id, overwrite = create_index_query_params.overwrite, "create-index");
+    let create_index_mode = create_index_query_params
+        .create_index_mode()
+        .map_err(IndexServiceError::InvalidConfig)?;
+    info!(index_id = %index_config.index_id, create_index_mode = ?create_index_mode, "create-index");
     index_service
-        .create_index(index_config, create_index_query_params.overwrite)
+        .create_index(index_config, create_index_mode)
+        .await
+}
+
+#[derive(Deserialize, utoipa::IntoParams, utoipa::ToSchema)]
+#[into_params(parameter_in = Query)]
+struct PutIndexQueryParams {
+    /// Tells what to do when the index already exists: `error_if_exists`, `if_not_exists`, or
+    /// `overwrite`.
+    #[serde(default)]
+    mode: CreateIndexMode,
+    /// Confirms that the existing index must be overwritten. Overwriting an index marks all its
+    /// splits for deletion, so this flag is required with `mode=overwrite`.
+    #[serde(default)]
+    confirm_overwrite: bool,
+}
+
+fn put_index_handler(
+    index_service: IndexService,
+    node_config: Arc<NodeConfig>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!("indexes" / String)
+        .and(warp::put())
+        .and(serde_qs::warp::query(serde_qs::Config::default()))
+        .and(extract_config_format())
+        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::filters::body::bytes())
+        .and(with_arg(index_service))
+        .and(with_arg(node_config))
+        .then(put_index)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[utoipa::path(
+    put,
+    tag = "Indexes",
+    path = "/indexes/{index_id}",
+    request_body = VersionedIndexConfig,
+    responses(
+        // We return `VersionedIndexMetadata` as it's the serialized model view.
+        (status = 200, description = "Successfully created or overwrote index.", body = VersionedIndexMetadata)
+    ),
+    params(
+        ("index_id" = String, Path, description = "The index ID to create."),
+        PutIndexQueryParams,
+    )
+)]
+/// Creates index, or handles the existing one according to `mode`.
+async fn put_index(
+    index_id: String,
+    put_index_query_params: PutIndexQueryParams,
+    config_format: ConfigFormat,
+    index_config_bytes: Bytes,
+    mut index_service: IndexService,
+    node_config: Arc<NodeConfig>,
+) -> Result<IndexMetadata, IndexServiceError> {
+    let index_config = quickwit_config::load_index_config_from_user_config(
+        config_format,
+        &index_config_bytes,
+        &node_config.default_index_root_uri,
+    )
+    .map_err(IndexServiceError::InvalidConfig)?;
+
+    if index_config.index_id != index_id {
+        return Err(IndexServiceError::InvalidConfig(anyhow!(
+            "index ID `{}` of the index config does not match index ID `{index_id}` of the path",
+            index_config.index_id
+        )));
+    }
+    let create_index_mode = put_index_query_params.mode;
+
+    if create_index_mode == CreateIndexMode::Overwrite && !put_index_query_params.confirm_overwrite
+    {
+        return Err(IndexServiceError::InvalidConfig(anyhow!(
+            "overwriting index `{index_id}` marks all its splits for deletion, set \
+             `confirm_overwrite=true` to proceed"
+        )));
+    }
+    info!(index_id = %index_id, create_index_mode = ?create_index_mode, "put-index");
+    index_service
+        .create_index(index_config, create_index_mode)
         .await
 }
 
@@ -1353,6 +1461,117 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_create_index_if_not_exists() {
+        let metastore = metastore_for_test();
+        let index_service = IndexService::new(metastore.clone(), StorageResolver::unconfigured());
+        let mut node_config = NodeConfig::for_test();
+        node_config.default_index_root_uri = Uri::for_test("file:///default-index-root-uri");
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(node_config));
+        let index_config_body = r#"{"version": "0.7", "index_id": "hdfs-logs", "doc_mapping": {"field_mappings":[{"name": "timestamp", "type": "i64", "fast": true, "indexed": true}]}}"#;
+        let mut index_uids = Vec::new();
+
+        for _ in 0..2 {
+            let resp = warp::test::request()
+                .path("/indexes?if_not_exists=true")
+                .method("POST")
+                .json(&true)
+                .body(index_config_body)
+                .reply(&index_management_handler)
+                .await;
+            assert_eq!(resp.status(), 200);
+            let resp_json: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+            index_uids.push(resp_json["index_uid"].clone());
+        }
+        assert_eq!(index_uids[0], index_uids[1]);
+
+        let resp = warp::test::request()
+            .path("/indexes?if_not_exists=true&overwrite=true")
+            .method("POST")
+            .json(&true)
+            .body(index_config_body)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_put_index() {
+        let metastore = metastore_for_test();
+        let index_service = IndexService::new(metastore.clone(), StorageResolver::unconfigured());
+        let mut node_config = NodeConfig::for_test();
+        node_config.default_index_root_uri = Uri::for_test("file:///default-index-root-uri");
+        let index_management_handler =
+            super::index_management_handlers(index_service, Arc::new(node_config))
+                .recover(recover_fn);
+        let index_config_body = r#"{"version": "0.7", "index_id": "hdfs-logs", "doc_mapping": {"field_mappings":[{"name": "timestamp", "type": "i64", "fast": true, "indexed": true}]}}"#;
+
+        let resp = warp::test::request()
+            .path("/indexes/hdfs-logs")
+            .method("PUT")
+            .json(&true)
+            .body(index_config_body)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+        let index_uid = resp_json["index_uid"].clone();
+
+        let resp = warp::test::request()
+            .path("/indexes/hdfs-logs?mode=error_if_exists")
+            .method("PUT")
+            .json(&true)
+            .body(index_config_body)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+
+        let resp = warp::test::request()
+            .path("/indexes/hdfs-logs?mode=if_not_exists")
+            .method("PUT")
+            .json(&true)
+            .body(index_config_body)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["index_uid"], index_uid);
+
+        // Overwriting an index requires a confirmation.
+        let resp = warp::test::request()
+            .path("/indexes/hdfs-logs?mode=overwrite")
+            .method("PUT")
+            .json(&true)
+            .body(index_config_body)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+        let body = std::str::from_utf8(resp.body()).unwrap();
+        assert!(body.contains("confirm_overwrite"));
+
+        let resp = warp::test::request()
+            .path("/indexes/hdfs-logs?mode=overwrite&confirm_overwrite=true")
+            .method("PUT")
+            .json(&true)
+            .body(index_config_body)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let resp_json: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(resp_json["index_uid"], index_uid);
+
+        // The index ID of the path must match the one of the index config.
+        let resp = warp::test::request()
+            .path("/indexes/other-logs?mode=if_not_exists")
+            .method("PUT")
+            .json(&true)
+            .body(index_config_body)
+            .reply(&index_management_handler)
+            .await;
+        assert_eq!(resp.status(), 400);
+    }
+
     #[tokio::test]
     async fn test_create_delete_index_and_source() {
         let mut metastore = metastore_for_test();
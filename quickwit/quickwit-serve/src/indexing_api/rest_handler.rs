@@ -17,18 +17,23 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::BTreeMap;
 use std::convert::Infallible;
+use std::time::Duration;
 
-use quickwit_actors::{AskError, Mailbox, Observe};
+use quickwit_actors::{ActorObservation, AskError, Mailbox, Observe, SpawnContext};
 use quickwit_indexing::actors::{IndexingService, IndexingServiceCounters};
 use warp::{Filter, Rejection};
 
 use crate::format::extract_format_from_qs;
-use crate::require;
 use crate::rest_api_response::into_rest_api_response;
+use crate::{require, with_arg};
+
+/// Maximum amount of time spent waiting for the observable state of an actor.
+const OBSERVE_ACTOR_TIMEOUT: Duration = Duration::from_secs(3);
 
 #[derive(utoipa::OpenApi)]
-#[openapi(paths(indexing_endpoint))]
+#[openapi(paths(indexing_endpoint, pipeline_state_endpoint))]
 pub struct IndexingApi;
 
 #[utoipa::path(
@@ -61,3 +66,94 @@ pub fn indexing_get_handler(
         .and(extract_format_from_qs())
         .map(into_rest_api_response)
 }
+
+#[utoipa::path(
+    get,
+    tag = "Indexing",
+    path = "/pipeline/state",
+    responses(
+        (status = 200, description = "Successfully observed the actors of the node.")
+    ),
+)]
+/// Observe Actors
+///
+/// Returns the latest observable state of each actor running on the node (publishers, merge
+/// planners, garbage collector, etc.), grouped by actor type. Actors that fail to report their
+/// state in time are listed with a `null` state.
+async fn pipeline_state_endpoint(
+    spawn_ctx: SpawnContext,
+) -> Result<BTreeMap<&'static str, Vec<ActorObservation>>, Infallible> {
+    let mut pipeline_state: BTreeMap<&'static str, Vec<ActorObservation>> = BTreeMap::new();
+
+    for actor_observation in spawn_ctx.observe(OBSERVE_ACTOR_TIMEOUT).await {
+        pipeline_state
+            .entry(actor_observation.type_name)
+            .or_default()
+            .push(actor_observation);
+    }
+    for actor_observations in pipeline_state.values_mut() {
+        actor_observations.sort_by(|left, right| left.instance_id.cmp(&right.instance_id));
+    }
+    Ok(pipeline_state)
+}
+
+fn pipeline_state_get_filter() -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::path!("pipeline" / "state").and(warp::get())
+}
+
+pub fn pipeline_state_get_handler(
+    spawn_ctx: SpawnContext,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    pipeline_state_get_filter()
+        .and(with_arg(spawn_ctx))
+        .then(pipeline_state_endpoint)
+        .and(extract_format_from_qs())
+        .map(into_rest_api_response)
+}
+
+#[cfg(test)]
+mod tests {
+    use quickwit_actors::Universe;
+    use quickwit_indexing::actors::{Publisher, PublisherType};
+    use quickwit_proto::metastore::MetastoreServiceClient;
+    use serde_json::Value as JsonValue;
+    use warp::Filter;
+
+    use super::*;
+    use crate::recover_fn;
+
+    #[tokio::test]
+    async fn test_pipeline_state_endpoint() {
+        let universe = Universe::with_accelerated_time();
+        let mock_metastore = MetastoreServiceClient::mock();
+        let publisher = Publisher::new(
+            PublisherType::MainPublisher,
+            MetastoreServiceClient::from(mock_metastore),
+            None,
+            None,
+        );
+        let (_publisher_mailbox, publisher_handle) = universe.spawn_builder().spawn(publisher);
+
+        let pipeline_state_handler =
+            pipeline_state_get_handler(universe.spawn_ctx().clone()).recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/pipeline/state")
+            .reply(&pipeline_state_handler)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let pipeline_state: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+        let publisher_observations = pipeline_state
+            .get(std::any::type_name::<Publisher>())
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(publisher_observations.len(), 1);
+
+        let publisher_state = publisher_observations[0].get("obs").unwrap();
+        assert_eq!(publisher_state["num_published_splits"], 0);
+
+        publisher_handle.quit().await;
+        universe.assert_quit().await;
+    }
+}
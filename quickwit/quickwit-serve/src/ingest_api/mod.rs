@@ -21,5 +21,7 @@ mod rest_handler;
 
 #[cfg(test)]
 pub(crate) use rest_handler::tests::setup_ingest_service;
-pub(crate) use rest_handler::{ingest_api_handlers, lines};
+pub(crate) use rest_handler::{
+    ingest_api_handlers, lines, TooManyIngestRequests, INGEST_RETRY_AFTER_SECS,
+};
 pub use rest_handler::{IngestApi, IngestApiSchemas};
@@ -17,8 +17,15 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use bytes::{Buf, Bytes};
-use quickwit_config::{IngestApiConfig, INGEST_V2_SOURCE_ID};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::{future, stream};
+use hyper::header::{CONTENT_TYPE, RETRY_AFTER};
+use hyper::http::HeaderValue;
+use quickwit_config::{validate_identifier, IngestApiConfig, INGEST_V2_SOURCE_ID};
 use quickwit_ingest::{
     CommitType, DocBatchBuilder, DocBatchV2Builder, FetchResponse, IngestRequest, IngestResponse,
     IngestService, IngestServiceClient, IngestServiceError, TailRequest,
@@ -28,11 +35,18 @@ use quickwit_proto::ingest::router::{
     IngestRouterServiceClient, IngestSubrequest,
 };
 use quickwit_proto::types::IndexId;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use warp::{Filter, Rejection};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use warp::{Filter, Rejection, Reply};
 
+use crate::compression::{accept_encoding, parse_compression_preference};
+use crate::decompression::{
+    content_encoding_from_headers, decompress_body, decompress_with_limit, to_utf8, Charset,
+    CompressionAlgorithm, DecompressOptions, BROTLI_DICTIONARY_ID_HEADER,
+};
 use crate::format::extract_format_from_qs;
+use crate::rest::InvalidArgument;
 use crate::rest_api_response::into_rest_api_response;
 use crate::{with_arg, BodyFormat};
 
@@ -49,17 +63,55 @@ pub struct IngestApi;
 )))]
 pub struct IngestApiSchemas;
 
+/// Delay in seconds advertised in the `Retry-After` header when the ingest service is
+/// unavailable, for instance because its queue is full.
+pub(crate) const INGEST_RETRY_AFTER_SECS: u64 = 1;
+
 #[derive(Debug, Error)]
 #[error("request body contains invalid UTF-8 characters")]
 struct InvalidUtf8;
 
 impl warp::reject::Reject for InvalidUtf8 {}
 
+#[derive(Debug, Error)]
+#[error("too many concurrent ingest requests, retry later")]
+pub(crate) struct TooManyIngestRequests;
+
+impl warp::reject::Reject for TooManyIngestRequests {}
+
 #[derive(Clone, Debug, Default, Deserialize, PartialEq)]
 struct IngestOptions {
     #[serde(alias = "commit")]
     #[serde(default)]
     commit_type: CommitType,
+    /// Backfilled documents are published without advancing the checkpoint of the ingest API
+    /// source, so they do not interfere with live ingestion.
+    #[serde(default)]
+    backfill: bool,
+}
+
+/// Outcome of the ingestion of one part of a `multipart/form-data` ingest request.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+struct IngestPartResponse {
+    /// Name of the part, from its `content-disposition` header.
+    part_name: String,
+    num_docs_for_processing: u64,
+    /// Why the part could not be ingested, for instance because its content encoding is not
+    /// supported.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+struct MultipartIngestResponse {
+    num_docs_for_processing: u64,
+    /// IDs of the splits published with the documents of the request. Only set for requests
+    /// with the `wait_for` or `force` commit type.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    published_split_ids: Vec<String>,
+    parts: Vec<IngestPartResponse>,
 }
 
 pub(crate) fn ingest_api_handlers(
@@ -67,62 +119,229 @@ pub(crate) fn ingest_api_handlers(
     ingest_service: IngestServiceClient,
     config: IngestApiConfig,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    ingest_handler(ingest_service.clone(), config.clone())
-        .or(tail_handler(ingest_service))
-        .or(ingest_v2_handler(ingest_router, config))
+    // The limit on concurrent requests is shared by all the ingest routes.
+    let ingest_semaphore_opt = config
+        .max_concurrent_requests
+        .map(|max_concurrent_requests| Arc::new(Semaphore::new(max_concurrent_requests.get())));
+    let response_compression_preference =
+        parse_compression_preference(&config.response_compression_preference);
+    ingest_multipart_handler(
+        ingest_service.clone(),
+        config.clone(),
+        ingest_semaphore_opt.clone(),
+        response_compression_preference.clone(),
+    )
+    .or(ingest_handler(
+        ingest_service.clone(),
+        config.clone(),
+        ingest_semaphore_opt.clone(),
+        response_compression_preference.clone(),
+    ))
+    .or(tail_handler(ingest_service))
+    .or(ingest_v2_handler(
+        ingest_router,
+        config,
+        ingest_semaphore_opt,
+        response_compression_preference,
+    ))
+}
+
+/// Acquires a permit from the semaphore bounding the number of in-flight ingest requests, if any.
+/// Requests are rejected rather than queued when no permit is available, so that the node does
+/// not accept more work than it can absorb. The permit is handed over to the ingest handler, which
+/// holds it until the request is fully processed.
+fn ingest_permit(
+    ingest_semaphore_opt: Option<Arc<Semaphore>>,
+) -> impl Filter<Extract = (Option<OwnedSemaphorePermit>,), Error = Rejection> + Clone {
+    warp::any().and_then(move || {
+        let ingest_semaphore_opt = ingest_semaphore_opt.clone();
+        async move {
+            let Some(ingest_semaphore) = ingest_semaphore_opt else {
+                return Ok(None);
+            };
+            ingest_semaphore
+                .try_acquire_owned()
+                .map(Some)
+                .map_err(|_| warp::reject::custom(TooManyIngestRequests))
+        }
+    })
+}
+
+/// Extracts the ingest options from the query string.
+///
+/// Backfill requests cannot wait for their documents to be committed: commits are tracked through
+/// the live checkpoint of the source, which backfills do not advance.
+fn ingest_options() -> impl Filter<Extract = (IngestOptions,), Error = Rejection> + Clone {
+    serde_qs::warp::query::<IngestOptions>(serde_qs::Config::default()).and_then(
+        |ingest_options: IngestOptions| async move {
+            if ingest_options.backfill && ingest_options.commit_type != CommitType::Auto {
+                return Err(warp::reject::custom(InvalidArgument(
+                    "backfill requests only support the `auto` commit type".to_string(),
+                )));
+            }
+            Ok(ingest_options)
+        },
+    )
+}
+
+/// Validates the ID of the index targeted by an ingest request, extracted from the path of the
+/// request. IDs that do not follow the naming rules of indexes are rejected with a 400, since no
+/// index can exist under them. Requests targeting a valid ID that matches no index are rejected
+/// with a 404 by the ingest service.
+async fn validate_index_id(index_id: String) -> Result<IndexId, Rejection> {
+    validate_identifier("index", &index_id)
+        .map_err(|error| warp::reject::custom(InvalidArgument(error.to_string())))?;
+    Ok(index_id)
 }
 
 fn ingest_filter(
     config: IngestApiConfig,
-) -> impl Filter<Extract = (String, Bytes, IngestOptions), Error = Rejection> + Clone {
+    ingest_semaphore_opt: Option<Arc<Semaphore>>,
+) -> impl Filter<
+    Extract = (IndexId, Option<OwnedSemaphorePermit>, Bytes, IngestOptions),
+    Error = Rejection,
+> + Clone {
     warp::path!(String / "ingest")
         .and(warp::post())
-        .and(warp::body::content_length_limit(
+        .and_then(validate_index_id)
+        .and(ingest_permit(ingest_semaphore_opt))
+        .and(decompress_with_limit(
             config.content_length_limit.as_u64(),
+            DecompressOptions::from_ingest_api_config(&config),
         ))
-        .and(warp::body::bytes())
-        .and(serde_qs::warp::query::<IngestOptions>(
-            serde_qs::Config::default(),
-        ))
+        .and(ingest_options())
 }
 
 fn ingest_handler(
     ingest_service: IngestServiceClient,
     config: IngestApiConfig,
+    ingest_semaphore_opt: Option<Arc<Semaphore>>,
+    response_compression_preference: Vec<CompressionAlgorithm>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    ingest_filter(config)
+    let commit_timeout = config.wait_for_commit_timeout();
+    ingest_filter(config, ingest_semaphore_opt)
+        .and(with_arg(commit_timeout))
         .and(with_arg(ingest_service))
         .then(ingest)
-        .map(|result| into_rest_api_response(result, BodyFormat::default()))
+        .and(accept_encoding(response_compression_preference))
+        .map(into_ingest_api_response)
+}
+
+/// Extracts the boundary of `multipart/form-data` requests. Other requests are left to the
+/// regular ingest route.
+fn multipart_boundary() -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(CONTENT_TYPE.as_str()).and_then(
+        |content_type_opt: Option<String>| async move {
+            let Some(content_type) = content_type_opt else {
+                return Err(warp::reject::not_found());
+            };
+            if !content_type
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with("multipart/form-data")
+            {
+                return Err(warp::reject::not_found());
+            }
+            multer::parse_boundary(&content_type)
+                .map_err(|error| warp::reject::custom(InvalidArgument(error.to_string())))
+        },
+    )
+}
+
+fn ingest_multipart_handler(
+    ingest_service: IngestServiceClient,
+    config: IngestApiConfig,
+    ingest_semaphore_opt: Option<Arc<Semaphore>>,
+    response_compression_preference: Vec<CompressionAlgorithm>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    warp::path!(String / "ingest")
+        .and(warp::post())
+        .and_then(validate_index_id)
+        .and(multipart_boundary())
+        .and(ingest_permit(ingest_semaphore_opt))
+        .and(decompress_with_limit(
+            config.content_length_limit.as_u64(),
+            DecompressOptions::from_ingest_api_config(&config),
+        ))
+        .and(ingest_options())
+        .and(with_arg(DecompressOptions::from_ingest_api_config(&config)))
+        .and(with_arg(config.wait_for_commit_timeout()))
+        .and(with_arg(ingest_service))
+        .then(ingest_multipart)
+        .and(accept_encoding(response_compression_preference))
+        .map(into_ingest_api_response)
+}
+
+/// Extracts the ingest options from the query string. Backfills are only supported by the ingest
+/// API source.
+fn ingest_v2_options() -> impl Filter<Extract = (IngestOptions,), Error = Rejection> + Clone {
+    serde_qs::warp::query::<IngestOptions>(serde_qs::Config::default()).and_then(
+        |ingest_options: IngestOptions| async move {
+            if ingest_options.backfill {
+                return Err(warp::reject::custom(InvalidArgument(
+                    "backfill is not supported by ingest V2".to_string(),
+                )));
+            }
+            Ok(ingest_options)
+        },
+    )
 }
 
 fn ingest_v2_filter(
     config: IngestApiConfig,
-) -> impl Filter<Extract = (String, Bytes, IngestOptions), Error = Rejection> + Clone {
+    ingest_semaphore_opt: Option<Arc<Semaphore>>,
+) -> impl Filter<
+    Extract = (IndexId, Option<OwnedSemaphorePermit>, Bytes, IngestOptions),
+    Error = Rejection,
+> + Clone {
     warp::path!(String / "ingest-v2")
         .and(warp::post())
-        .and(warp::body::content_length_limit(
+        .and_then(validate_index_id)
+        .and(ingest_permit(ingest_semaphore_opt))
+        .and(decompress_with_limit(
             config.content_length_limit.as_u64(),
+            DecompressOptions::from_ingest_api_config(&config),
         ))
-        .and(warp::body::bytes())
-        .and(serde_qs::warp::query::<IngestOptions>(
-            serde_qs::Config::default(),
-        ))
+        .and(ingest_v2_options())
 }
 
 fn ingest_v2_handler(
     ingest_router: IngestRouterServiceClient,
     config: IngestApiConfig,
+    ingest_semaphore_opt: Option<Arc<Semaphore>>,
+    response_compression_preference: Vec<CompressionAlgorithm>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
-    ingest_v2_filter(config)
+    ingest_v2_filter(config, ingest_semaphore_opt)
         .and(with_arg(ingest_router))
         .then(ingest_v2)
-        .and(with_arg(BodyFormat::default()))
-        .map(into_rest_api_response)
+        .and(accept_encoding(response_compression_preference))
+        .map(into_ingest_api_response)
+}
+
+/// Makes a JSON API response from an ingest result, compressed with the encoding negotiated
+/// with the client.
+///
+/// When the ingest service is unavailable, the client is invited to retry later
+/// via the `Retry-After` header.
+fn into_ingest_api_response<T: Serialize>(
+    result: Result<T, IngestServiceError>,
+    content_encoding_opt: Option<CompressionAlgorithm>,
+) -> warp::reply::Response {
+    let is_unavailable = matches!(result, Err(IngestServiceError::Unavailable));
+    let mut response = into_rest_api_response(result, BodyFormat::default())
+        .with_content_encoding(content_encoding_opt)
+        .into_response();
+    if is_unavailable {
+        response
+            .headers_mut()
+            .insert(RETRY_AFTER, HeaderValue::from(INGEST_RETRY_AFTER_SECS));
+    }
+    response
 }
 
 async fn ingest_v2(
     index_id: IndexId,
+    _ingest_permit_opt: Option<OwnedSemaphorePermit>,
     body: Bytes,
     ingest_options: IngestOptions,
     mut ingest_router: IngestRouterServiceClient,
@@ -168,6 +387,7 @@ fn convert_ingest_response_v2(
     if response.successes.pop().is_some() {
         return Ok(IngestResponse {
             num_docs_for_processing: num_docs as u64,
+            published_split_ids: Vec::new(),
         });
     }
     let ingest_failure = response.failures.pop().unwrap();
@@ -200,29 +420,189 @@ fn convert_ingest_response_v2(
     params(
         ("index_id" = String, Path, description = "The index ID to add docs to."),
         ("commit" = Option<CommitType>, Query, description = "Force or wait for commit at the end of the indexing operation."),
+        ("backfill" = Option<bool>, Query, description = "Publish the documents without advancing the checkpoint of the ingest API source. Only supports the `auto` commit type."),
     )
 )]
 /// Ingest documents
 async fn ingest(
     index_id: String,
+    _ingest_permit_opt: Option<OwnedSemaphorePermit>,
     body: Bytes,
     ingest_options: IngestOptions,
+    commit_timeout: Duration,
     mut ingest_service: IngestServiceClient,
 ) -> Result<IngestResponse, IngestServiceError> {
     // The size of the body should be an upper bound of the size of the batch. The removal of the
     // end of line character for each doc compensates the addition of the `DocCommand` header.
     let mut doc_batch_builder = DocBatchBuilder::with_capacity(index_id, body.remaining());
     for line in lines(&body) {
-        doc_batch_builder.ingest_doc(line);
+        if ingest_options.backfill {
+            doc_batch_builder.backfill_doc(line);
+        } else {
+            doc_batch_builder.ingest_doc(line);
+        }
     }
     let ingest_req = IngestRequest {
         doc_batches: vec![doc_batch_builder.build()],
         commit: ingest_options.commit_type.into(),
     };
-    let ingest_response = ingest_service.ingest(ingest_req).await?;
+    let ingest_response =
+        ingest_and_wait_for_commit(&mut ingest_service, ingest_req, commit_timeout).await?;
     Ok(ingest_response)
 }
 
+/// Sends `ingest_req` to the ingest service. Requests with the `wait_for` or `force` commit type
+/// only return once their documents are committed, that is, published and searchable. They give
+/// up after `commit_timeout`, but their documents stay accepted and are committed eventually.
+async fn ingest_and_wait_for_commit(
+    ingest_service: &mut IngestServiceClient,
+    ingest_req: IngestRequest,
+    commit_timeout: Duration,
+) -> Result<IngestResponse, IngestServiceError> {
+    if ingest_req.commit() == CommitType::Auto {
+        return ingest_service.ingest(ingest_req).await;
+    }
+    tokio::time::timeout(commit_timeout, ingest_service.ingest(ingest_req))
+        .await
+        .map_err(|_| {
+            IngestServiceError::Timeout(format!(
+                "documents were accepted but not committed within {} seconds",
+                commit_timeout.as_secs()
+            ))
+        })?
+}
+
+/// Ingests the parts of a `multipart/form-data` request. Each part is an NDJSON payload,
+/// decompressed according to its own `content-encoding` header.
+///
+/// A part that cannot be read or decompressed is reported in the response and does not prevent
+/// the other parts from being ingested.
+async fn ingest_multipart(
+    index_id: String,
+    boundary: String,
+    _ingest_permit_opt: Option<OwnedSemaphorePermit>,
+    body: Bytes,
+    ingest_options: IngestOptions,
+    decompress_options: DecompressOptions,
+    commit_timeout: Duration,
+    mut ingest_service: IngestServiceClient,
+) -> Result<MultipartIngestResponse, IngestServiceError> {
+    let body_stream = stream::once(future::ready(Ok::<_, Infallible>(body)));
+    let mut multipart = multer::Multipart::new(body_stream, boundary);
+    let mut doc_batches = Vec::new();
+    let mut part_responses = Vec::new();
+
+    loop {
+        // Once a part is malformed, the following parts cannot be located reliably.
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(error) => {
+                part_responses.push(IngestPartResponse {
+                    part_name: format!("part-{}", part_responses.len()),
+                    num_docs_for_processing: 0,
+                    error: Some(error.to_string()),
+                });
+                break;
+            }
+        };
+        let part_name = field
+            .name()
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| format!("part-{}", part_responses.len()));
+        let content_encoding_opt = content_encoding_from_headers(field.headers());
+        let brotli_dictionary_id_opt = field
+            .headers()
+            .get(BROTLI_DICTIONARY_ID_HEADER)
+            .and_then(|header_value| header_value.to_str().ok())
+            .map(|dictionary_id| dictionary_id.to_string());
+        let charset_opt = field
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|header_value| header_value.to_str().ok())
+            .and_then(Charset::from_content_type);
+        let body = match field.bytes().await {
+            Ok(body) => body,
+            Err(error) => {
+                part_responses.push(IngestPartResponse {
+                    part_name,
+                    num_docs_for_processing: 0,
+                    error: Some(error.to_string()),
+                });
+                break;
+            }
+        };
+        let body = match decompress_body(
+            content_encoding_opt,
+            brotli_dictionary_id_opt,
+            body,
+            decompress_options,
+        )
+        .await
+        {
+            Ok(body) => body,
+            Err(error) => {
+                part_responses.push(IngestPartResponse {
+                    part_name,
+                    num_docs_for_processing: 0,
+                    error: Some(error.to_string()),
+                });
+                continue;
+            }
+        };
+        let body = if decompress_options.validate_utf8 {
+            match to_utf8(body, charset_opt) {
+                Ok(body) => body,
+                Err(error) => {
+                    part_responses.push(IngestPartResponse {
+                        part_name,
+                        num_docs_for_processing: 0,
+                        error: Some(error.to_string()),
+                    });
+                    continue;
+                }
+            }
+        } else {
+            body
+        };
+        let mut doc_batch_builder = DocBatchBuilder::with_capacity(index_id.clone(), body.len());
+        let mut num_docs = 0;
+
+        for line in lines(&body) {
+            if ingest_options.backfill {
+                doc_batch_builder.backfill_doc(line);
+            } else {
+                doc_batch_builder.ingest_doc(line);
+            }
+            num_docs += 1;
+        }
+        doc_batches.push(doc_batch_builder.build());
+        part_responses.push(IngestPartResponse {
+            part_name,
+            num_docs_for_processing: num_docs,
+            error: None,
+        });
+    }
+    if doc_batches.is_empty() {
+        return Ok(MultipartIngestResponse {
+            num_docs_for_processing: 0,
+            published_split_ids: Vec::new(),
+            parts: part_responses,
+        });
+    }
+    let ingest_req = IngestRequest {
+        doc_batches,
+        commit: ingest_options.commit_type.into(),
+    };
+    let ingest_response =
+        ingest_and_wait_for_commit(&mut ingest_service, ingest_req, commit_timeout).await?;
+    Ok(MultipartIngestResponse {
+        num_docs_for_processing: ingest_response.num_docs_for_processing,
+        published_split_ids: ingest_response.published_split_ids,
+        parts: part_responses,
+    })
+}
+
 pub fn tail_handler(
     ingest_service: IngestServiceClient,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
@@ -269,22 +649,28 @@ fn is_empty_or_blank_line(line: &[u8]) -> bool {
 
 #[cfg(test)]
 pub(crate) mod tests {
+    use std::io::Write;
+    use std::num::NonZeroUsize;
     use std::str;
     use std::time::Duration;
 
     use bytes::Bytes;
     use bytesize::ByteSize;
-    use quickwit_actors::{Mailbox, Universe};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use quickwit_actors::{Mailbox, QueueCapacity, Universe};
     use quickwit_config::IngestApiConfig;
     use quickwit_ingest::{
-        init_ingest_api, CreateQueueIfNotExistsRequest, FetchRequest, FetchResponse,
-        IngestApiService, IngestResponse, IngestServiceClient, SuggestTruncateRequest,
-        QUEUES_DIR_NAME,
+        init_ingest_api, CreateQueueIfNotExistsRequest, DocCommand, FetchRequest, FetchResponse,
+        IngestApiService, IngestResponse, IngestServiceClient, LoadSheddingIngestApiService,
+        SuggestTruncateRequest, TailRequest, QUEUES_DIR_NAME,
     };
     use quickwit_proto::ingest::router::IngestRouterServiceClient;
+    use warp::Filter;
 
-    use super::ingest_api_handlers;
+    use super::{ingest_api_handlers, IngestPartResponse, MultipartIngestResponse};
     use crate::ingest_api::lines;
+    use crate::rest::recover_fn;
 
     #[test]
     fn test_process_lines() {
@@ -392,6 +778,236 @@ pub(crate) mod tests {
         universe.assert_quit().await;
     }
 
+    #[cfg(feature = "zstd")]
+    #[tokio::test]
+    async fn test_ingest_api_compresses_response_with_zstd() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mock().into();
+        let ingest_api_handlers =
+            ingest_api_handlers(ingest_router, ingest_service, IngestApiConfig::default());
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .header("accept-encoding", "zstd")
+            .body(r#"{"id": 1, "message": "push"}"#)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.headers().get("content-encoding").unwrap(), "zstd");
+        let decompressed_body = zstd::decode_all(&resp.body()[..]).unwrap();
+        let ingest_response: IngestResponse = serde_json::from_slice(&decompressed_body).unwrap();
+        assert_eq!(ingest_response.num_docs_for_processing, 1);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_ingest_api_does_not_compress_response_without_accept_encoding() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mock().into();
+        let ingest_api_handlers =
+            ingest_api_handlers(ingest_router, ingest_service, IngestApiConfig::default());
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .body(r#"{"id": 1, "message": "push"}"#)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert!(resp.headers().get("content-encoding").is_none());
+        let ingest_response: IngestResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(ingest_response.num_docs_for_processing, 1);
+        universe.assert_quit().await;
+    }
+
+    fn multipart_body(parts: &[(&str, Option<&str>, &[u8])]) -> Vec<u8> {
+        let mut body = Vec::new();
+
+        for (part_name, content_encoding_opt, payload) in parts {
+            body.extend_from_slice(b"--boundary\r\n");
+            body.extend_from_slice(
+                format!("content-disposition: form-data; name=\"{part_name}\"\r\n").as_bytes(),
+            );
+            if let Some(content_encoding) = content_encoding_opt {
+                body.extend_from_slice(
+                    format!("content-encoding: {content_encoding}\r\n").as_bytes(),
+                );
+            }
+            body.extend_from_slice(b"\r\n");
+            body.extend_from_slice(payload);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(b"--boundary--\r\n");
+        body
+    }
+
+    #[cfg(feature = "zstd")]
+    #[tokio::test]
+    async fn test_ingest_api_multipart_decompresses_each_part() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mock().into();
+        let ingest_api_handlers =
+            ingest_api_handlers(ingest_router, ingest_service, IngestApiConfig::default());
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(b"{\"id\": 1, \"message\": \"push\"}\n{\"id\": 2, \"message\": \"push\"}")
+            .unwrap();
+        let gzip_payload = encoder.finish().unwrap();
+        let zstd_payload = zstd::encode_all(&b"{\"id\": 3, \"message\": \"push\"}"[..], 0).unwrap();
+        let body = multipart_body(&[
+            ("logs-1", Some("gzip"), &gzip_payload),
+            ("logs-2", Some("zstd"), &zstd_payload),
+        ]);
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(body)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let ingest_response: MultipartIngestResponse = serde_json::from_slice(resp.body()).unwrap();
+        let expected_ingest_response = MultipartIngestResponse {
+            num_docs_for_processing: 3,
+            published_split_ids: Vec::new(),
+            parts: vec![
+                IngestPartResponse {
+                    part_name: "logs-1".to_string(),
+                    num_docs_for_processing: 2,
+                    error: None,
+                },
+                IngestPartResponse {
+                    part_name: "logs-2".to_string(),
+                    num_docs_for_processing: 1,
+                    error: None,
+                },
+            ],
+        };
+        assert_eq!(ingest_response, expected_ingest_response);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_ingest_api_multipart_reports_part_errors() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mock().into();
+        let ingest_api_handlers =
+            ingest_api_handlers(ingest_router, ingest_service, IngestApiConfig::default());
+        let body = multipart_body(&[
+            ("unsupported", Some("compress"), b"{\"id\": 1}"),
+            ("malformed", Some("gzip"), b"{\"id\": 2}"),
+            ("plain", None, b"{\"id\": 3}"),
+        ]);
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(body)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let ingest_response: MultipartIngestResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(ingest_response.num_docs_for_processing, 1);
+        assert_eq!(ingest_response.parts.len(), 3);
+
+        let unsupported_part = &ingest_response.parts[0];
+        assert_eq!(unsupported_part.part_name, "unsupported");
+        assert_eq!(unsupported_part.num_docs_for_processing, 0);
+        assert!(unsupported_part
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("`compress` is unknown"));
+
+        let malformed_part = &ingest_response.parts[1];
+        assert_eq!(malformed_part.part_name, "malformed");
+        assert!(malformed_part
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("failed to decompress gzip"));
+
+        let plain_part = &ingest_response.parts[2];
+        assert_eq!(plain_part.part_name, "plain");
+        assert_eq!(plain_part.num_docs_for_processing, 1);
+        assert!(plain_part.error.is_none());
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_ingest_api_multipart_requires_boundary() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mock().into();
+        let ingest_api_handlers =
+            ingest_api_handlers(ingest_router, ingest_service, IngestApiConfig::default())
+                .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .header("content-type", "multipart/form-data")
+            .body("")
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_ingest_api_backfill() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mock().into();
+        let ingest_api_handlers =
+            ingest_api_handlers(ingest_router, ingest_service, IngestApiConfig::default());
+        let resp = warp::test::request()
+            .path("/my-index/ingest?backfill=true")
+            .method("POST")
+            .body("{\"id\": 1}\n{\"id\": 2}")
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let ingest_response: IngestResponse = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(ingest_response.num_docs_for_processing, 2);
+
+        let resp = warp::test::request()
+            .path("/my-index/tail")
+            .method("GET")
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let fetch_response: FetchResponse = serde_json::from_slice(resp.body()).unwrap();
+        let doc_batch = fetch_response.doc_batch.unwrap();
+        assert_eq!(doc_batch.num_docs(), 2);
+        assert!(doc_batch
+            .iter()
+            .all(|doc| matches!(doc, DocCommand::Backfill { .. })));
+
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_ingest_api_backfill_requires_auto_commit() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mock().into();
+        let ingest_api_handlers =
+            ingest_api_handlers(ingest_router, ingest_service, IngestApiConfig::default())
+                .recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/my-index/ingest?backfill=true&commit=wait_for")
+            .method("POST")
+            .body(r#"{"id": 1}"#)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 400);
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_ingest_api_return_429_if_above_limits() {
         let config = IngestApiConfig {
@@ -414,6 +1030,147 @@ pub(crate) mod tests {
         universe.assert_quit().await;
     }
 
+    #[tokio::test]
+    async fn test_ingest_api_return_503_with_retry_after_if_queue_full() {
+        let universe = Universe::with_accelerated_time();
+        // The ingest service never processes its messages, so its mailbox stays full.
+        let (ingest_service_mailbox, _ingest_service_inbox) = universe
+            .create_mailbox::<IngestApiService>("ingest-api-service", QueueCapacity::Bounded(1));
+        ingest_service_mailbox
+            .try_send_message(TailRequest {
+                index_id: "my-index".to_string(),
+            })
+            .unwrap();
+        let ingest_service =
+            IngestServiceClient::new(LoadSheddingIngestApiService::new(ingest_service_mailbox));
+        let ingest_router = IngestRouterServiceClient::mock().into();
+        let ingest_api_handlers =
+            ingest_api_handlers(ingest_router, ingest_service, IngestApiConfig::default());
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .json(&true)
+            .body(r#"{"id": 1, "message": "push"}"#)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 503);
+        assert_eq!(resp.headers().get("retry-after").unwrap(), "1");
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_ingest_api_return_503_with_retry_after_if_too_many_concurrent_requests() {
+        let universe = Universe::with_accelerated_time();
+        // The ingest service never replies, so that the first request holds its permit.
+        let (ingest_service_mailbox, _ingest_service_inbox) =
+            universe.create_test_mailbox::<IngestApiService>();
+        let ingest_service = IngestServiceClient::from_mailbox(ingest_service_mailbox);
+        let ingest_router = IngestRouterServiceClient::mock().into();
+        let config = IngestApiConfig {
+            max_concurrent_requests: Some(NonZeroUsize::MIN),
+            ..Default::default()
+        };
+        let ingest_api_handlers =
+            ingest_api_handlers(ingest_router, ingest_service, config).recover(recover_fn);
+
+        let handle = tokio::spawn({
+            let ingest_api_handlers = ingest_api_handlers.clone();
+            async move {
+                warp::test::request()
+                    .path("/my-index/ingest")
+                    .method("POST")
+                    .body(r#"{"id": 1, "message": "push"}"#)
+                    .reply(&ingest_api_handlers)
+                    .await
+            }
+        });
+        universe.sleep(Duration::from_secs(1)).await;
+        assert!(!handle.is_finished());
+
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .body(r#"{"id": 2, "message": "push"}"#)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 503);
+        assert_eq!(resp.headers().get("retry-after").unwrap(), "1");
+
+        let resp = warp::test::request()
+            .path("/my-index/ingest-v2")
+            .method("POST")
+            .body(r#"{"id": 3, "message": "push"}"#)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 503);
+
+        handle.abort();
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ingest_api_return_408_if_commit_times_out() {
+        let universe = Universe::new();
+        // The ingest service never replies, as if the documents were never committed.
+        let (ingest_service_mailbox, _ingest_service_inbox) =
+            universe.create_test_mailbox::<IngestApiService>();
+        let ingest_service = IngestServiceClient::from_mailbox(ingest_service_mailbox);
+        let ingest_router = IngestRouterServiceClient::mock().into();
+        let config = IngestApiConfig {
+            wait_for_commit_timeout_secs: 10,
+            ..Default::default()
+        };
+        let ingest_api_handlers = ingest_api_handlers(ingest_router, ingest_service, config);
+        let resp = warp::test::request()
+            .path("/my-index/ingest?commit=wait_for")
+            .method("POST")
+            .body(r#"{"id": 1, "message": "push"}"#)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 408);
+        let body = str::from_utf8(resp.body()).unwrap();
+        assert!(body.contains("not committed within 10 seconds"));
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_ingest_api_validates_index_id() {
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mock().into();
+        let ingest_api_handlers =
+            ingest_api_handlers(ingest_router, ingest_service, IngestApiConfig::default())
+                .recover(recover_fn);
+
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .body(r#"{"id": 1, "message": "push"}"#)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        for path in ["/my%20index/ingest", "/_my-index/ingest", "/ab/ingest-v2"] {
+            let resp = warp::test::request()
+                .path(path)
+                .method("POST")
+                .body(r#"{"id": 1, "message": "push"}"#)
+                .reply(&ingest_api_handlers)
+                .await;
+            assert_eq!(resp.status(), 400, "path `{path}` should be rejected");
+            assert!(str::from_utf8(resp.body()).unwrap().contains("index ID `"));
+        }
+        let resp = warp::test::request()
+            .path("/index-not-found/ingest")
+            .method("POST")
+            .body(r#"{"id": 1, "message": "push"}"#)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 404);
+
+        universe.assert_quit().await;
+    }
+
     #[tokio::test]
     async fn test_ingest_api_return_413_if_above_content_limit() {
         let config = IngestApiConfig {
@@ -424,7 +1181,7 @@ pub(crate) mod tests {
             setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
         let ingest_router = IngestRouterServiceClient::mock().into();
         let ingest_api_handlers =
-            ingest_api_handlers(ingest_router, ingest_service, config.clone());
+            ingest_api_handlers(ingest_router, ingest_service, config.clone()).recover(recover_fn);
         let resp = warp::test::request()
             .path("/my-index/ingest")
             .method("POST")
@@ -433,6 +1190,32 @@ pub(crate) mod tests {
             .reply(&ingest_api_handlers)
             .await;
         assert_eq!(resp.status(), 413);
+        assert!(str::from_utf8(resp.body())
+            .unwrap()
+            .contains("request body is larger than the limit of 1 bytes"));
+        universe.assert_quit().await;
+    }
+
+    #[tokio::test]
+    async fn test_ingest_api_multipart_return_413_if_above_content_limit() {
+        let body = multipart_body(&[("logs", None, b"{\"id\": 1, \"message\": \"push\"}")]);
+        let config = IngestApiConfig {
+            content_length_limit: ByteSize(body.len() as u64 - 1),
+            ..Default::default()
+        };
+        let (universe, _temp_dir, ingest_service, _) =
+            setup_ingest_service(&["my-index"], &IngestApiConfig::default()).await;
+        let ingest_router = IngestRouterServiceClient::mock().into();
+        let ingest_api_handlers =
+            ingest_api_handlers(ingest_router, ingest_service, config).recover(recover_fn);
+        let resp = warp::test::request()
+            .path("/my-index/ingest")
+            .method("POST")
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(body)
+            .reply(&ingest_api_handlers)
+            .await;
+        assert_eq!(resp.status(), 413);
         universe.assert_quit().await;
     }
 
@@ -457,6 +1240,10 @@ pub(crate) mod tests {
             assert_eq!(resp.status(), 200);
             let ingest_response: IngestResponse = serde_json::from_slice(resp.body()).unwrap();
             assert_eq!(ingest_response.num_docs_for_processing, 1);
+            assert_eq!(
+                ingest_response.published_split_ids,
+                vec!["split-1".to_string()]
+            );
         });
         universe.sleep(Duration::from_secs(10)).await;
         assert!(!handle.is_finished());
@@ -478,6 +1265,7 @@ pub(crate) mod tests {
             .ask_for_res(SuggestTruncateRequest {
                 index_id: "my-index".to_string(),
                 up_to_position_included: 0,
+                published_split_ids: vec!["split-1".to_string()],
             })
             .await
             .unwrap();
@@ -527,6 +1315,7 @@ pub(crate) mod tests {
             .ask_for_res(SuggestTruncateRequest {
                 index_id: "my-index".to_string(),
                 up_to_position_included: 0,
+                published_split_ids: Vec::new(),
             })
             .await
             .unwrap();
@@ -19,7 +19,9 @@
 
 mod build_info;
 mod cluster_api;
+mod compression;
 mod debugging_api;
+mod decompression;
 mod delete_task_api;
 mod elasticsearch_api;
 mod format;
@@ -56,7 +58,7 @@ use bytesize::ByteSize;
 pub use format::BodyFormat;
 use futures::{Stream, StreamExt};
 use itertools::Itertools;
-use quickwit_actors::{ActorExitStatus, Mailbox, Universe};
+use quickwit_actors::{ActorExitStatus, Mailbox, SpawnContext, Universe};
 use quickwit_cluster::{
     start_cluster_service, Cluster, ClusterChange, ClusterMember, ListenerHandle,
 };
@@ -73,14 +75,14 @@ use quickwit_config::service::QuickwitService;
 use quickwit_config::{ClusterConfig, NodeConfig};
 use quickwit_control_plane::control_plane::{ControlPlane, ControlPlaneEventSubscriber};
 use quickwit_control_plane::{IndexerNodeInfo, IndexerPool};
-use quickwit_index_management::{IndexService as IndexManager, IndexServiceError};
+use quickwit_index_management::IndexService as IndexManager;
 use quickwit_indexing::actors::IndexingService;
 use quickwit_indexing::models::ShardPositionsService;
 use quickwit_indexing::start_indexing_service;
 use quickwit_ingest::{
     setup_local_shards_update_listener, start_ingest_api_service, wait_for_ingester_decommission,
     GetMemoryCapacity, IngestRequest, IngestRouter, IngestServiceClient, Ingester, IngesterPool,
-    LocalShardsUpdate,
+    LoadSheddingIngestApiService, LocalShardsUpdate,
 };
 use quickwit_jaeger::JaegerService;
 use quickwit_janitor::{start_janitor_service, JanitorService};
@@ -93,8 +95,7 @@ use quickwit_proto::indexing::{IndexingServiceClient, ShardPositionsUpdate};
 use quickwit_proto::ingest::ingester::IngesterServiceClient;
 use quickwit_proto::ingest::router::IngestRouterServiceClient;
 use quickwit_proto::metastore::{
-    EntityKind, ListIndexesMetadataRequest, MetastoreError, MetastoreService,
-    MetastoreServiceClient,
+    CreateIndexMode, ListIndexesMetadataRequest, MetastoreService, MetastoreServiceClient,
 };
 use quickwit_proto::search::ReportSplitsRequest;
 use quickwit_proto::types::NodeId;
@@ -161,6 +162,8 @@ struct QuickwitServices {
     /// It is only used to serve the rest API calls and will only execute
     /// the root requests.
     pub search_service: Arc<dyn SearchService>,
+    /// Context in which the actors of the node are spawned. It is used to observe them.
+    pub spawn_ctx: SpawnContext,
 
     /// The control plane listens to various events.
     /// We must maintain a reference to the subscription handles to continue receiving
@@ -219,6 +222,8 @@ async fn start_ingest_client_if_needed(
         let memory_capacity = ingest_api_service.ask(GetMemoryCapacity).await?;
         let min_rate = ConstantRate::new(ByteSize::mib(1).as_u64(), Duration::from_millis(100));
         let rate_modulator = RateModulator::new(rate_estimator.clone(), memory_capacity, min_rate);
+        // Ingest requests are rejected rather than queued when the mailbox of the ingest API
+        // service is full, so that clients get a fast 503 and can retry.
         let ingest_service = IngestServiceClient::tower()
             .stack_ingest_layer(
                 ServiceBuilder::new()
@@ -227,7 +232,7 @@ async fn start_ingest_client_if_needed(
                     .layer(RateLimitLayer::new(rate_modulator))
                     .into_inner(),
             )
-            .build_from_mailbox(ingest_api_service);
+            .build(LoadSheddingIngestApiService::new(ingest_api_service));
         Ok(ingest_service)
     } else {
         let balance_channel = balance_channel_for_service(cluster, QuickwitService::Indexer).await;
@@ -316,7 +321,9 @@ pub async fn serve_quickwit(
                 .stack_delete_index_layer(broker_layer.clone())
                 .stack_add_source_layer(broker_layer.clone())
                 .stack_delete_source_layer(broker_layer.clone())
-                .stack_toggle_source_layer(broker_layer)
+                .stack_toggle_source_layer(broker_layer.clone())
+                .stack_publish_splits_layer(broker_layer.clone())
+                .stack_mark_splits_for_deletion_layer(broker_layer)
                 .build(metastore);
             Some(metastore)
         } else {
@@ -430,7 +437,8 @@ pub async fn serve_quickwit(
     let mut index_manager = IndexManager::new(
         metastore_through_control_plane.clone(),
         storage_resolver.clone(),
-    );
+    )
+    .with_event_broker(event_broker.clone());
 
     if node_config.is_service_enabled(QuickwitService::Indexer)
         && node_config.indexer_config.enable_otlp_endpoint
@@ -442,13 +450,9 @@ pub async fn serve_quickwit(
                 OtlpGrpcTracesService::index_config(&node_config.default_index_root_uri)?;
 
             for index_config in [otel_logs_index_config, otel_traces_index_config] {
-                match index_manager.create_index(index_config, false).await {
-                    Ok(_)
-                    | Err(IndexServiceError::Metastore(MetastoreError::AlreadyExists(
-                        EntityKind::Index { .. },
-                    ))) => Ok(()),
-                    Err(error) => Err(error),
-                }?;
+                index_manager
+                    .create_index(index_config, CreateIndexMode::IfNotExists)
+                    .await?;
             }
         }
     }
@@ -568,6 +572,7 @@ pub async fn serve_quickwit(
         otlp_logs_service_opt,
         otlp_traces_service_opt,
         search_service,
+        spawn_ctx: universe.spawn_ctx().clone(),
     });
     // Setup and start gRPC server.
     let (grpc_readiness_trigger_tx, grpc_readiness_signal_rx) = oneshot::channel::<()>();
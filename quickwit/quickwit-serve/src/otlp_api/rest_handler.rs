@@ -199,6 +199,7 @@ mod tests {
             .returning(|_| {
                 Ok(IngestResponse {
                     num_docs_for_processing: 1,
+                    published_split_ids: Vec::new(),
                 })
             });
         let ingest_service_client = IngestServiceClient::from(ingest_service_mock);
@@ -290,6 +291,7 @@ mod tests {
             .returning(|_| {
                 Ok(IngestResponse {
                     num_docs_for_processing: 1,
+                    published_split_ids: Vec::new(),
                 })
             });
         let ingest_service_client = IngestServiceClient::from(ingest_service_mock);
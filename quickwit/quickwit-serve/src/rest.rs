@@ -34,12 +34,17 @@ use warp::{redirect, Filter, Rejection, Reply};
 
 use crate::cluster_api::cluster_handler;
 use crate::debugging_api::debugging_handler;
+use crate::decompression::{
+    load_brotli_dictionaries, DecompressedBodyTooLarge, InvalidUtf8, MalformedCompressedBody,
+    PayloadTooLarge, UndeclaredCompressedBody, UnknownBrotliDictionary,
+    UnsupportedCompressionAlgorithm,
+};
 use crate::delete_task_api::delete_task_api_handlers;
 use crate::elasticsearch_api::elastic_api_handlers;
 use crate::health_check_api::health_check_handlers;
 use crate::index_api::index_management_handlers;
-use crate::indexing_api::indexing_get_handler;
-use crate::ingest_api::ingest_api_handlers;
+use crate::indexing_api::{indexing_get_handler, pipeline_state_get_handler};
+use crate::ingest_api::{ingest_api_handlers, TooManyIngestRequests, INGEST_RETRY_AFTER_SECS};
 use crate::jaeger_api::jaeger_api_handlers;
 use crate::metrics_api::metrics_handler;
 use crate::node_info_handler::node_info_handler;
@@ -163,6 +168,8 @@ pub(crate) async fn start_rest_server(
 fn api_v1_routes(
     quickwit_services: Arc<QuickwitServices>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = Rejection> + Clone {
+    load_brotli_dictionaries(&quickwit_services.node_config.ingest_api_config);
+
     let api_v1_root_url = warp::path!("api" / "v1" / ..);
     api_v1_root_url.and(
         cluster_handler(quickwit_services.cluster.clone())
@@ -174,6 +181,9 @@ fn api_v1_routes(
             .or(indexing_get_handler(
                 quickwit_services.indexing_service_opt.clone(),
             ))
+            .or(pipeline_state_get_handler(
+                quickwit_services.spawn_ctx.clone(),
+            ))
             .or(search_get_handler(quickwit_services.search_service.clone()))
             .or(search_post_handler(
                 quickwit_services.search_service.clone(),
@@ -225,21 +235,73 @@ fn api_v1_routes(
 // More on this here: https://github.com/seanmonstar/warp/issues/388.
 // We may use this work on the PR is merged: https://github.com/seanmonstar/warp/pull/909.
 pub async fn recover_fn(rejection: Rejection) -> Result<impl Reply, Rejection> {
-    let err = get_status_with_error(rejection);
-    let status_code = err.service_code.to_http_status_code();
-    Ok(RestApiResponse::new::<(), _>(
-        &Err(err),
-        status_code,
-        &BodyFormat::default(),
-    ))
+    Ok(rejection_to_response(&rejection))
+}
+
+/// Converts a rejection into the JSON error response sent back to the client. The status code of
+/// each rejection is part of the HTTP contract of the REST API. Unknown rejections are answered
+/// with a 500 and a generic message, so that internal details do not leak.
+pub(crate) fn rejection_to_response(rejection: &Rejection) -> warp::reply::Response {
+    let rest_api_error = get_status_with_error(rejection);
+    let status_code = rest_api_error.service_code.to_http_status_code();
+    let mut response =
+        RestApiResponse::new::<(), _>(&Err(rest_api_error), status_code, &BodyFormat::default())
+            .into_response();
+    if rejection.find::<TooManyIngestRequests>().is_some() {
+        response.headers_mut().insert(
+            http::header::RETRY_AFTER,
+            HeaderValue::from(INGEST_RETRY_AFTER_SECS),
+        );
+    }
+    response
 }
 
-fn get_status_with_error(rejection: Rejection) -> RestApiError {
+fn get_status_with_error(rejection: &Rejection) -> RestApiError {
     if let Some(error) = rejection.find::<crate::format::UnsupportedMediaType>() {
         RestApiError {
             service_code: ServiceErrorCode::UnsupportedMediaType,
             message: error.to_string(),
         }
+    } else if let Some(error) = rejection.find::<UnsupportedCompressionAlgorithm>() {
+        RestApiError {
+            service_code: ServiceErrorCode::UnsupportedMediaType,
+            message: error.to_string(),
+        }
+    } else if let Some(error) = rejection.find::<MalformedCompressedBody>() {
+        RestApiError {
+            service_code: ServiceErrorCode::BadRequest,
+            message: error.to_string(),
+        }
+    } else if let Some(error) = rejection.find::<InvalidUtf8>() {
+        RestApiError {
+            service_code: ServiceErrorCode::BadRequest,
+            message: error.to_string(),
+        }
+    } else if let Some(error) = rejection.find::<UnknownBrotliDictionary>() {
+        RestApiError {
+            service_code: ServiceErrorCode::BadRequest,
+            message: error.to_string(),
+        }
+    } else if let Some(error) = rejection.find::<UndeclaredCompressedBody>() {
+        RestApiError {
+            service_code: ServiceErrorCode::BadRequest,
+            message: error.to_string(),
+        }
+    } else if let Some(error) = rejection.find::<PayloadTooLarge>() {
+        RestApiError {
+            service_code: ServiceErrorCode::PayloadTooLarge,
+            message: error.to_string(),
+        }
+    } else if let Some(error) = rejection.find::<DecompressedBodyTooLarge>() {
+        RestApiError {
+            service_code: ServiceErrorCode::PayloadTooLarge,
+            message: error.to_string(),
+        }
+    } else if let Some(error) = rejection.find::<TooManyIngestRequests>() {
+        RestApiError {
+            service_code: ServiceErrorCode::Unavailable,
+            message: error.to_string(),
+        }
     } else if rejection.is_not_found() {
         RestApiError {
             service_code: ServiceErrorCode::NotFound,
@@ -300,7 +362,7 @@ fn get_status_with_error(rejection: Rejection) -> RestApiError {
         }
     } else if let Some(error) = rejection.find::<warp::reject::PayloadTooLarge>() {
         RestApiError {
-            service_code: ServiceErrorCode::BadRequest,
+            service_code: ServiceErrorCode::PayloadTooLarge,
             message: error.to_string(),
         }
     } else {
@@ -359,6 +421,133 @@ mod tests {
     use tower::Service;
 
     use super::*;
+    use crate::decompression::CompressionAlgorithm;
+
+    async fn assert_rejection_response(
+        rejection: Rejection,
+        expected_status_code: StatusCode,
+        expected_message: &str,
+    ) {
+        let response = rejection_to_response(&rejection);
+        assert_eq!(response.status(), expected_status_code);
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+        let body_bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body_json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+        assert_eq!(
+            body_json,
+            serde_json::json!({ "message": expected_message })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejection_to_response() {
+        assert_rejection_response(
+            warp::reject::not_found(),
+            StatusCode::NOT_FOUND,
+            "Route not found",
+        )
+        .await;
+        assert_rejection_response(
+            warp::reject::custom(crate::format::UnsupportedMediaType),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            &crate::format::UnsupportedMediaType.to_string(),
+        )
+        .await;
+        let error = UnsupportedCompressionAlgorithm::Unknown("compress".to_string());
+        let expected_message = error.to_string();
+        assert_rejection_response(
+            warp::reject::custom(error),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            &expected_message,
+        )
+        .await;
+        assert_rejection_response(
+            warp::reject::custom(MalformedCompressedBody {
+                algorithm: CompressionAlgorithm::Gzip,
+                message: "invalid gzip header".to_string(),
+            }),
+            StatusCode::BAD_REQUEST,
+            "failed to decompress gzip request body: invalid gzip header",
+        )
+        .await;
+        assert_rejection_response(
+            warp::reject::custom(InvalidUtf8 { offset: 3 }),
+            StatusCode::BAD_REQUEST,
+            "request body is not valid UTF-8: invalid byte sequence at offset 3",
+        )
+        .await;
+        assert_rejection_response(
+            warp::reject::custom(UnknownBrotliDictionary {
+                dictionary_id: "logs-v1".to_string(),
+            }),
+            StatusCode::BAD_REQUEST,
+            "request references unknown brotli dictionary `logs-v1`",
+        )
+        .await;
+        assert_rejection_response(
+            warp::reject::custom(UndeclaredCompressedBody {
+                algorithm: CompressionAlgorithm::Gzip,
+            }),
+            StatusCode::BAD_REQUEST,
+            "request body looks gzip-compressed but has no `content-encoding` header, set \
+             `content-encoding: gzip` if it is",
+        )
+        .await;
+        assert_rejection_response(
+            warp::reject::custom(PayloadTooLarge {
+                limit_num_bytes: 1024,
+            }),
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "request body is larger than the limit of 1024 bytes",
+        )
+        .await;
+        assert_rejection_response(
+            warp::reject::custom(DecompressedBodyTooLarge {
+                limit_num_bytes: 1024,
+            }),
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "decompressed request body is larger than the limit of 1024 bytes",
+        )
+        .await;
+        assert_rejection_response(
+            warp::reject::custom(TooManyIngestRequests),
+            StatusCode::SERVICE_UNAVAILABLE,
+            "too many concurrent ingest requests, retry later",
+        )
+        .await;
+        let json_error = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let expected_message = json_error.to_string();
+        assert_rejection_response(
+            warp::reject::custom(InvalidJsonRequest(json_error)),
+            StatusCode::BAD_REQUEST,
+            &expected_message,
+        )
+        .await;
+        assert_rejection_response(
+            warp::reject::custom(InvalidArgument("invalid index ID".to_string())),
+            StatusCode::BAD_REQUEST,
+            "invalid index ID",
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_rejection_to_response_hides_unknown_rejections() {
+        #[derive(Debug)]
+        struct UnknownRejection;
+
+        impl warp::reject::Reject for UnknownRejection {}
+
+        assert_rejection_response(
+            warp::reject::custom(UnknownRejection),
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal server error",
+        )
+        .await;
+    }
 
     pub(crate) fn ingest_service_client() -> IngestServiceClient {
         let universe = quickwit_actors::Universe::new();
@@ -589,6 +778,7 @@ mod tests {
             HeaderName::from_static("x-custom-header-2"),
             HeaderValue::from_static("custom-value-2"),
         );
+        let universe = quickwit_actors::Universe::new();
         let metastore_client = MetastoreServiceClient::from(MetastoreServiceClient::mock());
         let index_service =
             IndexService::new(metastore_client.clone(), StorageResolver::unconfigured());
@@ -605,6 +795,7 @@ mod tests {
             control_plane_service,
             indexing_service_opt: None,
             index_manager: index_service,
+            spawn_ctx: universe.spawn_ctx().clone(),
             ingest_service: ingest_service_client(),
             ingester_service_opt: None,
             ingest_router_service: IngestRouterServiceClient::from(
@@ -17,13 +17,14 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use hyper::header::CONTENT_TYPE;
+use hyper::header::{CONTENT_ENCODING, CONTENT_TYPE, VARY};
 use hyper::http::{status, HeaderValue};
 use hyper::{Body, Response};
 use quickwit_proto::{ServiceError, ServiceErrorCode};
 use serde::{self, Serialize};
 use warp::Reply;
 
+use crate::decompression::CompressionAlgorithm;
 use crate::format::BodyFormat;
 
 const JSON_SERIALIZATION_ERROR: &str = "JSON serialization failed.";
@@ -71,6 +72,7 @@ pub(crate) fn into_rest_api_response<T: serde::Serialize, E: ServiceError>(
 pub struct RestApiResponse {
     status_code: status::StatusCode,
     inner: Result<Vec<u8>, ()>,
+    content_encoding_opt: Option<CompressionAlgorithm>,
 }
 
 impl RestApiResponse {
@@ -80,7 +82,21 @@ impl RestApiResponse {
         body_format: &BodyFormat,
     ) -> Self {
         let inner = body_format.result_to_vec(result);
-        RestApiResponse { status_code, inner }
+        RestApiResponse {
+            status_code,
+            inner,
+            content_encoding_opt: None,
+        }
+    }
+
+    /// Compresses the body of the response with the algorithm negotiated with the client, if any.
+    /// The body is sent as is if compression fails.
+    pub(crate) fn with_content_encoding(
+        mut self,
+        content_encoding_opt: Option<CompressionAlgorithm>,
+    ) -> Self {
+        self.content_encoding_opt = content_encoding_opt;
+        self
     }
 }
 
@@ -89,7 +105,25 @@ impl Reply for RestApiResponse {
     fn into_response(self) -> Response<Body> {
         match self.inner {
             Ok(body) => {
-                let mut response = Response::new(body.into());
+                let compressed_body_opt = self.content_encoding_opt.and_then(|algorithm| {
+                    let compressed_body = algorithm.compress(&body).ok()?;
+                    Some((algorithm, compressed_body))
+                });
+                let mut response = if let Some((algorithm, compressed_body)) = compressed_body_opt {
+                    let mut response = Response::new(compressed_body.into());
+                    response.headers_mut().insert(
+                        CONTENT_ENCODING,
+                        HeaderValue::from_static(algorithm.as_str()),
+                    );
+                    response
+                } else {
+                    Response::new(body.into())
+                };
+                if self.content_encoding_opt.is_some() {
+                    response
+                        .headers_mut()
+                        .insert(VARY, HeaderValue::from_static("accept-encoding"));
+                }
                 response
                     .headers_mut()
                     .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
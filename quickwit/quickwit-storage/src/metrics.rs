@@ -84,6 +84,8 @@ pub struct CacheMetrics {
     pub hits_num_items: IntCounter,
     pub hits_num_bytes: IntCounter,
     pub misses_num_items: IntCounter,
+    pub evict_num_items: IntCounter,
+    pub evict_num_bytes: IntCounter,
 }
 
 impl CacheMetrics {
@@ -116,6 +118,16 @@ impl CacheMetrics {
                 "Number of {component_name} cache misses",
                 &namespace,
             ),
+            evict_num_items: new_counter(
+                "cache_evict_total",
+                "Number of {component_name} cache evictions",
+                &namespace,
+            ),
+            evict_num_bytes: new_counter(
+                "cache_evict_bytes",
+                "Number of {component_name} cache evictions in bytes",
+                &namespace,
+            ),
         }
     }
 }
@@ -146,6 +146,27 @@ impl SplitCache {
         }
     }
 
+    /// Evicts splits from the cache, and deletes their files if they were already downloaded.
+    ///
+    /// This should be called when splits are deleted, for instance by the garbage collector,
+    /// so that they stop taking space in the cache.
+    pub fn evict_splits(&self, split_ids: &[String]) {
+        let mut splits_to_delete = Vec::new();
+        {
+            let mut split_table = self.split_table.lock().unwrap();
+            for split_id in split_ids {
+                let Ok(split_ulid) = Ulid::from_str(split_id) else {
+                    error!(split_id=%split_id, "received invalid split ulid: ignoring");
+                    continue;
+                };
+                if split_table.evict(split_ulid) {
+                    splits_to_delete.push(split_ulid);
+                }
+            }
+        }
+        delete_evicted_splits(&self.root_path, &splits_to_delete[..]);
+    }
+
     fn cached_split_filepath(&self, split_id: Ulid) -> PathBuf {
         let split_filename = quickwit_common::split_file(split_id);
         self.root_path.join(split_filename)
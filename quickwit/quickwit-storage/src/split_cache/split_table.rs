@@ -148,6 +148,12 @@ fn compute_timestamp(start: Instant) -> LastAccessDate {
     start.elapsed().as_micros() as u64
 }
 
+fn record_eviction_metrics(num_bytes: u64) {
+    let split_metrics = &crate::metrics::STORAGE_METRICS.searcher_split_cache;
+    split_metrics.evict_num_items.inc();
+    split_metrics.evict_num_bytes.inc_by(num_bytes);
+}
+
 // TODO improve SplitGuard with Atomic
 // Right only touch is helping.
 pub(super) struct SplitGuard;
@@ -333,6 +339,23 @@ impl SplitTable {
         }
     }
 
+    /// Removes a split from the split table, regardless of its status, for instance because it was
+    /// deleted by the garbage collector.
+    ///
+    /// Returns true if the split was on disk, in which case it is up to the caller to delete the
+    /// split file.
+    pub(crate) fn evict(&mut self, split_ulid: Ulid) -> bool {
+        let Some(split_info) = self.remove(split_ulid) else {
+            return false;
+        };
+        if let Status::OnDisk { num_bytes } = split_info.status {
+            record_eviction_metrics(num_bytes);
+            true
+        } else {
+            false
+        }
+    }
+
     pub(crate) fn register_as_downloaded(&mut self, split_ulid: Ulid, num_bytes: u64) {
         self.change_split_status(split_ulid, Status::OnDisk { num_bytes });
     }
@@ -406,6 +429,11 @@ impl SplitTable {
             }
             None
         } else {
+            for split_info in &split_infos {
+                if let Status::OnDisk { num_bytes } = split_info.status {
+                    record_eviction_metrics(num_bytes);
+                }
+            }
             Some(
                 split_infos
                     .into_iter()
@@ -607,6 +635,66 @@ mod tests {
         assert_eq!(split_to_download.split_ulid, new_ulid);
     }
 
+    #[test]
+    fn test_eviction_due_to_size_evicts_least_recently_accessed_first() {
+        let mut split_table = SplitTable::with_limits_and_existing_splits(
+            SplitCacheLimits {
+                max_num_bytes: ByteSize::mb(1),
+                max_num_splits: NonZeroU32::new(30).unwrap(),
+                num_concurrent_downloads: NonZeroU32::new(1).unwrap(),
+            },
+            Default::default(),
+        );
+        let split_ulids = sorted_split_ulids(4);
+        for &split_ulid in &split_ulids {
+            split_table.report(split_ulid, Uri::for_test(TEST_STORAGE_URI));
+            split_table.register_as_downloaded(split_ulid, 300_000);
+        }
+        // Accessing the oldest split makes it the most recently accessed one.
+        assert!(split_table
+            .get_split_guard(split_ulids[0], &Uri::for_test(TEST_STORAGE_URI))
+            .is_some());
+        let new_ulid = Ulid::new();
+        split_table.report(new_ulid, Uri::for_test(TEST_STORAGE_URI));
+        let DownloadOpportunity {
+            splits_to_delete,
+            split_to_download,
+        } = split_table.find_download_opportunity().unwrap();
+        assert_eq!(&splits_to_delete[..], &[split_ulids[1]][..]);
+        assert_eq!(split_to_download.split_ulid, new_ulid);
+        assert_eq!(split_table.num_bytes(), 900_000);
+    }
+
+    #[test]
+    fn test_evict_split() {
+        let mut split_table = SplitTable::with_limits_and_existing_splits(
+            SplitCacheLimits {
+                max_num_bytes: ByteSize::mb(10),
+                max_num_splits: NonZeroU32::new(5).unwrap(),
+                num_concurrent_downloads: NonZeroU32::new(1).unwrap(),
+            },
+            Default::default(),
+        );
+        let split_ulids = sorted_split_ulids(2);
+        let on_disk_ulid = split_ulids[0];
+        let candidate_ulid = split_ulids[1];
+        split_table.report(on_disk_ulid, Uri::for_test(TEST_STORAGE_URI));
+        split_table.register_as_downloaded(on_disk_ulid, 10_000);
+        split_table.report(candidate_ulid, Uri::for_test(TEST_STORAGE_URI));
+        assert_eq!(split_table.num_bytes(), 10_000);
+
+        assert!(split_table.evict(on_disk_ulid));
+        assert_eq!(split_table.num_bytes(), 0);
+        assert!(split_table
+            .get_split_guard(on_disk_ulid, &Uri::for_test(TEST_STORAGE_URI))
+            .is_none());
+
+        assert!(!split_table.evict(candidate_ulid));
+        assert!(split_table.start_download(candidate_ulid).is_none());
+
+        assert!(!split_table.evict(Ulid::new()));
+    }
+
     #[test]
     fn test_failed_download_can_be_re_reported() {
         let mut split_table = SplitTable::with_limits_and_existing_splits(